@@ -721,6 +721,50 @@ impl<RS: BezoutDomainStructure> ElementaryOpp<RS> {
     }
 }
 
+impl<RS: IntegralDomainStructure> MatrixStructure<RS> {
+    //fraction free gaussian elimination: after step k every entry is a minor of a and so division by the previous pivot is exact
+    pub fn det_bareiss(&self, mut a: Matrix<RS::Set>) -> Result<RS::Set, MatOppErr> {
+        let n = a.rows();
+        if n != a.cols() {
+            return Err(MatOppErr::NotSquare);
+        }
+        let mut negate = false;
+        let mut prev = self.ring.one();
+        for k in 0..n {
+            if self.ring.is_zero(a.at(k, k).unwrap()) {
+                match (k + 1..n).find(|i| !self.ring.is_zero(a.at(*i, k).unwrap())) {
+                    Some(i) => {
+                        for c in k..n {
+                            let tmp = a.at(i, c).unwrap().clone();
+                            *a.at_mut(i, c).unwrap() = a.at(k, c).unwrap().clone();
+                            *a.at_mut(k, c).unwrap() = tmp;
+                        }
+                        negate = !negate;
+                    }
+                    None => return Ok(self.ring.zero()),
+                }
+            }
+            for i in k + 1..n {
+                for j in k + 1..n {
+                    let x = self.ring.add(
+                        &self.ring.mul(a.at(i, j).unwrap(), a.at(k, k).unwrap()),
+                        &self
+                            .ring
+                            .neg(&self.ring.mul(a.at(i, k).unwrap(), a.at(k, j).unwrap())),
+                    );
+                    *a.at_mut(i, j).unwrap() = self.ring.div(&x, &prev).unwrap();
+                }
+            }
+            prev = a.at(k, k).unwrap().clone();
+        }
+        if negate {
+            Ok(self.ring.neg(&prev))
+        } else {
+            Ok(prev)
+        }
+    }
+}
+
 impl<RS: BezoutDomainStructure> MatrixStructure<RS> {
     pub fn row_span(&self, a: Matrix<RS::Set>) -> LinearLattice<RS::Set> {
         LinearLatticeStructure::new(self.ring.clone()).from_span(
@@ -2112,6 +2156,15 @@ where
     }
 }
 
+impl<R: MetaType> Matrix<R>
+where
+    R::Structure: IntegralDomainStructure,
+{
+    pub fn det_bareiss(&self) -> Result<R, MatOppErr> {
+        Self::structure().det_bareiss(self.clone())
+    }
+}
+
 impl<R: MetaType> Matrix<R>
 where
     R::Structure: BezoutDomainStructure,
@@ -2844,6 +2897,30 @@ mod tests {
         assert_eq!(m.det_naive().unwrap(), Integer::from(-15));
     }
 
+    #[test]
+    fn det_bareiss() {
+        let m = Matrix::<Integer>::from_rows(vec![
+            vec![Integer::from(1), Integer::from(3), Integer::from(2)],
+            vec![Integer::from(-3), Integer::from(-1), Integer::from(-3)],
+            vec![Integer::from(2), Integer::from(3), Integer::from(1)],
+        ]);
+        assert_eq!(m.det_bareiss().unwrap(), Integer::from(-15));
+
+        //needs a row swap
+        let m = Matrix::<Integer>::from_rows(vec![
+            vec![Integer::from(0), Integer::from(2), Integer::from(1)],
+            vec![Integer::from(3), Integer::from(1), Integer::from(4)],
+            vec![Integer::from(5), Integer::from(9), Integer::from(2)],
+        ]);
+        assert_eq!(m.det_bareiss().unwrap(), m.det_naive().unwrap());
+
+        let m = Matrix::<Integer>::from_rows(vec![
+            vec![Integer::from(1), Integer::from(2)],
+            vec![Integer::from(2), Integer::from(4)],
+        ]);
+        assert_eq!(m.det_bareiss().unwrap(), Integer::from(0));
+    }
+
     #[test]
     fn hermite_algorithm() {
         for a in vec![
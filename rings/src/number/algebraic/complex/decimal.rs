@@ -0,0 +1,147 @@
+use super::super::{round_interval, scaled_integer_to_decimal_string, ten_to_the};
+use super::*;
+
+impl ComplexAlgebraicRoot {
+    /// Return rational boxes [a, b] x [c, d] containing self in their interior with b - a <= accuracy and d - c <= accuracy.
+    pub fn rational_enclosure_mut(
+        &mut self,
+        accuracy: &Rational,
+    ) -> ((Rational, Rational), (Rational, Rational)) {
+        assert!(accuracy > &Rational::ZERO);
+        self.refine_to_accuracy(accuracy);
+        (
+            (self.tight_a.clone(), self.tight_b.clone()),
+            (self.tight_c.clone(), self.tight_d.clone()),
+        )
+    }
+
+    // The nearest integers to the real and imaginary parts of self * 10^digits, rounding half way cases away from zero.
+    fn round_decimal(&self, digits: usize) -> (Integer, Integer) {
+        let scale = ten_to_the(digits);
+        // Refine the isolating box until both coordinates are determined.
+        // A coordinate which stays close to a rounding boundary may be exactly on it, so it is computed exactly instead.
+        let give_up = Rational::from_integers(Integer::ONE, Integer::from(1u32 << 20)) / &scale;
+        let mut root = self.clone();
+        loop {
+            let re = round_interval(&(&root.tight_a * &scale), &(&root.tight_b * &scale));
+            let im = round_interval(&(&root.tight_c * &scale), &(&root.tight_d * &scale));
+            if let (Some(re), Some(im)) = (&re, &im) {
+                return (re.clone(), im.clone());
+            }
+            if root.accuracy_re() < give_up && root.accuracy_im() < give_up {
+                return (
+                    re.unwrap_or_else(|| {
+                        ComplexAlgebraic::Complex(self.clone())
+                            .re()
+                            .round_decimal(digits)
+                    }),
+                    im.unwrap_or_else(|| {
+                        ComplexAlgebraic::Complex(self.clone())
+                            .im()
+                            .round_decimal(digits)
+                    }),
+                );
+            }
+            root.refine();
+        }
+    }
+}
+
+impl ComplexAlgebraic {
+    /// Return rational intervals [a, b] and [c, d] containing the real and imaginary parts of self with b - a <= accuracy and d - c <= accuracy.
+    pub fn rational_enclosure(
+        &self,
+        accuracy: &Rational,
+    ) -> ((Rational, Rational), (Rational, Rational)) {
+        self.clone().rational_enclosure_mut(accuracy)
+    }
+
+    /// The same as rational_enclosure, but keep the refined isolating intervals of self so that later calls are cheaper.
+    pub fn rational_enclosure_mut(
+        &mut self,
+        accuracy: &Rational,
+    ) -> ((Rational, Rational), (Rational, Rational)) {
+        match self {
+            ComplexAlgebraic::Real(x) => (
+                x.rational_enclosure_mut(accuracy),
+                (Rational::ZERO, Rational::ZERO),
+            ),
+            ComplexAlgebraic::Complex(z) => z.rational_enclosure_mut(accuracy),
+        }
+    }
+
+    /// The decimal expansion of self, with the real and imaginary parts each correctly rounded to `digits` digits after the decimal point.
+    /// Exact half way cases are rounded away from zero.
+    pub fn to_decimal_string(&self, digits: usize) -> String {
+        match self {
+            ComplexAlgebraic::Real(x) => x.to_decimal_string(digits),
+            ComplexAlgebraic::Complex(z) => {
+                let (re, im) = z.round_decimal(digits);
+                let re_str = scaled_integer_to_decimal_string(&re, digits);
+                let im_str = scaled_integer_to_decimal_string(&im, digits);
+                if im_str.starts_with('-') {
+                    format!("{}{}i", re_str, im_str)
+                } else {
+                    format!("{}+{}i", re_str, im_str)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complex_decimal_string() {
+        assert_eq!(ComplexAlgebraic::i().to_decimal_string(3), "0.000+1.000i");
+        assert_eq!(ComplexAlgebraic::i().neg().to_decimal_string(0), "0-1i");
+
+        // the roots of x^2 + x + 1 are (-1 +/- i sqrt(3)) / 2 whose real part is a rounding boundary
+        let roots = Polynomial::from_coeffs(vec![Integer::ONE, Integer::ONE, Integer::ONE])
+            .all_complex_roots();
+        let mut strs = roots
+            .iter()
+            .map(|z| z.to_decimal_string(0))
+            .collect::<Vec<_>>();
+        strs.sort();
+        assert_eq!(strs, vec!["-1+1i", "-1-1i"]);
+        let mut strs = roots
+            .iter()
+            .map(|z| z.to_decimal_string(6))
+            .collect::<Vec<_>>();
+        strs.sort();
+        assert_eq!(strs, vec!["-0.500000+0.866025i", "-0.500000-0.866025i"]);
+
+        // the complex roots of x^3 - 2
+        let mut strs = Polynomial::from_coeffs(vec![
+            Integer::from(-2),
+            Integer::ZERO,
+            Integer::ZERO,
+            Integer::ONE,
+        ])
+        .all_complex_roots()
+        .iter()
+        .map(|z| z.to_decimal_string(10))
+        .collect::<Vec<_>>();
+        strs.sort();
+        assert_eq!(
+            strs,
+            vec![
+                "-0.6299605249+1.0911236360i",
+                "-0.6299605249-1.0911236360i",
+                "1.2599210499"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complex_rational_enclosure() {
+        let eps = Rational::from_signeds(1, 1000000);
+        let ((a, b), (c, d)) = ComplexAlgebraic::i().rational_enclosure(&eps);
+        assert!(&b - &a <= eps && &d - &c <= eps);
+        assert!(a < Rational::ZERO && Rational::ZERO < b);
+        assert!(c < Rational::ONE && Rational::ONE < d);
+    }
+}
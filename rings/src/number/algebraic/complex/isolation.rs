@@ -0,0 +1,664 @@
+use super::super::real::isolation::root_absolute_value_bound;
+use super::*;
+use malachite_base::num::arithmetic::traits::{CeilingSqrt, Floor, FloorSqrt, Lcm, Pow, PowerOf2};
+use malachite_base::num::conversion::traits::{ConvertibleFrom, RoundingFrom};
+use malachite_base::num::logic::traits::SignificantBits;
+use malachite_base::rounding_modes::RoundingMode;
+use std::collections::VecDeque;
+
+//the largest e such that 2^e <= x for x > 0
+fn floor_log2(x: &Rational) -> i64 {
+    debug_assert!(x > &Rational::ZERO);
+    let mut e =
+        x.numerator_ref().significant_bits() as i64 - x.denominator_ref().significant_bits() as i64;
+    //now 2^(e-1) < x < 2^(e+1)
+    if &Rational::power_of_2(e) > x {
+        e -= 1;
+    }
+    e
+}
+
+//round x down to a multiple of 2^e
+fn round_dyadic(x: &Rational, e: i64) -> Rational {
+    Rational::from(Floor::floor(x / Rational::power_of_2(e))) * Rational::power_of_2(e)
+}
+
+//the Taylor expansion of a polynomial about a complex rational point z
+struct TaylorExpansion {
+    //the coefficients, as gaussian integers, of den^n p(z + y / den) = sum_j A_j y^j
+    coeffs: Vec<(Integer, Integer)>,
+    den: Natural,
+    re: Rational,
+    im: Rational,
+}
+
+impl TaylorExpansion {
+    fn new(p: &Polynomial<Integer>, re: &Rational, im: &Rational) -> Self {
+        let den = re.denominator_ref().lcm(im.denominator_ref());
+        let u = Integer::try_from(re * Rational::from(&den)).unwrap();
+        let v = Integer::try_from(im * Rational::from(&den)).unwrap();
+        let n = p.degree().unwrap();
+        //den^n p(y / den)
+        let mut re_coeffs = p
+            .coeffs()
+            .into_iter()
+            .enumerate()
+            .map(|(j, c)| c * Integer::from(den.clone().pow((n - j) as u64)))
+            .collect::<Vec<_>>();
+        let mut im_coeffs = vec![Integer::ZERO; n + 1];
+        //replace y by y + u + iv
+        for i in 0..n {
+            for j in (i..n).rev() {
+                let (x, y) = (&re_coeffs[j + 1], &im_coeffs[j + 1]);
+                let s = &u * x - &v * y;
+                let t = &u * y + &v * x;
+                re_coeffs[j] += s;
+                im_coeffs[j] += t;
+            }
+        }
+        Self {
+            coeffs: re_coeffs.into_iter().zip(im_coeffs).collect(),
+            den,
+            re: re.clone(),
+            im: im.clone(),
+        }
+    }
+
+    //The Pellet test on the disc about z of radius r.
+    //Returns Some(k) if |a_k| r^k > sum_{i != k} |a_i| r^i where a_i are the Taylor coefficients of p about z, in which case the open disc contains exactly k roots of p and its boundary contains none.
+    fn pellet(&self, r: &Rational) -> Option<usize> {
+        debug_assert!(r > &Rational::ZERO);
+        let n = self.coeffs.len() - 1;
+        //the coefficients of p(z + y / den) with respect to y are compared on the disc of radius rn / rd = r den
+        let rad = r * Rational::from(&self.den);
+        let (rn, rd) = (rad.numerator_ref(), rad.denominator_ref());
+        let mut rd_pows = vec![Natural::ONE];
+        for i in 0..n {
+            let x = &rd_pows[i] * rd;
+            rd_pows.push(x);
+        }
+        let mut rn_pow = Natural::ONE;
+        let mut lower = vec![];
+        let mut upper = vec![];
+        let mut total = Natural::ZERO;
+        for (i, (x, y)) in self.coeffs.iter().enumerate() {
+            let norm = x.unsigned_abs_ref() * x.unsigned_abs_ref()
+                + y.unsigned_abs_ref() * y.unsigned_abs_ref();
+            let scale = &rn_pow * &rd_pows[n - i];
+            let u = (&norm).ceiling_sqrt() * &scale;
+            lower.push(norm.floor_sqrt() * scale);
+            total += &u;
+            upper.push(u);
+            rn_pow *= rn;
+        }
+        (0..=n).find(|&k| &lower[k] + &upper[k] > total)
+    }
+
+    //a Newton step for a cluster of k roots: z - k p(z) / p'(z)
+    fn newton(&self, k: usize) -> Option<(Rational, Rational)> {
+        let (a0r, a0i) = &self.coeffs[0];
+        let (a1r, a1i) = &self.coeffs[1];
+        let norm = a1r * a1r + a1i * a1i;
+        if norm == Integer::ZERO {
+            return None;
+        }
+        //a_0 / a_1 = A_0 / (A_1 den)
+        let d = Rational::from(norm) * Rational::from(&self.den);
+        let k = Rational::from(k);
+        let re = &self.re - &k * Rational::from(a0r * a1r + a0i * a1i) / &d;
+        let im = &self.im - &k * Rational::from(a0i * a1r - a0r * a1i) / &d;
+        Some((re, im))
+    }
+}
+
+enum SoftPellet {
+    Holds(usize),
+    Fails,
+    Unknown,
+}
+
+//Decides Pellet tests for a polynomial. Floating point arithmetic with rigorous error bounds is tried first, and exact arithmetic is used when that is inconclusive.
+struct PelletTester<'a> {
+    poly: &'a Polynomial<Integer>,
+    //the coefficients as floats, if they are all comfortably in range
+    float_coeffs: Option<Vec<f64>>,
+}
+
+impl<'a> PelletTester<'a> {
+    fn new(poly: &'a Polynomial<Integer>) -> Self {
+        let float_coeffs = if poly
+            .coeffs()
+            .into_iter()
+            .all(|c| c.unsigned_abs_ref().significant_bits() < 900)
+        {
+            Some(
+                poly.coeffs()
+                    .into_iter()
+                    .map(|c| f64::rounding_from(c, RoundingMode::Nearest).0)
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        Self { poly, float_coeffs }
+    }
+
+    fn to_float(x: &Rational) -> Option<f64> {
+        if x == &Rational::ZERO {
+            Some(0.0)
+        } else if f64::convertible_from(x) {
+            let f = f64::rounding_from(x, RoundingMode::Exact).0;
+            (f.abs() > 1e-150 && f.abs() < 1e150).then_some(f)
+        } else {
+            None
+        }
+    }
+
+    fn soft_pellet(&self, x: &Rational, y: &Rational, r: &Rational) -> SoftPellet {
+        //r = rm 2^re with 1 <= rm < 2
+        let re = floor_log2(r);
+        let (Some(coeffs), Some(x), Some(y), Some(rm)) = (
+            &self.float_coeffs,
+            Self::to_float(x),
+            Self::to_float(y),
+            Self::to_float(&(r / Rational::power_of_2(re))),
+        ) else {
+            return SoftPellet::Unknown;
+        };
+        let n = coeffs.len() - 1;
+        //Taylor shift by x + iy, together with the Taylor shift of |p| by |x| + |y| which bounds the rounding errors
+        let mut tay_re = coeffs.clone();
+        let mut tay_im = vec![0.0; n + 1];
+        let mut abs = coeffs.iter().map(|c| c.abs()).collect::<Vec<_>>();
+        let abs_c = (x.abs() + y.abs()) * (1.0 + 4.0 * f64::EPSILON);
+        for i in 0..n {
+            for j in (i..n).rev() {
+                let (a, b) = (tay_re[j + 1], tay_im[j + 1]);
+                tay_re[j] += x * a - y * b;
+                tay_im[j] += x * b + y * a;
+                abs[j] += abs_c * abs[j + 1];
+            }
+        }
+        if !abs.iter().all(|a| a.is_finite()) {
+            return SoftPellet::Unknown;
+        }
+        //a generous bound on the relative rounding error of every chain of operations
+        let gamma = (16 * n + 32) as f64 * f64::EPSILON;
+        //the terms |a_i| r^i may be far out of the range of floats, so they are computed as m_i 2^(e_i)
+        let mut terms = vec![];
+        let mut pow_m = 1.0;
+        let mut pow_e = 0i64;
+        for i in 0..=n {
+            let t = tay_re[i].hypot(tay_im[i]) * pow_m;
+            let e = gamma * abs[i] * pow_m;
+            terms.push((t, e, abs[i] * pow_m, pow_e));
+            pow_m *= rm;
+            pow_e += re;
+            if pow_m >= 2.0 {
+                pow_m /= 2.0;
+                pow_e += 1;
+            }
+        }
+        //every term is at most 2^top
+        let top = terms
+            .iter()
+            .filter(|(_, _, bound, _)| *bound > 0.0)
+            .map(|(_, _, bound, e)| e + bound.log2().ceil() as i64 + 1)
+            .max()
+            .unwrap();
+        //multiply by 2^k for k <= 1, where any underflow is an absolute error far less than 2^-1000
+        let scale = |mut v: f64, mut k: i64| {
+            while k < -1000 {
+                v *= 2f64.powi(-1000);
+                k += 1000;
+            }
+            v * 2f64.powi(k as i32)
+        };
+        let tiny = 2f64.powi(-1000);
+        let mut lower = vec![];
+        let mut upper = vec![];
+        for (t, e, _, pow_e) in terms {
+            upper.push(scale(t * (1.0 + gamma) + e, pow_e - top) + tiny);
+            lower.push((scale(t * (1.0 - gamma) - e, pow_e - top) - tiny).max(0.0));
+        }
+        //sums of the bounds over all terms except the k-th
+        let others = |bounds: &Vec<f64>| {
+            let mut prefix = vec![0.0];
+            for b in bounds {
+                prefix.push(prefix.last().unwrap() + b);
+            }
+            let mut suffix = vec![0.0; n + 2];
+            for k in (0..=n).rev() {
+                suffix[k] = suffix[k + 1] + bounds[k];
+            }
+            (0..=n)
+                .map(|k| prefix[k] + suffix[k + 1])
+                .collect::<Vec<_>>()
+        };
+        let others_upper = others(&upper);
+        let others_lower = others(&lower);
+        if let Some(k) = (0..=n).find(|&k| lower[k] > others_upper[k] * (1.0 + gamma)) {
+            SoftPellet::Holds(k)
+        } else if (0..=n).all(|k| upper[k] * (1.0 + gamma) <= others_lower[k] * (1.0 - gamma)) {
+            SoftPellet::Fails
+        } else {
+            SoftPellet::Unknown
+        }
+    }
+
+    //the Pellet test on the disc about x + iy of radius r
+    fn pellet(&self, x: &Rational, y: &Rational, r: &Rational) -> Option<usize> {
+        match self.soft_pellet(x, y, r) {
+            SoftPellet::Holds(k) => Some(k),
+            SoftPellet::Fails => None,
+            SoftPellet::Unknown => TaylorExpansion::new(self.poly, x, y).pellet(r),
+        }
+    }
+}
+
+//the squared distance from (x, y) to the rectangle [a, b] x [c, d]
+fn distance_squared_to_rectangle(
+    x: &Rational,
+    y: &Rational,
+    (a, b, c, d): (&Rational, &Rational, &Rational, &Rational),
+) -> Rational {
+    let dx = if x < a {
+        a - x
+    } else if x > b {
+        x - b
+    } else {
+        Rational::ZERO
+    };
+    let dy = if y < c {
+        c - y
+    } else if y > d {
+        y - d
+    } else {
+        Rational::ZERO
+    };
+    &dx * &dx + &dy * &dy
+}
+
+//a connected set of squares [i 2^e, (i+1) 2^e] x [j 2^e, (j+1) 2^e] which may contain roots
+#[derive(Debug, Clone)]
+struct Component {
+    e: i64,
+    squares: Vec<(Integer, Integer)>,
+    //controls how far the Newton steps try to jump
+    newton: u64,
+}
+
+impl Component {
+    fn side(&self) -> Rational {
+        Rational::power_of_2(self.e)
+    }
+
+    fn square(&self, (i, j): &(Integer, Integer)) -> (Rational, Rational, Rational, Rational) {
+        let s = self.side();
+        (
+            Rational::from(i) * &s,
+            Rational::from(i + Integer::ONE) * &s,
+            Rational::from(j) * &s,
+            Rational::from(j + Integer::ONE) * &s,
+        )
+    }
+
+    //the bounding rectangle of the squares, enlarged by one square in each direction
+    fn enlarged_rectangle(&self) -> (Rational, Rational, Rational, Rational) {
+        let i0 = self.squares.iter().map(|(i, _)| i).min().unwrap();
+        let i1 = self.squares.iter().map(|(i, _)| i).max().unwrap();
+        let j0 = self.squares.iter().map(|(_, j)| j).min().unwrap();
+        let j1 = self.squares.iter().map(|(_, j)| j).max().unwrap();
+        let s = self.side();
+        (
+            Rational::from(i0 - Integer::ONE) * &s,
+            Rational::from(i1 + Integer::TWO) * &s,
+            Rational::from(j0 - Integer::ONE) * &s,
+            Rational::from(j1 + Integer::TWO) * &s,
+        )
+    }
+
+    //split a set of squares into its 8-connected components
+    fn connected_components(e: i64, squares: Vec<(Integer, Integer)>, newton: u64) -> Vec<Self> {
+        let mut remaining: HashSet<(Integer, Integer)> = squares.into_iter().collect();
+        let mut components = vec![];
+        while let Some(start) = remaining.iter().next().cloned() {
+            remaining.remove(&start);
+            let mut component = vec![];
+            let mut boundary = vec![start];
+            while let Some((i, j)) = boundary.pop() {
+                for di in [-1, 0, 1] {
+                    for dj in [-1, 0, 1] {
+                        let nbr = (&i + Integer::from(di), &j + Integer::from(dj));
+                        if remaining.remove(&nbr) {
+                            boundary.push(nbr);
+                        }
+                    }
+                }
+                component.push((i, j));
+            }
+            components.push(Self {
+                e,
+                squares: component,
+                newton,
+            });
+        }
+        components
+    }
+}
+
+impl Polynomial<Integer> {
+    //Isolate the roots of a squarefree polynomial in the open upper half plane, of which there must be exactly target_uhp_num.
+    //This is a subdivision algorithm in the style of Becker, Sagraloff, Sharma and Yap: squares are discarded using Pellet tests,
+    //connected components of the remaining squares are certified to contain a single root using Pellet tests,
+    //and components containing a cluster of roots are shrunk quickly using Newton steps.
+    pub(super) fn uhp_complex_roots_by_pellet(
+        &self,
+        target_uhp_num: usize,
+    ) -> Vec<ComplexAlgebraicRoot> {
+        let tester = PelletTester::new(self);
+        let bound = root_absolute_value_bound(self);
+        let e = floor_log2(&bound);
+        //the rectangle [-bound, bound] x [0, bound] contains every root in the upper half plane
+        let mut queue = VecDeque::from([Component {
+            e,
+            squares: vec![
+                (Integer::from(-1), Integer::ZERO),
+                (Integer::ZERO, Integer::ZERO),
+            ],
+            newton: 1,
+        }]);
+        let mut found: Vec<(Rational, Rational, Rational, Rational)> = vec![];
+        while found.len() < target_uhp_num {
+            let mut component = queue.pop_front().unwrap();
+
+            //a disc containing the component, which can be used to count its roots if it lies in the upper half plane and meets no other component
+            let (a, b, c, d) = component.enlarged_rectangle();
+            let x = (&a + &b) / Rational::TWO;
+            let y = (&c + &d) / Rational::TWO;
+            let r = Rational::from_signeds(3, 4) * std::cmp::max(&b - &a, &d - &c);
+            let r_sq = &r * &r;
+            let isolated = y > r
+                && queue.iter().all(|other| {
+                    other.squares.iter().all(|sq| {
+                        let (sa, sb, sc, sd) = other.square(sq);
+                        distance_squared_to_rectangle(&x, &y, (&sa, &sb, &sc, &sd)) > r_sq
+                    })
+                })
+                && found.iter().all(|(fa, fb, fc, fd)| {
+                    distance_squared_to_rectangle(&x, &y, (fa, fb, fc, fd)) > r_sq
+                });
+            if isolated {
+                match tester.pellet(&x, &y, &r) {
+                    Some(0) => {
+                        continue;
+                    }
+                    Some(1) => {
+                        found.push((a, b, c, d));
+                        continue;
+                    }
+                    Some(k) => {
+                        //try to jump to a much smaller disc around the cluster of k roots
+                        let small_r = &r * Rational::power_of_2(-(component.newton as i64));
+                        let small_e = floor_log2(&small_r) - 1;
+                        if let Some((nx, ny)) = TaylorExpansion::new(self, &x, &y).newton(k) {
+                            let nx = round_dyadic(&nx, small_e);
+                            let ny = round_dyadic(&ny, small_e);
+                            let offset = (&nx - &x) * (&nx - &x) + (&ny - &y) * (&ny - &y);
+                            let margin = &r - &small_r;
+                            if offset < &margin * &margin
+                                && tester.pellet(&nx, &ny, &small_r) == Some(k)
+                            {
+                                //all k roots of the component are in the small disc
+                                let s = Rational::power_of_2(small_e);
+                                let i0 = Floor::floor((&nx - &small_r) / &s);
+                                let i1 = Floor::floor((&nx + &small_r) / &s);
+                                let j0 = Floor::floor((&ny - &small_r) / &s);
+                                let j1 = Floor::floor((&ny + &small_r) / &s);
+                                let mut squares = vec![];
+                                let mut i = i0;
+                                while i <= i1 {
+                                    let mut j = j0.clone();
+                                    while j <= j1 {
+                                        squares.push((i.clone(), j.clone()));
+                                        j += Integer::ONE;
+                                    }
+                                    i += Integer::ONE;
+                                }
+                                queue.push_back(Component {
+                                    e: small_e,
+                                    squares,
+                                    newton: 2 * component.newton,
+                                });
+                                continue;
+                            }
+                        }
+                        component.newton = std::cmp::max(component.newton / 2, 1);
+                    }
+                    None => {}
+                }
+            }
+
+            //subdivide and discard squares which contain no roots
+            let child_e = component.e - 1;
+            let s = Rational::power_of_2(child_e);
+            let exclusion_r = Rational::from_signeds(3, 4) * &s;
+            let mut children = vec![];
+            for (i, j) in &component.squares {
+                for di in [0, 1] {
+                    for dj in [0, 1] {
+                        let ci = Integer::TWO * i + Integer::from(di);
+                        let cj = Integer::TWO * j + Integer::from(dj);
+                        let cx = (Rational::from(&ci) + Rational::ONE_HALF) * &s;
+                        let cy = (Rational::from(&cj) + Rational::ONE_HALF) * &s;
+                        if tester.pellet(&cx, &cy, &exclusion_r) != Some(0) {
+                            children.push((ci, cj));
+                        }
+                    }
+                }
+            }
+            queue.extend(Component::connected_components(
+                child_e,
+                children,
+                component.newton,
+            ));
+        }
+        found
+            .into_iter()
+            .map(|(a, b, c, d)| ComplexAlgebraicRoot {
+                poly: self.clone(),
+                tight_a: a,
+                tight_b: b,
+                tight_c: c,
+                tight_d: d,
+            })
+            .collect()
+    }
+}
+
+impl ComplexAlgebraicRoot {
+    //Try to shrink the isolating box using a Newton step, certified by a Pellet test. Return whether this succeeded.
+    //Once the box is small enough the width of the new box is roughly the square of the width of the old box.
+    pub(super) fn refine_by_newton(&mut self) -> bool {
+        let w = std::cmp::max(self.accuracy_re(), self.accuracy_im()) / Rational::TWO;
+        let t = floor_log2(&w) + 1;
+        let x = (&self.tight_a + &self.tight_b) / Rational::TWO;
+        let y = (&self.tight_c + &self.tight_d) / Rational::TWO;
+        let Some((nx, ny)) = TaylorExpansion::new(&self.poly, &x, &y).newton(1) else {
+            return false;
+        };
+        //first try a disc of radius about w^2 and then one of radius about w/4
+        for e in [std::cmp::min(2 * t, t - 2), t - 2] {
+            let r = Rational::power_of_2(e);
+            let nx = round_dyadic(&nx, e - 2);
+            let ny = round_dyadic(&ny, e - 2);
+            let (a, b) = (&nx - &r, &nx + &r);
+            let (c, d) = (&ny - &r, &ny + &r);
+            if self.tight_a < a
+                && b < self.tight_b
+                && self.tight_c < c
+                && d < self.tight_d
+                && PelletTester::new(&self.poly).pellet(&nx, &ny, &r) == Some(1)
+            {
+                //the disc is inside the old box so its unique root is self
+                self.tight_a = a;
+                self.tight_b = b;
+                self.tight_c = c;
+                self.tight_d = d;
+                return true;
+            }
+        }
+        false
+    }
+
+    //Try to shrink the isolating box by splitting it into a 4 by 4 grid and discarding the cells which Pellet tests show contain no root.
+    //Return whether this succeeded.
+    pub(super) fn refine_by_subdivision(&mut self) -> bool {
+        const N: usize = 4;
+        let tester = PelletTester::new(&self.poly);
+        let w = self.accuracy_re() / Rational::from(N);
+        let h = self.accuracy_im() / Rational::from(N);
+        //a disc about the centre of a cell containing the cell
+        let r = (&w + &h) / Rational::TWO;
+        let mut cells = vec![];
+        for i in 0..N {
+            for j in 0..N {
+                let x = &self.tight_a + Rational::from(2 * i + 1) * &w / Rational::TWO;
+                let y = &self.tight_c + Rational::from(2 * j + 1) * &h / Rational::TWO;
+                if tester.pellet(&x, &y, &r) != Some(0) {
+                    cells.push((i, j));
+                }
+            }
+        }
+        //every cell outside the bounding rectangle of the remaining cells contains no roots, so the root is strictly inside it
+        let i0 = cells.iter().map(|(i, _)| *i).min().unwrap();
+        let i1 = cells.iter().map(|(i, _)| *i).max().unwrap() + 1;
+        let j0 = cells.iter().map(|(_, j)| *j).min().unwrap();
+        let j1 = cells.iter().map(|(_, j)| *j).max().unwrap() + 1;
+        if i1 - i0 == N && j1 - j0 == N {
+            return false;
+        }
+        let a = &self.tight_a + Rational::from(i0) * &w;
+        let b = &self.tight_a + Rational::from(i1) * &w;
+        let c = &self.tight_c + Rational::from(j0) * &h;
+        let d = &self.tight_c + Rational::from(j1) * &h;
+        self.tight_a = a;
+        self.tight_b = b;
+        self.tight_c = c;
+        self.tight_d = d;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pellet() {
+        //x^2 + 1
+        let f = Polynomial::from_coeffs(vec![Integer::ONE, Integer::ZERO, Integer::ONE]);
+        let at = |x: i64, y: i64| TaylorExpansion::new(&f, &Rational::from(x), &Rational::from(y));
+        assert_eq!(at(0, 0).pellet(&Rational::from_signeds(1, 2)), Some(0));
+        assert_eq!(at(0, 0).pellet(&Rational::from(3)), Some(2));
+        assert_eq!(at(0, 1).pellet(&Rational::from_signeds(1, 2)), Some(1));
+        assert_eq!(at(0, 0).pellet(&Rational::ONE), None);
+        assert_eq!(at(0, 1).newton(1), Some((Rational::ZERO, Rational::ONE)));
+    }
+
+    #[test]
+    fn test_uhp_roots_by_pellet() {
+        //x^n - 2 for several n and a product of polynomials with close roots
+        for n in 2..10 {
+            let mut coeffs = vec![Integer::ZERO; n + 1];
+            coeffs[0] = Integer::from(-2);
+            coeffs[n] = Integer::ONE;
+            let f = Polynomial::from_coeffs(coeffs);
+            let num_real = if n % 2 == 0 { 2 } else { 1 };
+            let roots = f.uhp_complex_roots_by_pellet((n - num_real) / 2);
+            assert_eq!(roots.len(), (n - num_real) / 2);
+            for root in roots {
+                root.check_invariants().unwrap();
+            }
+        }
+
+        //the roots of (x^2 - 2x + 2)(x^2 - 2x + 2 + 1/10000) = (x^2 - 2x + 2)(10000x^2 - 20000x + 20001) are close together
+        let f = Polynomial::product(vec![
+            &Polynomial::from_coeffs(vec![Integer::from(2), Integer::from(-2), Integer::ONE]),
+            &Polynomial::from_coeffs(vec![
+                Integer::from(20001),
+                Integer::from(-20000),
+                Integer::from(10000),
+            ]),
+        ]);
+        let roots = f.uhp_complex_roots_by_pellet(2);
+        assert_eq!(roots.len(), 2);
+        for root in &roots {
+            assert_eq!(
+                f.count_complex_roots(&root.tight_a, &root.tight_b, &root.tight_c, &root.tight_d),
+                Some(1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_uhp_roots_by_pellet_high_degree() {
+        //x^60 - x - 1 is irreducible with two real roots
+        let mut coeffs = vec![Integer::ZERO; 61];
+        coeffs[0] = Integer::from(-1);
+        coeffs[1] = Integer::from(-1);
+        coeffs[60] = Integer::ONE;
+        let f = Polynomial::from_coeffs(coeffs);
+        let roots = f.uhp_complex_roots_by_pellet(29);
+        assert_eq!(roots.len(), 29);
+        for mut root in roots.clone() {
+            assert!(Rational::ZERO < root.tight_c);
+            let eps = Rational::from_signeds(1, 1024);
+            while root.accuracy_re() > eps || root.accuracy_im() > eps {
+                assert!(root.refine_by_newton() || root.refine_by_subdivision());
+            }
+            //the disc about the center of the box with radius half its perimeter contains exactly one root
+            let re = (&root.tight_a + &root.tight_b) / Rational::TWO;
+            let im = (&root.tight_c + &root.tight_d) / Rational::TWO;
+            let r = (&root.tight_b - &root.tight_a) + (&root.tight_d - &root.tight_c);
+            assert_eq!(TaylorExpansion::new(&f, &re, &im).pellet(&r), Some(1));
+        }
+        //the isolating boxes are disjoint
+        for (i, r) in roots.iter().enumerate() {
+            for s in &roots[i + 1..] {
+                assert!(
+                    r.tight_b <= s.tight_a
+                        || s.tight_b <= r.tight_a
+                        || r.tight_d <= s.tight_c
+                        || s.tight_d <= r.tight_c
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_refine_by_newton() {
+        //x^3 - 2
+        let f = Polynomial::from_coeffs(vec![
+            Integer::from(-2),
+            Integer::ZERO,
+            Integer::ZERO,
+            Integer::ONE,
+        ]);
+        let mut root = f.uhp_complex_roots_by_pellet(1).pop().unwrap();
+        let mut steps = 0;
+        while root.accuracy_re() > Rational::from_signeds(1, 1i64 << 62) {
+            if !root.refine_by_newton() {
+                root.refine();
+            }
+            steps += 1;
+        }
+        root.check_invariants().unwrap();
+        assert!(steps < 30);
+        //the root is 2^(1/3) (-1/2 + i sqrt(3)/2) = -0.6299... + 1.0911...i
+        assert!(Rational::from_signeds(-63, 100) < root.tight_a);
+        assert!(root.tight_b < Rational::from_signeds(-62, 100));
+        assert!(Rational::from_signeds(109, 100) < root.tight_c);
+        assert!(root.tight_d < Rational::from_signeds(110, 100));
+    }
+}
@@ -4,6 +4,7 @@ use crate::structure::structure::*;
 use super::poly_tools::*;
 use super::real::RealAlgebraic;
 use super::{bisection_gen::RationalSimpleBetweenGenerator, rat_to_string};
+use crate::polynomial::multiplication::karatsuba_mul;
 use crate::polynomial::polynomial::*;
 use algebraeon_sets::structure::*;
 use malachite_base::num::basic::traits::{One, OneHalf, Two, Zero};
@@ -14,6 +15,8 @@ use std::{collections::HashSet, fmt::Display, rc::Rc, str::FromStr};
 use boxes::*;
 
 mod boxes;
+mod decimal;
+mod isolation;
 pub mod polynomial;
 
 fn bisect_box(
@@ -370,6 +373,12 @@ impl ComplexAlgebraicRoot {
     }
 
     pub fn refine(&mut self) {
+        if self.refine_by_newton() || self.refine_by_subdivision() {
+            #[cfg(debug_assertions)]
+            self.check_invariants().unwrap();
+            return;
+        }
+
         let ((n1, a1, b1, c1, d1), (n2, a2, b2, c2, d2)) = bisect_box(
             &self.poly,
             1,
@@ -805,6 +814,10 @@ impl SemiRingStructure for CannonicalStructure<ComplexAlgebraic> {
             }
         }
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        karatsuba_mul(self, a, b)
+    }
 }
 
 impl RingStructure for CannonicalStructure<ComplexAlgebraic> {
@@ -861,7 +874,7 @@ impl IntegralDomainStructure for CannonicalStructure<ComplexAlgebraic> {
                     // );
 
                     //refine until eps < |a|
-                    if &eps * &eps > w_mag_sq {
+                    if &eps * &eps >= w_mag_sq {
                         root.refine();
                         continue;
                     }
@@ -963,6 +976,51 @@ impl ComplexAlgebraic {
             ComplexAlgebraic::Complex(complex_root) => complex_root.min_poly(),
         }
     }
+
+    fn into_real(self) -> RealAlgebraic {
+        match self {
+            ComplexAlgebraic::Real(x) => x,
+            ComplexAlgebraic::Complex(_) => panic!("expected a real number"),
+        }
+    }
+
+    /// The real part (z + conj(z)) / 2
+    pub fn re(&self) -> RealAlgebraic {
+        match self {
+            ComplexAlgebraic::Real(x) => x.clone(),
+            ComplexAlgebraic::Complex(_) => Self::mul(
+                &Self::add(self, &self.conjugate()),
+                &Self::Real(RealAlgebraic::Rational(Rational::ONE_HALF)),
+            )
+            .into_real(),
+        }
+    }
+
+    /// The imaginary part (z - conj(z)) / 2i
+    pub fn im(&self) -> RealAlgebraic {
+        match self {
+            ComplexAlgebraic::Real(_) => RealAlgebraic::zero(),
+            ComplexAlgebraic::Complex(_) => Self::mul(
+                &Self::add(self, &self.conjugate().neg()),
+                &Self::mul(
+                    &Self::i(),
+                    &Self::Real(RealAlgebraic::Rational(-Rational::ONE_HALF)),
+                ),
+            )
+            .into_real(),
+        }
+    }
+
+    /// The modulus sqrt(z conj(z))
+    pub fn abs(&self) -> RealAlgebraic {
+        match self {
+            ComplexAlgebraic::Real(x) => RealAlgebraic::abs(x),
+            ComplexAlgebraic::Complex(_) => Self::mul(self, &self.conjugate())
+                .into_real()
+                .nth_root(2)
+                .unwrap(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1117,4 +1175,73 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_complex_inv_boundary_box() {
+        //i in a box whose radius equals the distance from its center to 0
+        let i = ComplexAlgebraic::Complex(ComplexAlgebraicRoot {
+            tight_a: Rational::from_signeds(-1, 2),
+            tight_b: Rational::from_signeds(1, 2),
+            tight_c: Rational::from_signeds(1, 2),
+            tight_d: Rational::from_signeds(3, 2),
+            poly: Polynomial::from_coeffs(vec![Integer::ONE, Integer::ZERO, Integer::ONE]),
+        });
+        assert_eq!(i.inv().unwrap(), ComplexAlgebraic::i().neg());
+    }
+
+    #[test]
+    fn test_complex_parts() {
+        let x = &Polynomial::<Integer>::var().into_ergonomic();
+        let rat = |n: i64, d: i64| RealAlgebraic::Rational(Rational::from_signeds(n, d));
+
+        // 2 + 3i and 2 - 3i
+        let f = (x.pow(2) - 4 * x + 13).into_verbose();
+        for z in f.all_complex_roots() {
+            assert_eq!(z.re(), rat(2, 1));
+            assert_eq!(RealAlgebraic::abs(&z.im()), rat(3, 1));
+            assert_eq!(RealAlgebraic::mul(&z.abs(), &z.abs()), rat(13, 1));
+            let w = z.conjugate();
+            assert_eq!(w.re(), z.re());
+            assert_eq!(w.im(), RealAlgebraic::neg(&z.im()));
+            assert_eq!(
+                ComplexAlgebraic::add(
+                    &ComplexAlgebraic::Real(z.re()),
+                    &ComplexAlgebraic::mul(&ComplexAlgebraic::i(), &ComplexAlgebraic::Real(z.im()))
+                ),
+                z
+            );
+        }
+
+        // |z| = 1 for the non-real roots of x^3 - 1
+        let f = (x.pow(3) - 1).into_verbose();
+        for z in f.all_complex_roots() {
+            assert_eq!(z.abs(), rat(1, 1));
+        }
+
+        // real parts and moduli of the complex roots of x^3 - x - 1
+        let f = (x.pow(3) - x - 1).into_verbose();
+        let roots = f.all_complex_roots();
+        let real_root = roots
+            .iter()
+            .find_map(|z| match z {
+                ComplexAlgebraic::Real(r) => Some(r.clone()),
+                ComplexAlgebraic::Complex(_) => None,
+            })
+            .unwrap();
+        for z in &roots {
+            if let ComplexAlgebraic::Complex(_) = z {
+                // the roots sum to 0 and multiply to 1
+                assert_eq!(RealAlgebraic::mul(&z.re(), &rat(-2, 1)), real_root);
+                assert_eq!(
+                    RealAlgebraic::mul(&RealAlgebraic::mul(&z.abs(), &z.abs()), &real_root),
+                    rat(1, 1)
+                );
+            }
+        }
+
+        assert_eq!(ComplexAlgebraic::i().im(), rat(1, 1));
+        assert_eq!(ComplexAlgebraic::i().re(), rat(0, 1));
+        assert_eq!(ComplexAlgebraic::Real(rat(-5, 2)).abs(), rat(5, 2));
+        assert_eq!(ComplexAlgebraic::Real(rat(-5, 2)).im(), rat(0, 1));
+    }
 }
@@ -658,61 +658,7 @@ impl Polynomial<Integer> {
             debug_assert_eq!((deg - num_real_roots) % 2, 0);
             let target_uhp_num = (deg - num_real_roots) / 2;
 
-            let mut a = Rational::from(-1);
-            let mut b = Rational::from(1);
-            let mut c = Rational::from_signeds(1, 2);
-            let mut d = Rational::from(2);
-
-            loop {
-                match self.count_complex_roots(&a, &b, &c, &d) {
-                    Some(n) => {
-                        debug_assert!(n <= target_uhp_num);
-                        if n == target_uhp_num {
-                            break;
-                        }
-                    }
-                    None => {
-                        //boundary root
-                    }
-                }
-                a *= Rational::from(2);
-                b *= Rational::from(2);
-                c *= Rational::from_signeds(1, 2);
-                d *= Rational::from(2);
-            }
-
-            fn bisect(
-                poly: &Polynomial<Integer>,
-                n: usize,
-                a: &Rational,
-                b: &Rational,
-                c: &Rational,
-                d: &Rational,
-            ) -> Vec<ComplexAlgebraicRoot> {
-                debug_assert!(a < b);
-                debug_assert!(c < d);
-                debug_assert_eq!(poly.count_complex_roots(&a, &b, &c, &d).unwrap(), n);
-                if n == 0 {
-                    vec![]
-                } else if n == 1 {
-                    vec![ComplexAlgebraicRoot {
-                        poly: poly.clone(),
-                        tight_a: a.clone(),
-                        tight_b: b.clone(),
-                        tight_c: c.clone(),
-                        tight_d: d.clone(),
-                    }]
-                } else {
-                    let ((n1, a1, b1, c1, d1), (n2, a2, b2, c2, d2)) =
-                        bisect_box(poly, n, a, b, c, d);
-
-                    let mut roots = bisect(poly, n1, &a1, &b1, &c1, &d1);
-                    roots.append(&mut bisect(poly, n2, &a2, &b2, &c2, &d2));
-                    return roots;
-                }
-            }
-
-            bisect(self, target_uhp_num, &a, &b, &c, &d)
+            self.uhp_complex_roots_by_pellet(target_uhp_num)
         }
     }
 
@@ -0,0 +1,358 @@
+use super::complex::ComplexAlgebraic;
+use super::real::RealAlgebraic;
+use crate::number::natural::factor::factor;
+use crate::polynomial::polynomial::*;
+use crate::structure::structure::*;
+use malachite_base::num::arithmetic::traits::Floor;
+use malachite_base::num::basic::traits::{One, Two, Zero};
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+
+/// The nth cyclotomic polynomial, the minimal polynomial of the primitive nth roots of unity.
+///
+/// Computed as the product of (x^d - 1)^μ(n/d) over the divisors d of n.
+pub fn cyclotomic_polynomial(n: usize) -> Polynomial<Integer> {
+    assert!(n >= 1);
+    let x_pow_minus_one = |d: usize| {
+        Polynomial::add(
+            &Polynomial::var_pow(d),
+            &Polynomial::neg(&Polynomial::one()),
+        )
+    };
+    let mut num = Polynomial::<Integer>::one();
+    let mut den = Polynomial::<Integer>::one();
+    for d in (1..=n).filter(|d| n.is_multiple_of(*d)) {
+        match factor(Natural::from(n / d)).unwrap().mobius() {
+            1 => num = Polynomial::mul(&num, &x_pow_minus_one(d)),
+            -1 => den = Polynomial::mul(&den, &x_pow_minus_one(d)),
+            _ => {}
+        }
+    }
+    Polynomial::div(&num, &den).unwrap()
+}
+
+/// The minimal polynomial of cos(2π/n) as a primitive integer polynomial with positive leading coefficient.
+///
+/// For n >= 3 the cyclotomic polynomial Φ_n is palindromic of degree 2m, so x^(-m) Φ_n(x) is a polynomial g(y) of degree m in y = x + 1/x.
+/// It is obtained from the relation x^j + x^(-j) = 2T_j(y/2) with the Chebyshev polynomials T_j, and then cos(2π/n) is a root of g(2x).
+pub fn cos_two_pi_over_n_min_poly(n: usize) -> Polynomial<Integer> {
+    assert!(n >= 1);
+    match n {
+        1 => Polynomial::from_coeffs(vec![Integer::from(-1), Integer::ONE]),
+        2 => Polynomial::from_coeffs(vec![Integer::ONE, Integer::ONE]),
+        _ => {
+            let phi = cyclotomic_polynomial(n);
+            let m = phi.degree().unwrap() / 2;
+            // 2x, 2T_j(x) for the current and previous j
+            let two_x = Polynomial::from_coeffs(vec![Integer::ZERO, Integer::TWO]);
+            let mut prev = Polynomial::constant(Integer::TWO);
+            let mut curr = two_x.clone();
+            let mut g = Polynomial::constant(phi.coeff(m));
+            for j in 1..=m {
+                g = Polynomial::add(
+                    &g,
+                    &Polynomial::mul(&curr, &Polynomial::constant(phi.coeff(m + j))),
+                );
+                let next =
+                    Polynomial::add(&Polynomial::mul(&two_x, &curr), &Polynomial::neg(&prev));
+                prev = curr;
+                curr = next;
+            }
+            g.primitive_part().unwrap()
+        }
+    }
+}
+
+// Write q mod 1 as k/n with 0 <= k < n and gcd(k, n) = 1
+fn reduce_fraction_mod_one(q: &Rational) -> (usize, usize) {
+    let q = q - Rational::from(Floor::floor(q.clone()));
+    let k = usize::try_from(&Rational::numerator(&q)).unwrap();
+    let n = usize::try_from(&Rational::denominator(&q)).unwrap();
+    (k, n)
+}
+
+fn gcd_usize(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd_usize(b, a % b)
+    }
+}
+
+impl RealAlgebraic {
+    /// The exact value of cos(2πq)
+    pub fn cos_two_pi(q: &Rational) -> Self {
+        let (k, n) = reduce_fraction_mod_one(q);
+        // cos(2πj/n) is decreasing for 0 <= j <= n/2, and its conjugates are cos(2πi/n) for the i in that range coprime to n
+        let j = std::cmp::min(k, n - k);
+        let conjugates = (0..=n / 2)
+            .filter(|i| gcd_usize(*i, n) == 1)
+            .collect::<Vec<_>>();
+        let rank = conjugates.iter().position(|i| *i == j).unwrap();
+        let roots = cos_two_pi_over_n_min_poly(n).real_roots_irreducible(None, None, false, false);
+        debug_assert_eq!(roots.len(), conjugates.len());
+        roots.into_iter().rev().nth(rank).unwrap()
+    }
+
+    /// The exact value of sin(2πq)
+    pub fn sin_two_pi(q: &Rational) -> Self {
+        Self::cos_two_pi(&(Rational::from_signeds(1, 4) - q))
+    }
+}
+
+impl ComplexAlgebraic {
+    /// The root of unity e^(2πiq)
+    pub fn root_of_unity(q: &Rational) -> Self {
+        let (k, n) = reduce_fraction_mod_one(q);
+        if n <= 2 {
+            return ComplexAlgebraic::Real(RealAlgebraic::cos_two_pi(q));
+        }
+        // the roots of the nth cyclotomic polynomial are determined by their real part and the sign of their imaginary part
+        let mut re = RealAlgebraic::cos_two_pi(q);
+        let upper_half_plane = 2 * k < n;
+        let mut candidates = cyclotomic_polynomial(n)
+            .all_complex_roots_irreducible()
+            .into_iter()
+            .map(|z| match z {
+                ComplexAlgebraic::Real(_) => unreachable!(),
+                ComplexAlgebraic::Complex(z) => z,
+            })
+            .collect::<Vec<_>>();
+        let mut accuracy = Rational::ONE;
+        loop {
+            let (re_a, re_b) = match &mut re {
+                RealAlgebraic::Rational(x) => (x.clone(), x.clone()),
+                RealAlgebraic::Real(x) => x.rational_enclosure_mut(&accuracy),
+            };
+            candidates.retain_mut(|z| {
+                let ((a, b), (c, d)) = z.rational_enclosure_mut(&accuracy);
+                a <= re_b
+                    && re_a <= b
+                    && match upper_half_plane {
+                        true => d > Rational::ZERO,
+                        false => c < Rational::ZERO,
+                    }
+            });
+            debug_assert!(!candidates.is_empty());
+            if candidates.len() == 1 {
+                return ComplexAlgebraic::Complex(candidates.into_iter().next().unwrap());
+            }
+            accuracy /= Rational::TWO;
+        }
+    }
+}
+
+impl ComplexAlgebraic {
+    /// If the argument of self is qπ for a rational number q with -1 < q <= 1 then return q.
+    /// Return None if self is zero or its argument is not a rational multiple of π.
+    ///
+    /// The argument is a rational multiple of π exactly when self / |self| is a root of unity, which is detected by comparing its minimal polynomial with the cyclotomic polynomials of the same degree.
+    pub fn argument_over_pi(&self) -> Option<Rational> {
+        match self {
+            ComplexAlgebraic::Real(x) => match x.cmp(&RealAlgebraic::zero()) {
+                std::cmp::Ordering::Less => Some(Rational::ONE),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(Rational::ZERO),
+            },
+            ComplexAlgebraic::Complex(_) => {
+                let w = ComplexAlgebraic::div(self, &ComplexAlgebraic::Real(self.abs())).unwrap();
+                let min_poly = w.min_poly();
+                let deg = min_poly.degree().unwrap();
+                // phi(n) >= sqrt(n/2) so phi(n) = deg implies n <= 2 deg^2
+                let n = (1..=2 * deg * deg).find(|n| {
+                    factor(Natural::from(*n)).map_or(Natural::ONE, |f| f.euler_totient()) == deg
+                        && cyclotomic_polynomial(*n).apply_map(|c| Rational::from(c)) == min_poly
+                })?;
+                // w = e^(2πik/n) for some k coprime to n, and an approximation of the argument narrows down which
+                let ((a, b), (c, d)) =
+                    w.rational_enclosure(&Rational::from_unsigneds(1u32, 64 * n as u32));
+                let approx_re = ((a + b) / Rational::TWO).as_f64();
+                let approx_im = ((c + d) / Rational::TWO).as_f64();
+                let approx_k = approx_im.atan2(approx_re) * n as f64 / (2.0 * std::f64::consts::PI);
+                let mut ks = (0..n).filter(|k| gcd_usize(*k, n) == 1).collect::<Vec<_>>();
+                let dist = |k: usize| {
+                    let t = (k as f64 - approx_k).rem_euclid(n as f64);
+                    t.min(n as f64 - t)
+                };
+                ks.sort_by(|k1, k2| dist(*k1).partial_cmp(&dist(*k2)).unwrap());
+                for k in ks {
+                    let q = Rational::from_unsigneds(k, n);
+                    if ComplexAlgebraic::root_of_unity(&q) == w {
+                        let q = Rational::TWO * q;
+                        return Some(if q > Rational::ONE {
+                            q - Rational::TWO
+                        } else {
+                            q
+                        });
+                    }
+                }
+                unreachable!()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cyclotomic_polynomial() {
+        let ints =
+            |xs: Vec<i64>| Polynomial::from_coeffs(xs.into_iter().map(Integer::from).collect());
+        assert_eq!(cyclotomic_polynomial(1), ints(vec![-1, 1]));
+        assert_eq!(cyclotomic_polynomial(2), ints(vec![1, 1]));
+        assert_eq!(cyclotomic_polynomial(6), ints(vec![1, -1, 1]));
+        assert_eq!(cyclotomic_polynomial(12), ints(vec![1, 0, -1, 0, 1]));
+        // the first cyclotomic polynomial with a coefficient other than 0 and ±1
+        assert_eq!(cyclotomic_polynomial(105).coeff(7), Integer::from(-2));
+        for n in 1..13 {
+            assert!(cyclotomic_polynomial(n).is_irreducible());
+        }
+        for n in 1..40 {
+            let f = cyclotomic_polynomial(n);
+            assert_eq!(
+                Natural::from(f.degree().unwrap()),
+                factor(Natural::from(n)).map_or(Natural::ONE, |f| f.euler_totient())
+            );
+        }
+    }
+
+    #[test]
+    fn test_cos_min_poly() {
+        let ints =
+            |xs: Vec<i64>| Polynomial::from_coeffs(xs.into_iter().map(Integer::from).collect());
+        assert_eq!(cos_two_pi_over_n_min_poly(3), ints(vec![1, 2]));
+        assert_eq!(cos_two_pi_over_n_min_poly(4), ints(vec![0, 1]));
+        assert_eq!(cos_two_pi_over_n_min_poly(5), ints(vec![-1, 2, 4]));
+        assert_eq!(cos_two_pi_over_n_min_poly(7), ints(vec![-1, -4, 4, 8]));
+        assert_eq!(cos_two_pi_over_n_min_poly(8), ints(vec![-1, 0, 2]));
+        for n in 3..20 {
+            assert!(cos_two_pi_over_n_min_poly(n).is_irreducible());
+        }
+    }
+
+    #[test]
+    fn test_cos_sin_two_pi() {
+        let q = |n: i64, d: i64| Rational::from_signeds(n, d);
+        let rat = |n: i64, d: i64| RealAlgebraic::Rational(Rational::from_signeds(n, d));
+        assert_eq!(RealAlgebraic::cos_two_pi(&q(0, 1)), rat(1, 1));
+        assert_eq!(RealAlgebraic::cos_two_pi(&q(1, 2)), rat(-1, 1));
+        assert_eq!(RealAlgebraic::cos_two_pi(&q(1, 3)), rat(-1, 2));
+        assert_eq!(RealAlgebraic::cos_two_pi(&q(-1, 6)), rat(1, 2));
+        assert_eq!(RealAlgebraic::sin_two_pi(&q(1, 4)), rat(1, 1));
+        assert_eq!(RealAlgebraic::sin_two_pi(&q(7, 12)), rat(-1, 2));
+        assert_eq!(RealAlgebraic::sin_two_pi(&q(5, 2)), rat(0, 1));
+
+        // cos(π/4) = sin(π/4) = 1/√2
+        let x = RealAlgebraic::cos_two_pi(&q(1, 8));
+        assert_eq!(x, RealAlgebraic::sin_two_pi(&q(1, 8)));
+        assert_eq!(RealAlgebraic::mul(&x, &x), rat(1, 2));
+        assert!(x > rat(0, 1));
+        assert_eq!(RealAlgebraic::cos_two_pi(&q(3, 8)), RealAlgebraic::neg(&x));
+
+        // cos(2π/5) = (√5 - 1)/4
+        let x = RealAlgebraic::cos_two_pi(&q(1, 5));
+        assert_eq!(x.to_decimal_string(10), "0.3090169944");
+        assert_eq!(
+            RealAlgebraic::cos_two_pi(&q(2, 5)).to_decimal_string(10),
+            "-0.8090169944"
+        );
+        assert_eq!(RealAlgebraic::cos_two_pi(&q(-9, 5)), x);
+
+        for (k, n, cos, sin) in [
+            (1, 7, "0.6234898019", "0.7818314825"),
+            (3, 7, "-0.9009688679", "0.4338837391"),
+            (5, 7, "-0.2225209340", "-0.9749279122"),
+            (1, 9, "0.7660444431", "0.6427876097"),
+            (7, 12, "-0.8660254038", "-0.5000000000"),
+            (4, 15, "-0.1045284633", "0.9945218954"),
+        ] {
+            assert_eq!(
+                RealAlgebraic::cos_two_pi(&q(k, n)).to_decimal_string(10),
+                cos
+            );
+            assert_eq!(
+                RealAlgebraic::sin_two_pi(&q(k, n)).to_decimal_string(10),
+                sin
+            );
+        }
+    }
+
+    #[test]
+    fn test_root_of_unity() {
+        let q = |n: i64, d: i64| Rational::from_signeds(n, d);
+        assert_eq!(
+            ComplexAlgebraic::root_of_unity(&q(0, 1)),
+            ComplexAlgebraic::one()
+        );
+        assert_eq!(
+            ComplexAlgebraic::root_of_unity(&q(1, 2)),
+            ComplexAlgebraic::one().neg()
+        );
+        assert_eq!(
+            ComplexAlgebraic::root_of_unity(&q(1, 4)),
+            ComplexAlgebraic::i()
+        );
+        assert_eq!(
+            ComplexAlgebraic::root_of_unity(&q(3, 4)),
+            ComplexAlgebraic::i().neg()
+        );
+        for (k, n, s) in [
+            (1, 3, "-0.50000+0.86603i"),
+            (2, 3, "-0.50000-0.86603i"),
+            (1, 5, "0.30902+0.95106i"),
+            (-1, 5, "0.30902-0.95106i"),
+            (3, 7, "-0.90097+0.43388i"),
+            (5, 12, "-0.86603+0.50000i"),
+        ] {
+            assert_eq!(
+                ComplexAlgebraic::root_of_unity(&q(k, n)).to_decimal_string(5),
+                s
+            );
+        }
+        // the vertices of a regular pentagon multiply to 1 and sum to 0
+        let zeta = ComplexAlgebraic::root_of_unity(&q(1, 5));
+        assert_eq!(
+            ComplexAlgebraic::nat_pow(&zeta, &Natural::from(5u32)),
+            ComplexAlgebraic::one()
+        );
+        assert_eq!(
+            ComplexAlgebraic::mul(&zeta, &zeta),
+            ComplexAlgebraic::root_of_unity(&q(2, 5))
+        );
+    }
+
+    #[test]
+    fn test_argument_over_pi() {
+        let q = |n: i64, d: i64| Rational::from_signeds(n, d);
+        let rat = |n: i64, d: i64| ComplexAlgebraic::Real(RealAlgebraic::Rational(q(n, d)));
+        assert_eq!(rat(0, 1).argument_over_pi(), None);
+        assert_eq!(rat(3, 2).argument_over_pi(), Some(q(0, 1)));
+        assert_eq!(rat(-3, 2).argument_over_pi(), Some(q(1, 1)));
+        assert_eq!(ComplexAlgebraic::i().argument_over_pi(), Some(q(1, 2)));
+        assert_eq!(
+            ComplexAlgebraic::i().neg().argument_over_pi(),
+            Some(q(-1, 2))
+        );
+        // 1 + i and -3 - 3i
+        let one_plus_i = ComplexAlgebraic::add(&ComplexAlgebraic::one(), &ComplexAlgebraic::i());
+        assert_eq!(one_plus_i.argument_over_pi(), Some(q(1, 4)));
+        assert_eq!(
+            ComplexAlgebraic::mul(&one_plus_i, &rat(-3, 1)).argument_over_pi(),
+            Some(q(-3, 4))
+        );
+        // 2 e^(2πi 2/5)
+        assert_eq!(
+            ComplexAlgebraic::mul(&ComplexAlgebraic::root_of_unity(&q(2, 5)), &rat(2, 1))
+                .argument_over_pi(),
+            Some(q(4, 5))
+        );
+        // 1 + 2i has argument arctan(2) which is not a rational multiple of π
+        let z = ComplexAlgebraic::add(
+            &ComplexAlgebraic::one(),
+            &ComplexAlgebraic::mul(&ComplexAlgebraic::i(), &rat(2, 1)),
+        );
+        assert_eq!(z.argument_over_pi(), None);
+    }
+}
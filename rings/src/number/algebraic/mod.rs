@@ -1,9 +1,12 @@
+use malachite_nz::integer::Integer;
 use malachite_q::Rational;
 
 pub mod bisection_gen;
 pub mod complex;
+pub mod cyclotomic;
 pub mod padic;
 pub mod poly_tools;
+pub mod polynomial_systems;
 pub mod real;
 
 fn rat_to_string(a: Rational) -> String {
@@ -22,3 +25,44 @@ fn rat_to_string(a: Rational) -> String {
 
     b.to_string()
 }
+
+// the nearest integer to x, rounding half way cases away from zero
+fn round_half_away(x: &Rational) -> Integer {
+    use malachite_base::num::arithmetic::traits::{Ceiling, Floor};
+    if x >= &Rational::from(0) {
+        Floor::floor(x + Rational::from_signeds(1, 2))
+    } else {
+        Ceiling::ceiling(x - Rational::from_signeds(1, 2))
+    }
+}
+
+// the nearest integer to every x in the open interval (lo, hi), if there is one
+fn round_interval(lo: &Rational, hi: &Rational) -> Option<Integer> {
+    let n = round_half_away(&((lo + hi) / Rational::from(2)));
+    let half = Rational::from_signeds(1, 2);
+    if lo >= &(Rational::from(&n) - &half) && hi <= &(Rational::from(&n) + &half) {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+// write n / 10^digits in decimal notation with exactly `digits` digits after the decimal point
+fn scaled_integer_to_decimal_string(n: &Integer, digits: usize) -> String {
+    let mut s = n.unsigned_abs_ref().to_string();
+    if s.len() <= digits {
+        s = "0".repeat(digits + 1 - s.len()) + &s;
+    }
+    if digits > 0 {
+        s.insert(s.len() - digits, '.');
+    }
+    if n < &Integer::from(0) {
+        s.insert(0, '-');
+    }
+    s
+}
+
+fn ten_to_the(digits: usize) -> Rational {
+    use malachite_base::num::arithmetic::traits::Pow;
+    Rational::from(Integer::from(10).pow(digits as u64))
+}
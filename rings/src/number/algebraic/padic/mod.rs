@@ -432,6 +432,7 @@ pub mod structure {
     };
 
     use super::*;
+    use crate::polynomial::multiplication::karatsuba_mul;
     use algebraeon_sets::structure::*;
 
     impl PAdicRational {
@@ -780,6 +781,10 @@ pub mod structure {
                 }
             }
         }
+
+        fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+            karatsuba_mul(self, a, b)
+        }
     }
 
     impl RingStructure for PAdicAlgebraicStructure {
@@ -0,0 +1,281 @@
+use super::{complex::ComplexAlgebraic, real::RealAlgebraic};
+use crate::polynomial::{groebner::*, multipoly::*, polynomial::*};
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+use malachite_q::Rational;
+use std::collections::HashMap;
+
+fn rational_to_complex(x: &Rational) -> ComplexAlgebraic {
+    ComplexAlgebraic::Real(RealAlgebraic::Rational(x.clone()))
+}
+
+//the distinct roots of a nonzero polynomial over the algebraic numbers, only the real ones if real_only
+fn distinct_roots(f: &Polynomial<ComplexAlgebraic>, real_only: bool) -> Vec<ComplexAlgebraic> {
+    let roots = if f
+        .coeffs()
+        .into_iter()
+        .all(|c| matches!(c, ComplexAlgebraic::Real(RealAlgebraic::Rational(_))))
+    {
+        let f = f
+            .apply_map(|c| match c {
+                ComplexAlgebraic::Real(RealAlgebraic::Rational(c)) => c.clone(),
+                _ => unreachable!(),
+            })
+            .primitive_part_fof();
+        if real_only {
+            f.all_real_roots()
+                .into_iter()
+                .map(ComplexAlgebraic::Real)
+                .collect()
+        } else {
+            f.all_complex_roots()
+        }
+    } else if real_only {
+        Polynomial::<ComplexAlgebraic>::structure()
+            .all_real_roots(f)
+            .into_iter()
+            .map(ComplexAlgebraic::Real)
+            .collect()
+    } else {
+        Polynomial::<ComplexAlgebraic>::structure().all_complex_roots(f)
+    };
+    let mut distinct: Vec<ComplexAlgebraic> = vec![];
+    for root in roots {
+        if !distinct.contains(&root) {
+            distinct.push(root);
+        }
+    }
+    distinct
+}
+
+impl MultiPolynomialStructure<CannonicalStructure<Rational>> {
+    fn solutions_impl(
+        &self,
+        polys: &[MultiPolynomial<Rational>],
+        vars: &[Variable],
+        real_only: bool,
+    ) -> Result<Vec<Vec<ComplexAlgebraic>>, &'static str> {
+        for p in polys {
+            if p.free_vars().iter().any(|v| !vars.contains(v)) {
+                return Err("a polynomial involves a variable not in vars");
+            }
+        }
+        let n = vars.len();
+        //lexicographic with vars[0] largest, so the basis is triangular with the last variable eliminated last
+        let basis = self.groebner_basis(polys, vars, MonomialOrdering::Lexicographic);
+        if basis.iter().any(|g| self.as_constant(g).is_some()) {
+            return Ok(vec![]);
+        }
+
+        //levels[k] are the elements of the basis whose largest variable is vars[k], by increasing degree in vars[k]
+        let mut levels = vec![vec![]; n];
+        for g in basis {
+            let k = (0..n)
+                .find(|k| self.expand(&g, &vars[*k]).degree().unwrap() > 0)
+                .unwrap();
+            levels[k].push(self.expand(&g, &vars[k]));
+        }
+        for level in levels.iter_mut() {
+            level.sort_by_key(|g| g.degree().unwrap());
+            //the ideal is zero dimensional if and only if each variable has a pure power as a leading monomial
+            if !level
+                .iter()
+                .any(|g| g.leading_coeff().unwrap().as_constant().is_some())
+            {
+                return Err("the system has infinitely many solutions");
+            }
+        }
+
+        //extend partial solutions for vars[k+1], ..., vars[n-1] to vars[k], ..., vars[n-1]
+        //by the theorem of Gianni and Kalkbrener the gcd of the specialized ideal is the specialization of the
+        //least degree element of levels[k] whose leading coefficient does not vanish at the partial solution
+        let complex_poly_ring = MultiPolynomial::<ComplexAlgebraic>::structure();
+        let mut partial_solutions: Vec<Vec<ComplexAlgebraic>> = vec![vec![]];
+        for k in (0..n).rev() {
+            let mut extended = vec![];
+            for point in partial_solutions {
+                let values = vars[k + 1..]
+                    .iter()
+                    .cloned()
+                    .zip(point.iter())
+                    .collect::<HashMap<_, _>>();
+                let specialize = |c: &MultiPolynomial<Rational>| {
+                    complex_poly_ring.evaluate(&c.apply_map(rational_to_complex), &values)
+                };
+                let g = levels[k]
+                    .iter()
+                    .find(|g| {
+                        !ComplexAlgebraic::structure()
+                            .is_zero(&specialize(&g.leading_coeff().unwrap()))
+                    })
+                    .unwrap()
+                    .apply_map(specialize);
+                for root in distinct_roots(&g, real_only) {
+                    let mut solution = vec![root];
+                    solution.extend(point.iter().cloned());
+                    extended.push(solution);
+                }
+            }
+            partial_solutions = extended;
+        }
+        Ok(partial_solutions)
+    }
+
+    /// All complex solutions of a system of polynomial equations, as the values of vars. Err if there are infinitely many.
+    pub fn all_complex_solutions(
+        &self,
+        polys: &[MultiPolynomial<Rational>],
+        vars: &[Variable],
+    ) -> Result<Vec<Vec<ComplexAlgebraic>>, &'static str> {
+        self.solutions_impl(polys, vars, false)
+    }
+
+    /// All real solutions of a system of polynomial equations with finitely many complex solutions, as the values of vars.
+    pub fn all_real_solutions(
+        &self,
+        polys: &[MultiPolynomial<Rational>],
+        vars: &[Variable],
+    ) -> Result<Vec<Vec<RealAlgebraic>>, &'static str> {
+        Ok(self
+            .solutions_impl(polys, vars, true)?
+            .into_iter()
+            .map(|solution| {
+                solution
+                    .into_iter()
+                    .map(|x| match x {
+                        ComplexAlgebraic::Real(x) => x,
+                        ComplexAlgebraic::Complex(_) => unreachable!(),
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_solutions(
+        polys: &[MultiPolynomial<Rational>],
+        vars: &[Variable],
+        solutions: &[Vec<ComplexAlgebraic>],
+    ) {
+        let ring = MultiPolynomial::<ComplexAlgebraic>::structure();
+        for (i, a) in solutions.iter().enumerate() {
+            assert_eq!(a.len(), vars.len());
+            let values = vars
+                .iter()
+                .cloned()
+                .zip(a.iter())
+                .collect::<HashMap<_, _>>();
+            for p in polys {
+                assert_eq!(
+                    ring.evaluate(&p.apply_map(rational_to_complex), &values),
+                    rational_to_complex(&Rational::from(0))
+                );
+            }
+            assert!(!solutions[..i].contains(a));
+        }
+    }
+
+    #[test]
+    fn test_circle_and_line() {
+        let ring = MultiPolynomial::<Rational>::structure();
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let vars = vec![x.clone(), y.clone()];
+        let xp = ring.var(x.clone());
+        let yp = ring.var(y.clone());
+        let c = |n: i64| MultiPolynomial::constant(Rational::from(n));
+
+        //x^2 + y^2 = 1 and x = y
+        let polys = vec![
+            ring.sum(vec![&ring.mul(&xp, &xp), &ring.mul(&yp, &yp), &c(-1)]),
+            ring.add(&xp, &ring.neg(&yp)),
+        ];
+        let solutions = ring.all_complex_solutions(&polys, &vars).unwrap();
+        assert_eq!(solutions.len(), 2);
+        check_solutions(&polys, &vars, &solutions);
+        let real_solutions = ring.all_real_solutions(&polys, &vars).unwrap();
+        assert_eq!(real_solutions.len(), 2);
+
+        //x^2 + y^2 = -1 and x = y
+        let polys = vec![
+            ring.sum(vec![&ring.mul(&xp, &xp), &ring.mul(&yp, &yp), &c(1)]),
+            ring.add(&xp, &ring.neg(&yp)),
+        ];
+        let solutions = ring.all_complex_solutions(&polys, &vars).unwrap();
+        assert_eq!(solutions.len(), 2);
+        check_solutions(&polys, &vars, &solutions);
+        assert_eq!(ring.all_real_solutions(&polys, &vars).unwrap().len(), 0);
+
+        //x = 1 and x = 2
+        let polys = vec![ring.add(&xp, &c(-1)), ring.add(&xp, &c(-2))];
+        assert_eq!(ring.all_complex_solutions(&polys, &vars).unwrap().len(), 0);
+
+        //xy = 0 has infinitely many solutions
+        let polys = vec![ring.mul(&xp, &yp)];
+        assert!(ring.all_complex_solutions(&polys, &vars).is_err());
+        assert!(ring.all_complex_solutions(&[], &vars).is_err());
+    }
+
+    #[test]
+    fn test_vanishing_leading_coefficients() {
+        //y(y - x), y(y - 1), (y - 1)(y - x) vanish only at (0, 0) and (1, 1) though each pair shares a component
+        let ring = MultiPolynomial::<Rational>::structure();
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let xp = ring.var(x.clone());
+        let yp = ring.var(y.clone());
+        let one = ring.one();
+        let polys = vec![
+            ring.mul(&yp, &ring.add(&yp, &ring.neg(&xp))),
+            ring.mul(&yp, &ring.add(&yp, &ring.neg(&one))),
+            ring.mul(
+                &ring.add(&yp, &ring.neg(&one)),
+                &ring.add(&yp, &ring.neg(&xp)),
+            ),
+        ];
+        for vars in [vec![x.clone(), y.clone()], vec![y.clone(), x.clone()]] {
+            let solutions = ring.all_real_solutions(&polys, &vars).unwrap();
+            assert_eq!(solutions.len(), 2);
+            for solution in solutions {
+                assert_eq!(solution[0], solution[1]);
+                assert!(
+                    solution[0] == RealAlgebraic::Rational(Rational::from(0))
+                        || solution[0] == RealAlgebraic::Rational(Rational::from(1))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cyclic_3() {
+        let ring = MultiPolynomial::<Rational>::structure();
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let z = Variable::new("z");
+        let vars = vec![x.clone(), y.clone(), z.clone()];
+        let xp = ring.var(x.clone());
+        let yp = ring.var(y.clone());
+        let zp = ring.var(z.clone());
+        let polys = vec![
+            ring.sum(vec![&xp, &yp, &zp]),
+            ring.sum(vec![
+                &ring.mul(&xp, &yp),
+                &ring.mul(&yp, &zp),
+                &ring.mul(&zp, &xp),
+            ]),
+            ring.add(
+                &ring.product(vec![&xp, &yp, &zp]),
+                &MultiPolynomial::constant(Rational::from(-1)),
+            ),
+        ];
+        //the permutations of the cube roots of unity
+        let solutions = ring.all_complex_solutions(&polys, &vars).unwrap();
+        assert_eq!(solutions.len(), 6);
+        check_solutions(&polys, &vars, &solutions);
+        assert_eq!(ring.all_real_solutions(&polys, &vars).unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,749 @@
+use super::*;
+use crate::polynomial::multipoly::*;
+use malachite_q::arithmetic::traits::SimplestRationalInInterval;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+//the degree of p in v
+fn degree_in(p: &MultiPolynomial<Rational>, v: &Variable) -> usize {
+    p.expand(v).degree().unwrap_or(0)
+}
+
+//scale p so that its leading coefficient with respect to the lexicographic order on vars, last variable first, is 1
+//return None if p is constant since constants carry no information about cell boundaries
+fn normalize(
+    p: &MultiPolynomial<Rational>,
+    vars: &[Variable],
+) -> Option<MultiPolynomial<Rational>> {
+    let p = p.clone().reduce();
+    if p.as_constant().is_some() {
+        return None;
+    }
+    let mut lc = p.clone();
+    for v in vars.iter().rev() {
+        lc = lc.expand(v).leading_coeff().unwrap();
+    }
+    let lc = lc.as_constant().unwrap();
+    Some(p.apply_map(|c| c / &lc).reduce())
+}
+
+fn push_new(polys: &mut Vec<MultiPolynomial<Rational>>, p: Option<MultiPolynomial<Rational>>) {
+    if let Some(p) = p {
+        if !polys.contains(&p) {
+            polys.push(p);
+        }
+    }
+}
+
+//Hong's improvement of the Collins projection of polys eliminating v, the last of vars:
+//  the leading coefficients of the reducta of each polynomial
+//  the principal subresultant coefficients of each reductum and its derivative
+//  the principal subresultant coefficients of each reductum of a polynomial with every later polynomial
+//Each polynomial must have positive degree in v. The result involves only the remaining variables.
+fn project(
+    polys: &[MultiPolynomial<Rational>],
+    vars: &[Variable],
+) -> Vec<MultiPolynomial<Rational>> {
+    let (v, lower_vars) = vars.split_last().unwrap();
+    let expanded = polys.iter().map(|p| p.expand(v)).collect::<Vec<_>>();
+    //the reducta of p which can have positive degree: once a leading coefficient is a nonzero constant the later reducta are never needed
+    let reducta = expanded
+        .iter()
+        .map(|p| {
+            let mut reducta = vec![];
+            let mut coeffs = p.coeffs();
+            while let Some(d) = Polynomial::<MultiPolynomial<Rational>>::from_coeffs(
+                coeffs.iter().map(|c| (*c).clone()).collect::<Vec<_>>(),
+            )
+            .degree()
+            {
+                coeffs.truncate(d + 1);
+                if d == 0 {
+                    break;
+                }
+                reducta.push(coeffs.clone());
+                if coeffs[d].clone().reduce().as_constant().is_some() {
+                    break;
+                }
+                coeffs.pop();
+            }
+            reducta
+        })
+        .collect::<Vec<_>>();
+
+    let ring = MultiPolynomial::<Rational>::structure();
+    let collapse = |coeffs: Vec<MultiPolynomial<Rational>>| {
+        ring.sum(
+            coeffs
+                .iter()
+                .enumerate()
+                .map(|(k, c)| ring.mul(c, &ring.var_pow(v.clone(), k)))
+                .collect(),
+        )
+    };
+    let mut proj = vec![];
+    for (i, p_reducta) in reducta.iter().enumerate() {
+        for g in p_reducta {
+            let d = g.len() - 1;
+            push_new(&mut proj, normalize(g[d], lower_vars));
+            let g = Polynomial::<MultiPolynomial<Rational>>::from_coeffs(
+                g.iter().map(|c| (*c).clone()).collect::<Vec<_>>(),
+            );
+            let dg = collapse(g.clone().derivative().into_coeffs());
+            let g = collapse(g.into_coeffs());
+            for psc in MultiPolynomial::principal_subresultant_coefficients(&g, &dg, v) {
+                push_new(&mut proj, normalize(&psc, lower_vars));
+            }
+            for q in &polys[i + 1..] {
+                for psc in MultiPolynomial::principal_subresultant_coefficients(&g, q, v) {
+                    push_new(&mut proj, normalize(&psc, lower_vars));
+                }
+            }
+        }
+    }
+    proj
+}
+
+//the value of p at the point whose coordinates with respect to vars are given
+fn evaluate_at(
+    p: &MultiPolynomial<Rational>,
+    vars: &[Variable],
+    point: &[RealAlgebraic],
+) -> RealAlgebraic {
+    let values = vars
+        .iter()
+        .cloned()
+        .zip(point.iter())
+        .collect::<HashMap<_, _>>();
+    MultiPolynomial::<RealAlgebraic>::structure().evaluate(
+        &p.apply_map(|c| RealAlgebraic::Rational(c.clone())),
+        &values,
+    )
+}
+
+fn sign(x: &RealAlgebraic) -> Ordering {
+    x.cmp(&RealAlgebraic::Rational(Rational::ZERO))
+}
+
+//the distinct real roots, in increasing order, of a polynomial over the real algebraic numbers
+fn distinct_real_roots(p: &Polynomial<RealAlgebraic>) -> Vec<RealAlgebraic> {
+    let mut roots = if p
+        .coeffs()
+        .into_iter()
+        .all(|c| matches!(c, RealAlgebraic::Rational(_)))
+    {
+        p.apply_map(|c| match c {
+            RealAlgebraic::Rational(c) => c.clone(),
+            RealAlgebraic::Real(_) => unreachable!(),
+        })
+        .primitive_part_fof()
+        .all_real_roots()
+    } else {
+        Polynomial::<RealAlgebraic>::structure().all_real_roots(p)
+    };
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+//a simple rational strictly between a < b
+fn rational_between(a: &RealAlgebraic, b: &RealAlgebraic) -> Rational {
+    debug_assert!(a < b);
+    let mut a = a.clone();
+    let mut b = b.clone();
+    let mut accuracy = Rational::ONE;
+    loop {
+        let (_, a_hi) = a.rational_enclosure_mut(&accuracy);
+        let (b_lo, _) = b.rational_enclosure_mut(&accuracy);
+        if a_hi < b_lo {
+            return Rational::simplest_rational_in_open_interval(&a_hi, &b_lo);
+        }
+        accuracy /= Rational::TWO;
+    }
+}
+
+/// A cell in a cylindrical algebraic decomposition of R^k together with the cells of R^(k+1) in the cylinder above it.
+#[derive(Debug, Clone)]
+pub struct CadCell {
+    index: Vec<usize>,
+    sample: Vec<RealAlgebraic>,
+    children: Vec<CadCell>,
+}
+
+impl CadCell {
+    /// The position of the cell in each of the stacks containing it. Even positions are sectors and odd positions are sections, counting up from the bottom of the stack.
+    pub fn index(&self) -> &Vec<usize> {
+        &self.index
+    }
+
+    /// A point in the cell.
+    pub fn sample_point(&self) -> &Vec<RealAlgebraic> {
+        &self.sample
+    }
+
+    /// The dimension of the cell, which is the number of sectors in its index.
+    pub fn dimension(&self) -> usize {
+        self.index.iter().filter(|i| *i % 2 == 0).count()
+    }
+
+    /// The cells of the stack over this cell, in increasing order of the last coordinate.
+    pub fn children(&self) -> &Vec<CadCell> {
+        &self.children
+    }
+}
+
+/// A cylindrical algebraic decomposition of R^n, with respect to an ordering of the variables x_1, ..., x_n, such that each of a list of polynomials has constant sign on every cell.
+#[derive(Debug, Clone)]
+pub struct CylindricalAlgebraicDecomposition {
+    vars: Vec<Variable>,
+    polys: Vec<MultiPolynomial<Rational>>,
+    //levels[k] are the polynomials in x_1, ..., x_(k+1) with positive degree in x_(k+1) whose real roots are the section boundaries over cells of R^k
+    levels: Vec<Vec<MultiPolynomial<Rational>>>,
+    base: CadCell,
+}
+
+impl CylindricalAlgebraicDecomposition {
+    /// Decompose R^n into cells on which every polynomial in polys has constant sign. The polynomials may only involve the variables vars.
+    pub fn new(vars: Vec<Variable>, polys: Vec<MultiPolynomial<Rational>>) -> Self {
+        for p in &polys {
+            for v in p.free_vars() {
+                assert!(
+                    vars.contains(&v),
+                    "polynomial involves a variable not in vars"
+                );
+            }
+        }
+        let n = vars.len();
+
+        //projection phase
+        let mut levels = vec![vec![]; n];
+        let mut pending = vec![];
+        for p in &polys {
+            push_new(&mut pending, normalize(p, &vars));
+        }
+        for k in (0..n).rev() {
+            let (level, lower): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|p| degree_in(p, &vars[k]) > 0);
+            pending = lower;
+            if !level.is_empty() {
+                for p in project(&level, &vars[0..=k]) {
+                    push_new(&mut pending, Some(p));
+                }
+            }
+            levels[k] = level;
+        }
+        debug_assert!(pending.is_empty());
+
+        //lifting phase
+        let mut base = CadCell {
+            index: vec![],
+            sample: vec![],
+            children: vec![],
+        };
+        Self::lift(&vars, &levels, &mut base);
+
+        Self {
+            vars,
+            polys,
+            levels,
+            base,
+        }
+    }
+
+    fn lift(
+        vars: &Vec<Variable>,
+        levels: &Vec<Vec<MultiPolynomial<Rational>>>,
+        cell: &mut CadCell,
+    ) {
+        let k = cell.sample.len();
+        if k == vars.len() {
+            return;
+        }
+        let mut roots = vec![];
+        for p in &levels[k] {
+            let p = p
+                .expand(&vars[k])
+                .apply_map(|c| evaluate_at(c, &vars[0..k], &cell.sample));
+            //p vanishes identically over the cell when it is nullified, and then it does not bound any sections
+            if p.degree().unwrap_or(0) > 0 {
+                roots.extend(distinct_real_roots(&p));
+            }
+        }
+        roots.sort();
+        roots.dedup();
+
+        let mut coordinates = vec![];
+        match (roots.first(), roots.last()) {
+            (Some(first), Some(last)) => {
+                let (first_lo, _) = first.rational_enclosure(&Rational::ONE);
+                let (_, last_hi) = last.rational_enclosure(&Rational::ONE);
+                coordinates.push(RealAlgebraic::Rational(Rational::from(
+                    Floor::floor(first_lo) - Integer::ONE,
+                )));
+                for i in 0..roots.len() {
+                    coordinates.push(roots[i].clone());
+                    if i + 1 < roots.len() {
+                        coordinates.push(RealAlgebraic::Rational(rational_between(
+                            &roots[i],
+                            &roots[i + 1],
+                        )));
+                    }
+                }
+                coordinates.push(RealAlgebraic::Rational(Rational::from(
+                    Ceiling::ceiling(last_hi) + Integer::ONE,
+                )));
+            }
+            _ => coordinates.push(RealAlgebraic::Rational(Rational::ZERO)),
+        }
+
+        for (i, x) in coordinates.into_iter().enumerate() {
+            let mut index = cell.index.clone();
+            index.push(i);
+            let mut sample = cell.sample.clone();
+            sample.push(x);
+            let mut child = CadCell {
+                index,
+                sample,
+                children: vec![],
+            };
+            Self::lift(vars, levels, &mut child);
+            cell.children.push(child);
+        }
+    }
+
+    pub fn variables(&self) -> &Vec<Variable> {
+        &self.vars
+    }
+
+    pub fn polynomials(&self) -> &Vec<MultiPolynomial<Rational>> {
+        &self.polys
+    }
+
+    /// The projection polynomials in x_1, ..., x_k with positive degree in x_k, for 1 <= k <= n. Every cell of R^k is sign invariant for them.
+    pub fn projection_polynomials(&self, k: usize) -> &Vec<MultiPolynomial<Rational>> {
+        assert!(1 <= k && k <= self.vars.len());
+        &self.levels[k - 1]
+    }
+
+    /// The unique cell of R^0, whose descendants are all the cells of the decomposition.
+    pub fn base_cell(&self) -> &CadCell {
+        &self.base
+    }
+
+    /// The cells of the induced decomposition of R^k, for 0 <= k <= n.
+    pub fn cells_at_level(&self, k: usize) -> Vec<&CadCell> {
+        assert!(k <= self.vars.len());
+        let mut cells = vec![&self.base];
+        for _ in 0..k {
+            cells = cells.into_iter().flat_map(|c| c.children.iter()).collect();
+        }
+        cells
+    }
+
+    /// The cells of R^n.
+    pub fn cells(&self) -> Vec<&CadCell> {
+        self.cells_at_level(self.vars.len())
+    }
+
+    /// The sign of a polynomial in the first k variables on a cell of R^k.
+    /// It is constant on the cell whenever the polynomial is one of the input or projection polynomials.
+    pub fn sign(&self, p: &MultiPolynomial<Rational>, cell: &CadCell) -> Ordering {
+        sign(&evaluate_at(p, &self.vars, &cell.sample))
+    }
+
+    /// The signs of the input polynomials on a cell of R^n.
+    pub fn signs(&self, cell: &CadCell) -> Vec<Ordering> {
+        assert_eq!(cell.sample.len(), self.vars.len());
+        self.polys.iter().map(|p| self.sign(p, cell)).collect()
+    }
+}
+
+/// A condition on the sign of a real number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignCondition {
+    Negative,
+    NonPositive,
+    Zero,
+    NonZero,
+    NonNegative,
+    Positive,
+}
+
+impl SignCondition {
+    pub fn holds(&self, sign: Ordering) -> bool {
+        match self {
+            SignCondition::Negative => sign.is_lt(),
+            SignCondition::NonPositive => sign.is_le(),
+            SignCondition::Zero => sign.is_eq(),
+            SignCondition::NonZero => sign.is_ne(),
+            SignCondition::NonNegative => sign.is_ge(),
+            SignCondition::Positive => sign.is_gt(),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            SignCondition::Negative => "<",
+            SignCondition::NonPositive => "<=",
+            SignCondition::Zero => "=",
+            SignCondition::NonZero => "!=",
+            SignCondition::NonNegative => ">=",
+            SignCondition::Positive => ">",
+        }
+    }
+}
+
+/// A quantifier free formula whose atoms are sign conditions on polynomials with rational coefficients.
+#[derive(Debug, Clone)]
+pub enum RealFormula {
+    True,
+    False,
+    Atom(MultiPolynomial<Rational>, SignCondition),
+    Not(Box<RealFormula>),
+    And(Vec<RealFormula>),
+    Or(Vec<RealFormula>),
+}
+
+impl RealFormula {
+    fn collect_polynomials(&self, polys: &mut Vec<MultiPolynomial<Rational>>) {
+        match self {
+            RealFormula::True | RealFormula::False => {}
+            RealFormula::Atom(p, _) => {
+                if !polys.contains(p) {
+                    polys.push(p.clone());
+                }
+            }
+            RealFormula::Not(f) => f.collect_polynomials(polys),
+            RealFormula::And(fs) | RealFormula::Or(fs) => {
+                for f in fs {
+                    f.collect_polynomials(polys);
+                }
+            }
+        }
+    }
+
+    /// The distinct polynomials appearing in the atoms of the formula.
+    pub fn polynomials(&self) -> Vec<MultiPolynomial<Rational>> {
+        let mut polys = vec![];
+        self.collect_polynomials(&mut polys);
+        polys
+    }
+
+    /// Evaluate the formula given the sign of each polynomial appearing in it.
+    pub fn evaluate_with_signs(
+        &self,
+        sign: &impl Fn(&MultiPolynomial<Rational>) -> Ordering,
+    ) -> bool {
+        match self {
+            RealFormula::True => true,
+            RealFormula::False => false,
+            RealFormula::Atom(p, cond) => cond.holds(sign(p)),
+            RealFormula::Not(f) => !f.evaluate_with_signs(sign),
+            RealFormula::And(fs) => fs.iter().all(|f| f.evaluate_with_signs(sign)),
+            RealFormula::Or(fs) => fs.iter().any(|f| f.evaluate_with_signs(sign)),
+        }
+    }
+
+    /// Evaluate the formula at the point whose coordinates with respect to vars are given.
+    pub fn evaluate(&self, vars: &[Variable], point: &[RealAlgebraic]) -> bool {
+        assert_eq!(vars.len(), point.len());
+        self.evaluate_with_signs(&|p| sign(&evaluate_at(p, vars, point)))
+    }
+}
+
+impl Display for RealFormula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |f: &mut std::fmt::Formatter<'_>, fs: &Vec<RealFormula>, sep: &str| {
+            for (i, g) in fs.iter().enumerate() {
+                if i != 0 {
+                    write!(f, " {} ", sep)?;
+                }
+                write!(f, "({})", g)?;
+            }
+            Ok(())
+        };
+        match self {
+            RealFormula::True => write!(f, "true"),
+            RealFormula::False => write!(f, "false"),
+            RealFormula::Atom(p, cond) => write!(f, "{} {} 0", p, cond.symbol()),
+            RealFormula::Not(g) => write!(f, "not ({})", g),
+            RealFormula::And(fs) => join(f, fs, "and"),
+            RealFormula::Or(fs) => join(f, fs, "or"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    Exists,
+    ForAll,
+}
+
+/// A formula Q_1 y_1 ... Q_m y_m F where F is quantifier free.
+#[derive(Debug, Clone)]
+pub struct PrenexFormula {
+    pub quantifiers: Vec<(Quantifier, Variable)>,
+    pub matrix: RealFormula,
+}
+
+impl PrenexFormula {
+    /// Find a quantifier free formula in free_vars equivalent to self, using a cylindrical algebraic decomposition with the free variables first.
+    /// The solution set is described by the signs of the projection polynomials in the free variables. This fails if those signs do not separate the cells where the formula holds from those where it does not.
+    pub fn eliminate_quantifiers(
+        &self,
+        free_vars: Vec<Variable>,
+    ) -> Result<RealFormula, &'static str> {
+        let k = free_vars.len();
+        let mut vars = free_vars;
+        for (_, v) in &self.quantifiers {
+            if vars.contains(v) {
+                return Err("a quantified variable is also free or quantified twice");
+            }
+            vars.push(v.clone());
+        }
+        let polys = self.matrix.polynomials();
+        if polys
+            .iter()
+            .any(|p| p.free_vars().iter().any(|v| !vars.contains(v)))
+        {
+            return Err("the formula involves a variable which is neither free nor quantified");
+        }
+        let cad = CylindricalAlgebraicDecomposition::new(vars, polys);
+
+        fn truth(
+            cad: &CylindricalAlgebraicDecomposition,
+            quantifiers: &Vec<(Quantifier, Variable)>,
+            matrix: &RealFormula,
+            cell: &CadCell,
+        ) -> bool {
+            let level = cell.sample.len();
+            if level == cad.vars.len() {
+                matrix.evaluate_with_signs(&|p| cad.sign(p, cell))
+            } else {
+                let mut children = cell.children.iter();
+                match quantifiers[level + quantifiers.len() - cad.vars.len()].0 {
+                    Quantifier::Exists => children.any(|c| truth(cad, quantifiers, matrix, c)),
+                    Quantifier::ForAll => children.all(|c| truth(cad, quantifiers, matrix, c)),
+                }
+            }
+        }
+
+        //describe each cell of R^k by the signs of the projection polynomials in the free variables
+        let descriptors = (1..=k)
+            .flat_map(|j| cad.projection_polynomials(j).iter())
+            .collect::<Vec<_>>();
+        let mut sign_vectors: Vec<(Vec<Ordering>, bool)> = vec![];
+        for cell in cad.cells_at_level(k) {
+            let value = truth(&cad, &self.quantifiers, &self.matrix, cell);
+            let signs = descriptors
+                .iter()
+                .map(|p| cad.sign(p, cell))
+                .collect::<Vec<_>>();
+            match sign_vectors.iter().find(|(s, _)| s == &signs) {
+                Some((_, other_value)) => {
+                    if *other_value != value {
+                        return Err("the projection polynomials do not separate the solution set");
+                    }
+                }
+                None => sign_vectors.push((signs, value)),
+            }
+        }
+
+        if sign_vectors.iter().all(|(_, value)| *value) {
+            Ok(RealFormula::True)
+        } else if sign_vectors.iter().all(|(_, value)| !*value) {
+            Ok(RealFormula::False)
+        } else {
+            Ok(RealFormula::Or(
+                sign_vectors
+                    .into_iter()
+                    .filter(|(_, value)| *value)
+                    .map(|(signs, _)| {
+                        RealFormula::And(
+                            descriptors
+                                .iter()
+                                .zip(signs)
+                                .map(|(p, s)| {
+                                    RealFormula::Atom(
+                                        (*p).clone(),
+                                        match s {
+                                            Ordering::Less => SignCondition::Negative,
+                                            Ordering::Equal => SignCondition::Zero,
+                                            Ordering::Greater => SignCondition::Positive,
+                                        },
+                                    )
+                                })
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(v: &Variable) -> MultiPolynomial<Rational> {
+        MultiPolynomial::var(v.clone())
+    }
+
+    fn rat(n: i64) -> MultiPolynomial<Rational> {
+        MultiPolynomial::constant(Rational::from(n))
+    }
+
+    fn sum(ps: Vec<MultiPolynomial<Rational>>) -> MultiPolynomial<Rational> {
+        MultiPolynomial::sum(ps.iter().collect())
+    }
+
+    fn product(ps: Vec<MultiPolynomial<Rational>>) -> MultiPolynomial<Rational> {
+        MultiPolynomial::product(ps.iter().collect())
+    }
+
+    #[test]
+    fn test_cad_circle() {
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let circle = sum(vec![
+            product(vec![var(&x), var(&x)]),
+            product(vec![var(&y), var(&y)]),
+            rat(-1),
+        ]);
+        let cad = CylindricalAlgebraicDecomposition::new(vec![x.clone(), y.clone()], vec![circle]);
+        assert_eq!(cad.cells_at_level(1).len(), 5);
+        assert_eq!(cad.cells().len(), 13);
+        let mut counts = HashMap::new();
+        for cell in cad.cells() {
+            *counts
+                .entry((cad.signs(cell)[0], cell.dimension()))
+                .or_insert(0) += 1;
+        }
+        //the circle is two points and two arcs, the inside is one cell over each of x = -1 < x < 1
+        assert_eq!(counts.get(&(Ordering::Equal, 0)), Some(&2));
+        assert_eq!(counts.get(&(Ordering::Equal, 1)), Some(&2));
+        assert_eq!(counts.get(&(Ordering::Less, 2)), Some(&1));
+        assert_eq!(counts.get(&(Ordering::Less, 1)), None);
+        assert_eq!(counts.values().sum::<usize>(), 13);
+    }
+
+    #[test]
+    fn test_cad_sphere() {
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let z = Variable::new("z");
+        let sphere = sum(vec![
+            product(vec![var(&x), var(&x)]),
+            product(vec![var(&y), var(&y)]),
+            product(vec![var(&z), var(&z)]),
+            rat(-1),
+        ]);
+        let cad = CylindricalAlgebraicDecomposition::new(vec![x, y, z], vec![sphere]);
+        assert_eq!(cad.cells().len(), 25);
+        //the poles over x = -1 and x = 1, the equator points over y = +-sqrt(1 - x^2) and the two hemispheres over the disk
+        assert_eq!(
+            cad.cells()
+                .into_iter()
+                .filter(|cell| cad.signs(cell)[0] == Ordering::Equal)
+                .count(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_cad_two_curves() {
+        //the parabola y = x^2 and the line y = x meet at (0, 0) and (1, 1)
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let parabola = sum(vec![var(&y), product(vec![rat(-1), var(&x), var(&x)])]);
+        let line = sum(vec![var(&y), product(vec![rat(-1), var(&x)])]);
+        let cad = CylindricalAlgebraicDecomposition::new(
+            vec![x.clone(), y.clone()],
+            vec![parabola, line],
+        );
+        let sections = cad
+            .cells()
+            .into_iter()
+            .filter(|cell| cad.signs(cell) == vec![Ordering::Equal, Ordering::Equal])
+            .map(|cell| cell.sample_point().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            vec![
+                vec![
+                    RealAlgebraic::Rational(Rational::ZERO),
+                    RealAlgebraic::Rational(Rational::ZERO)
+                ],
+                vec![
+                    RealAlgebraic::Rational(Rational::ONE),
+                    RealAlgebraic::Rational(Rational::ONE)
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eliminate_exists() {
+        //exists y such that x^2 + y^2 = 1 if and only if -1 <= x <= 1
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let circle = sum(vec![
+            product(vec![var(&x), var(&x)]),
+            product(vec![var(&y), var(&y)]),
+            rat(-1),
+        ]);
+        let formula = PrenexFormula {
+            quantifiers: vec![(Quantifier::Exists, y.clone())],
+            matrix: RealFormula::Atom(circle, SignCondition::Zero),
+        };
+        let qf = formula.eliminate_quantifiers(vec![x.clone()]).unwrap();
+        for (n, expected) in [
+            (-4, false),
+            (-3, false),
+            (-2, true),
+            (-1, true),
+            (0, true),
+            (1, true),
+            (2, true),
+            (3, false),
+        ] {
+            let point = vec![RealAlgebraic::Rational(Rational::from_signeds(n, 2))];
+            assert_eq!(qf.evaluate(std::slice::from_ref(&x), &point), expected);
+        }
+    }
+
+    #[test]
+    fn test_eliminate_forall() {
+        //x^2 + bx + 1 > 0 for all x if and only if b^2 < 4
+        let b = Variable::new("b");
+        let x = Variable::new("x");
+        let quadratic = sum(vec![
+            product(vec![var(&x), var(&x)]),
+            product(vec![var(&b), var(&x)]),
+            rat(1),
+        ]);
+        let formula = PrenexFormula {
+            quantifiers: vec![(Quantifier::ForAll, x.clone())],
+            matrix: RealFormula::Atom(quadratic, SignCondition::Positive),
+        };
+        let qf = formula.eliminate_quantifiers(vec![b.clone()]).unwrap();
+        for n in -6..=6 {
+            let point = vec![RealAlgebraic::Rational(Rational::from(n))];
+            assert_eq!(qf.evaluate(std::slice::from_ref(&b), &point), n * n < 4);
+        }
+        //with no free variables the result is true or false
+        let formula = PrenexFormula {
+            quantifiers: vec![
+                (Quantifier::Exists, b.clone()),
+                (Quantifier::ForAll, x.clone()),
+            ],
+            matrix: formula.matrix,
+        };
+        assert!(matches!(
+            formula.eliminate_quantifiers(vec![]),
+            Ok(RealFormula::True)
+        ));
+    }
+}
@@ -0,0 +1,615 @@
+use std::collections::HashMap;
+
+use crate::number::natural::functions::sqrt_floor;
+
+use super::*;
+
+/// Lazily generate the partial quotients a0, a1, a2, ... of the continued fraction expansion of a real algebraic number.
+///
+/// The expansion is finite exactly when the number is rational, in which case the last partial quotient is at least 2 unless it is the only one.
+#[derive(Debug, Clone)]
+pub struct ContinuedFraction {
+    state: ContinuedFractionState,
+}
+
+#[derive(Debug, Clone)]
+enum ContinuedFractionState {
+    Finished,
+    // the remaining value is n/d with d > 0
+    Rational {
+        n: Integer,
+        d: Integer,
+    },
+    // the remaining value is (p + sqrt(disc))/q where disc > 0 is not a square and q divides disc - p^2
+    Quadratic {
+        p: Integer,
+        q: Integer,
+        disc: Integer,
+        isqrt_disc: Integer,
+    },
+    // the remaining value is (a*x + b)/(c*x + d) where x is the root
+    Algebraic {
+        root: Box<RealAlgebraicRoot>,
+        a: Integer,
+        b: Integer,
+        c: Integer,
+        d: Integer,
+    },
+}
+
+impl ContinuedFraction {
+    pub fn from_rational(x: &Rational) -> Self {
+        Self {
+            state: ContinuedFractionState::Rational {
+                n: Rational::numerator(x),
+                d: Rational::denominator(x),
+            },
+        }
+    }
+
+    fn from_quadratic_surd(p: Integer, q: Integer, disc: Integer) -> Self {
+        debug_assert_ne!(q, Integer::ZERO);
+        debug_assert_eq!((&disc - &p * &p) % &q, Integer::ZERO);
+        let isqrt_disc = Integer::from(sqrt_floor(disc.unsigned_abs_ref()));
+        debug_assert_ne!(&isqrt_disc * &isqrt_disc, disc);
+        Self {
+            state: ContinuedFractionState::Quadratic {
+                p,
+                q,
+                disc,
+                isqrt_disc,
+            },
+        }
+    }
+}
+
+// floor((p + sqrt(disc))/q) where sqrt(disc) is irrational with floor isqrt_disc
+fn quadratic_surd_floor(p: &Integer, q: &Integer, isqrt_disc: &Integer) -> Integer {
+    let floor_div = |n: Integer, d: &Integer| Rational::from_integers(n, d.clone()).floor();
+    if q > &Integer::ZERO {
+        floor_div(p + isqrt_disc, q)
+    } else {
+        -floor_div(p + isqrt_disc, &-q) - Integer::ONE
+    }
+}
+
+// Express a quadratic irrational as (p + sqrt(disc))/q with q dividing disc - p^2
+fn quadratic_surd(root: &mut RealAlgebraicRoot) -> (Integer, Integer, Integer) {
+    debug_assert_eq!(root.poly.degree(), Some(2));
+    let a = root.poly.coeff(2);
+    let b = root.poly.coeff(1);
+    let c = root.poly.coeff(0);
+    let disc = &b * &b - Integer::from(4) * &a * &c;
+    let two_a = Integer::TWO * a;
+    // the roots are (-b +/- sqrt(disc)) / 2a
+    match root.cmp_rat_mut(&Rational::from_integers(-&b, two_a.clone())) {
+        std::cmp::Ordering::Greater => (-b, two_a, disc),
+        std::cmp::Ordering::Less => (b, -two_a, disc),
+        std::cmp::Ordering::Equal => unreachable!(),
+    }
+}
+
+impl Iterator for ContinuedFraction {
+    type Item = Integer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.state {
+            ContinuedFractionState::Finished => None,
+            ContinuedFractionState::Rational { n, d } => {
+                let a = Rational::from_integers(n.clone(), d.clone()).floor();
+                let r = &*n - &a * &*d;
+                if r == Integer::ZERO {
+                    self.state = ContinuedFractionState::Finished;
+                } else {
+                    (*n, *d) = (d.clone(), r);
+                }
+                Some(a)
+            }
+            ContinuedFractionState::Quadratic {
+                p,
+                q,
+                disc,
+                isqrt_disc,
+            } => {
+                let a = quadratic_surd_floor(p, q, isqrt_disc);
+                let next_p = &a * &*q - &*p;
+                let next_q = (&*disc - &next_p * &next_p) / &*q;
+                (*p, *q) = (next_p, next_q);
+                Some(a)
+            }
+            ContinuedFractionState::Algebraic { root, a, b, c, d } => loop {
+                // the mobius map is monotone on the isolating interval when the pole lies outside it
+                let den_a = Rational::from(&*c) * &root.tight_a + Rational::from(&*d);
+                let den_b = Rational::from(&*c) * &root.tight_b + Rational::from(&*d);
+                if den_a != Rational::ZERO
+                    && den_b != Rational::ZERO
+                    && (den_a > Rational::ZERO) == (den_b > Rational::ZERO)
+                {
+                    let val_a = (Rational::from(&*a) * &root.tight_a + Rational::from(&*b)) / den_a;
+                    let val_b = (Rational::from(&*a) * &root.tight_b + Rational::from(&*b)) / den_b;
+                    let (lo, hi) = if val_a < val_b {
+                        (val_a, val_b)
+                    } else {
+                        (val_b, val_a)
+                    };
+                    // the value lies strictly between lo and hi
+                    let q = lo.floor();
+                    if hi <= &q + Integer::ONE {
+                        let next_c = &*a - &q * &*c;
+                        let next_d = &*b - &q * &*d;
+                        (*a, *b, *c, *d) = (c.clone(), d.clone(), next_c, next_d);
+                        return Some(q);
+                    }
+                }
+                root.refine();
+            },
+        }
+    }
+}
+
+/// The convergents p_k/q_k of a sequence of partial quotients
+#[derive(Debug, Clone)]
+pub struct Convergents<I: Iterator<Item = Integer>> {
+    quotients: I,
+    // (p_{k-1}, q_{k-1}) and (p_{k-2}, q_{k-2})
+    prev: (Integer, Integer),
+    prev_prev: (Integer, Integer),
+}
+
+pub fn convergents<I: IntoIterator<Item = Integer>>(quotients: I) -> Convergents<I::IntoIter> {
+    Convergents {
+        quotients: quotients.into_iter(),
+        prev: (Integer::ONE, Integer::ZERO),
+        prev_prev: (Integer::ZERO, Integer::ONE),
+    }
+}
+
+impl<I: Iterator<Item = Integer>> Iterator for Convergents<I> {
+    type Item = Rational;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let a = self.quotients.next()?;
+        let p = &a * &self.prev.0 + &self.prev_prev.0;
+        let q = &a * &self.prev.1 + &self.prev_prev.1;
+        self.prev_prev = std::mem::replace(&mut self.prev, (p.clone(), q.clone()));
+        Some(Rational::from_integers(p, q))
+    }
+}
+
+/// An eventually periodic continued fraction [a0; a1, ..., a(n-1), (b0, ..., b(m-1))] where the b's repeat forever.
+///
+/// These are exactly the expansions of rational numbers (with an empty period) and quadratic irrationals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PeriodicContinuedFraction {
+    initial: Vec<Integer>,
+    period: Vec<Integer>,
+}
+
+impl PeriodicContinuedFraction {
+    pub fn new(initial: Vec<Integer>, period: Vec<Integer>) -> Self {
+        assert!(
+            !(initial.is_empty() && period.is_empty()),
+            "continued fraction should have at least one partial quotient"
+        );
+        for (i, a) in initial.iter().chain(period.iter()).enumerate() {
+            assert!(
+                i == 0 || a > &Integer::ZERO,
+                "partial quotients after the first should be positive"
+            );
+        }
+        if initial.is_empty() {
+            assert!(
+                period[0] > Integer::ZERO,
+                "repeated partial quotients should be positive"
+            );
+        }
+        Self::reduce(initial, period)
+    }
+
+    // write the expansion with the shortest possible period and the shortest possible initial part
+    fn reduce(mut initial: Vec<Integer>, mut period: Vec<Integer>) -> Self {
+        let m = period.len();
+        if let Some(k) =
+            (1..m).find(|k| m.is_multiple_of(*k) && (*k..m).all(|i| period[i] == period[i - k]))
+        {
+            period.truncate(k);
+        }
+        while !period.is_empty() && initial.last() == period.last() {
+            initial.pop();
+            period.rotate_right(1);
+        }
+        Self { initial, period }
+    }
+
+    pub fn initial(&self) -> &Vec<Integer> {
+        &self.initial
+    }
+
+    pub fn period(&self) -> &Vec<Integer> {
+        &self.period
+    }
+
+    /// Reconstruct the rational number or quadratic irrational with this expansion
+    pub fn evaluate(&self) -> RealAlgebraic {
+        let tail = if self.period.is_empty() {
+            None
+        } else {
+            // y = [b0; b1, ..., b(m-1), y] = (p y + p') / (q y + q')
+            // so y is the root greater than 1 of q y^2 + (q' - p) y - p'
+            let (mut p, mut q) = (Integer::ONE, Integer::ZERO);
+            let (mut pp, mut qq) = (Integer::ZERO, Integer::ONE);
+            for b in &self.period {
+                (p, pp) = (b * &p + &pp, p);
+                (q, qq) = (b * &q + &qq, q);
+            }
+            let poly = Polynomial::from_coeffs(vec![-pp, qq - &p, q]);
+            let y = poly.all_real_roots().into_iter().max().unwrap();
+            debug_assert!(y > RealAlgebraic::one());
+            Some(y)
+        };
+        // fold the initial partial quotients into the tail from the right
+        let mut x = tail;
+        for a in self.initial.iter().rev() {
+            let a = RealAlgebraic::Rational(Rational::from(a));
+            x = Some(match x {
+                None => a,
+                Some(y) => RealAlgebraic::add(&a, &y.inv().unwrap()),
+            });
+        }
+        x.unwrap()
+    }
+}
+
+impl Display for PeriodicContinuedFraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut terms = self
+            .initial
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>();
+        if !self.period.is_empty() {
+            terms.push(format!(
+                "({})",
+                self.period
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        match terms.len() {
+            1 => write!(f, "[{}]", terms[0]),
+            _ => write!(f, "[{}; {}]", terms[0], terms[1..].join(", ")),
+        }
+    }
+}
+
+impl RealAlgebraicRoot {
+    /// The floor of the root. Since the root is irrational it is never an integer.
+    pub fn floor_mut(&mut self) -> Integer {
+        loop {
+            let a = self.tight_a.clone().floor();
+            if self.tight_b <= &a + Integer::ONE {
+                return a;
+            }
+            self.refine();
+        }
+    }
+}
+
+impl RealAlgebraic {
+    pub fn continued_fraction(&self) -> ContinuedFraction {
+        match self {
+            RealAlgebraic::Rational(x) => ContinuedFraction::from_rational(x),
+            RealAlgebraic::Real(root) => {
+                let mut root = root.clone();
+                if root.poly.degree() == Some(2) {
+                    let (p, q, disc) = quadratic_surd(&mut root);
+                    ContinuedFraction::from_quadratic_surd(p, q, disc)
+                } else {
+                    ContinuedFraction {
+                        state: ContinuedFractionState::Algebraic {
+                            root: Box::new(root),
+                            a: Integer::ONE,
+                            b: Integer::ZERO,
+                            c: Integer::ZERO,
+                            d: Integer::ONE,
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the exact eventually periodic continued fraction expansion when self is rational or a quadratic irrational, and None otherwise
+    pub fn periodic_continued_fraction(&self) -> Option<PeriodicContinuedFraction> {
+        match self {
+            RealAlgebraic::Rational(x) => Some(PeriodicContinuedFraction::new(
+                ContinuedFraction::from_rational(x).collect(),
+                vec![],
+            )),
+            RealAlgebraic::Real(root) => {
+                if root.poly.degree() != Some(2) {
+                    return None;
+                }
+                let (p, q, disc) = quadratic_surd(&mut root.clone());
+                let mut cf = ContinuedFraction::from_quadratic_surd(p, q, disc);
+                let mut seen = HashMap::new();
+                let mut quotients = vec![];
+                loop {
+                    let key = match &cf.state {
+                        ContinuedFractionState::Quadratic { p, q, .. } => (p.clone(), q.clone()),
+                        _ => unreachable!(),
+                    };
+                    if let Some(start) = seen.get(&key) {
+                        let period = quotients.split_off(*start);
+                        return Some(PeriodicContinuedFraction::new(quotients, period));
+                    }
+                    seen.insert(key, quotients.len());
+                    quotients.push(cf.next().unwrap());
+                }
+            }
+        }
+    }
+
+    /// The closest rational number to self with denominator at most max_denominator.
+    /// When two such rationals are equally close the one with the smaller denominator is returned.
+    pub fn best_rational_approximation(&self, max_denominator: &Natural) -> Rational {
+        assert!(*max_denominator >= Natural::ONE);
+        let n = Integer::from(max_denominator);
+        let (mut p1, mut q1) = (Integer::ONE, Integer::ZERO);
+        let (mut p2, mut q2) = (Integer::ZERO, Integer::ONE);
+        for a in self.continued_fraction() {
+            let q = &a * &q1 + &q2;
+            if q > n {
+                // compare the last convergent with the best semiconvergent
+                let t = Rational::from_integers(&n - &q2, q1.clone()).floor();
+                let convergent = Rational::from_integers(p1.clone(), q1.clone());
+                let semiconvergent = Rational::from_integers(&p2 + &t * &p1, &q2 + &t * &q1);
+                let mid = (&convergent + &semiconvergent) / Rational::TWO;
+                let (lower, upper) = if convergent < semiconvergent {
+                    (convergent, semiconvergent)
+                } else {
+                    (semiconvergent, convergent)
+                };
+                return match self.clone().cmp_mut(&mut RealAlgebraic::Rational(mid)) {
+                    std::cmp::Ordering::Less => lower,
+                    std::cmp::Ordering::Greater => upper,
+                    std::cmp::Ordering::Equal => {
+                        if lower.denominator_ref() <= upper.denominator_ref() {
+                            lower
+                        } else {
+                            upper
+                        }
+                    }
+                };
+            }
+            let p = &a * &p1 + &p2;
+            (p2, q2) = (p1, q1);
+            (p1, q1) = (p, q);
+        }
+        // self is rational with denominator at most max_denominator
+        Rational::from_integers(p1, q1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use malachite_base::num::arithmetic::traits::Abs;
+
+    use super::*;
+
+    fn ints(xs: Vec<i64>) -> Vec<Integer> {
+        xs.into_iter().map(Integer::from).collect()
+    }
+
+    fn sqrt(n: i64) -> RealAlgebraic {
+        Polynomial::from_coeffs(vec![Integer::from(-n), Integer::ZERO, Integer::ONE])
+            .all_real_roots()
+            .into_iter()
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rational_continued_fraction() {
+        let rat = |s: &'static str| Rational::from_str(s).unwrap();
+        assert_eq!(
+            ContinuedFraction::from_rational(&rat("415/93")).collect::<Vec<_>>(),
+            ints(vec![4, 2, 6, 7])
+        );
+        assert_eq!(
+            ContinuedFraction::from_rational(&rat("-415/93")).collect::<Vec<_>>(),
+            ints(vec![-5, 1, 1, 6, 7])
+        );
+        assert_eq!(
+            ContinuedFraction::from_rational(&rat("3")).collect::<Vec<_>>(),
+            ints(vec![3])
+        );
+        assert_eq!(
+            ContinuedFraction::from_rational(&rat("0")).collect::<Vec<_>>(),
+            ints(vec![0])
+        );
+        assert_eq!(
+            convergents(ContinuedFraction::from_rational(&rat("415/93"))).collect::<Vec<_>>(),
+            vec![rat("4"), rat("9/2"), rat("58/13"), rat("415/93")]
+        );
+    }
+
+    #[test]
+    fn test_quadratic_continued_fraction() {
+        assert_eq!(
+            sqrt(2).continued_fraction().take(5).collect::<Vec<_>>(),
+            ints(vec![1, 2, 2, 2, 2])
+        );
+        assert_eq!(
+            sqrt(2).periodic_continued_fraction().unwrap(),
+            PeriodicContinuedFraction::new(ints(vec![1]), ints(vec![2]))
+        );
+        assert_eq!(
+            sqrt(7).periodic_continued_fraction().unwrap(),
+            PeriodicContinuedFraction::new(ints(vec![2]), ints(vec![1, 1, 1, 4]))
+        );
+        assert_eq!(
+            RealAlgebraic::neg(&sqrt(7))
+                .periodic_continued_fraction()
+                .unwrap(),
+            PeriodicContinuedFraction::new(ints(vec![-3, 2]), ints(vec![1, 4, 1, 1]))
+        );
+        // the golden ratio (1 + sqrt(5))/2
+        let phi = Polynomial::from_coeffs(vec![-Integer::ONE, -Integer::ONE, Integer::ONE])
+            .all_real_roots()
+            .into_iter()
+            .max()
+            .unwrap();
+        let phi_cf = phi.periodic_continued_fraction().unwrap();
+        assert_eq!(
+            phi_cf,
+            PeriodicContinuedFraction::new(ints(vec![]), ints(vec![1]))
+        );
+        assert_eq!(phi_cf.to_string(), "[(1)]");
+        // (3 - sqrt(2)) / 7
+        let x = RealAlgebraic::div(
+            &RealAlgebraic::add(
+                &RealAlgebraic::from_int(&Integer::from(3)),
+                &RealAlgebraic::neg(&sqrt(2)),
+            ),
+            &RealAlgebraic::from_int(&Integer::from(7)),
+        )
+        .unwrap();
+        let x_cf = x.periodic_continued_fraction().unwrap();
+        assert_eq!(x_cf.to_string(), "[0; 4, (2)]");
+        assert_eq!(
+            x_cf.clone()
+                .initial()
+                .iter()
+                .chain(x_cf.period().iter().cycle())
+                .take(20)
+                .cloned()
+                .collect::<Vec<_>>(),
+            x.continued_fraction().take(20).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            RealAlgebraic::Rational(Rational::from_signeds(-415, 93))
+                .periodic_continued_fraction()
+                .unwrap()
+                .to_string(),
+            "[-5; 1, 1, 6, 7]"
+        );
+    }
+
+    #[test]
+    fn test_periodic_continued_fraction_evaluate() {
+        assert_eq!(
+            PeriodicContinuedFraction::new(ints(vec![1]), ints(vec![2])).evaluate(),
+            sqrt(2)
+        );
+        assert_eq!(
+            PeriodicContinuedFraction::new(ints(vec![2]), ints(vec![1, 1, 1, 4])).evaluate(),
+            sqrt(7)
+        );
+        assert_eq!(
+            PeriodicContinuedFraction::new(ints(vec![4, 2, 6, 7]), ints(vec![])).evaluate(),
+            RealAlgebraic::Rational(Rational::from_signeds(415, 93))
+        );
+        assert_eq!(
+            PeriodicContinuedFraction::new(ints(vec![1, 2, 2]), ints(vec![2, 2])),
+            PeriodicContinuedFraction::new(ints(vec![1]), ints(vec![2]))
+        );
+        for (initial, period) in [
+            (vec![0, 4, 2, 1], vec![3, 1, 2, 1]),
+            (vec![-3, 2], vec![1, 4, 1, 1]),
+            (vec![], vec![3, 1, 7]),
+            (vec![5], vec![1]),
+        ] {
+            let cf = PeriodicContinuedFraction::new(ints(initial), ints(period));
+            assert_eq!(cf.evaluate().periodic_continued_fraction().unwrap(), cf);
+        }
+    }
+
+    #[test]
+    fn test_algebraic_continued_fraction() {
+        // the real cube root of 2
+        let x = Polynomial::from_coeffs(vec![
+            Integer::from(-2),
+            Integer::ZERO,
+            Integer::ZERO,
+            Integer::ONE,
+        ])
+        .all_real_roots()
+        .into_iter()
+        .next()
+        .unwrap();
+        assert!(x.periodic_continued_fraction().is_none());
+        assert_eq!(
+            x.continued_fraction().take(12).collect::<Vec<_>>(),
+            ints(vec![1, 3, 1, 5, 1, 1, 4, 1, 1, 8, 1, 14])
+        );
+    }
+
+    #[test]
+    fn test_best_rational_approximation() {
+        let rat = |s: &'static str| Rational::from_str(s).unwrap();
+        let x = sqrt(2);
+        assert_eq!(
+            x.best_rational_approximation(&Natural::from(1u32)),
+            rat("1")
+        );
+        assert_eq!(
+            x.best_rational_approximation(&Natural::from(2u32)),
+            rat("3/2")
+        );
+        assert_eq!(
+            x.best_rational_approximation(&Natural::from(4u32)),
+            rat("4/3")
+        );
+        assert_eq!(
+            x.best_rational_approximation(&Natural::from(5u32)),
+            rat("7/5")
+        );
+        assert_eq!(
+            x.best_rational_approximation(&Natural::from(100u32)),
+            rat("140/99")
+        );
+        let pi_approx = RealAlgebraic::Rational(rat("3141592653589793/1000000000000000"));
+        assert_eq!(
+            pi_approx.best_rational_approximation(&Natural::from(10u32)),
+            rat("22/7")
+        );
+        assert_eq!(
+            pi_approx.best_rational_approximation(&Natural::from(200u32)),
+            rat("355/113")
+        );
+        assert_eq!(
+            RealAlgebraic::Rational(rat("3/7")).best_rational_approximation(&Natural::from(7u32)),
+            rat("3/7")
+        );
+        // 1/2 is equally close to 0 and 1
+        assert_eq!(
+            RealAlgebraic::Rational(rat("1/2")).best_rational_approximation(&Natural::from(1u32)),
+            rat("0")
+        );
+        // exhaustive check against brute force
+        for d in 1u32..30 {
+            let mut best: Option<Rational> = None;
+            for q in 1..=d {
+                let p = (Rational::from(q) * rat("1414213562/1000000000")).floor();
+                for p in [p.clone(), p + Integer::ONE] {
+                    let c = Rational::from_integers(p, Integer::from(q));
+                    let err = |r: &Rational| {
+                        (r * r - Rational::TWO).abs() / (r + rat("1414213562/1000000000"))
+                    };
+                    if best.is_none() || err(&c) < err(best.as_ref().unwrap()) {
+                        best = Some(c);
+                    }
+                }
+            }
+            assert_eq!(
+                x.best_rational_approximation(&Natural::from(d)),
+                best.unwrap()
+            );
+        }
+    }
+}
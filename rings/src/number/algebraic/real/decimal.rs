@@ -0,0 +1,114 @@
+use super::super::{round_half_away, round_interval, scaled_integer_to_decimal_string, ten_to_the};
+use super::*;
+
+impl RealAlgebraicRoot {
+    /// Return rationals a < b with a < self < b and b - a <= accuracy.
+    pub fn rational_enclosure_mut(&mut self, accuracy: &Rational) -> (Rational, Rational) {
+        assert!(accuracy > &Rational::ZERO);
+        self.refine_to_accuracy(accuracy);
+        (self.tight_a.clone(), self.tight_b.clone())
+    }
+
+    // The nearest integer to self * scale. There are no ties because self is irrational.
+    fn round_scaled_mut(&mut self, scale: &Rational) -> Integer {
+        debug_assert!(scale > &Rational::ZERO);
+        loop {
+            if let Some(n) = round_interval(&(&self.tight_a * scale), &(&self.tight_b * scale)) {
+                return n;
+            }
+            self.refine();
+        }
+    }
+}
+
+impl RealAlgebraic {
+    /// Return rationals a <= b with a <= self <= b and b - a <= accuracy. Both bounds equal self when self is rational.
+    pub fn rational_enclosure(&self, accuracy: &Rational) -> (Rational, Rational) {
+        self.clone().rational_enclosure_mut(accuracy)
+    }
+
+    /// The same as rational_enclosure, but keep the refined isolating interval of self so that later calls are cheaper.
+    pub fn rational_enclosure_mut(&mut self, accuracy: &Rational) -> (Rational, Rational) {
+        match self {
+            RealAlgebraic::Rational(x) => (x.clone(), x.clone()),
+            RealAlgebraic::Real(x) => x.rational_enclosure_mut(accuracy),
+        }
+    }
+
+    /// The nearest integer to self * 10^digits, rounding half way cases away from zero.
+    pub(crate) fn round_decimal(&self, digits: usize) -> Integer {
+        let scale = ten_to_the(digits);
+        match self {
+            RealAlgebraic::Rational(x) => round_half_away(&(x * scale)),
+            RealAlgebraic::Real(x) => x.clone().round_scaled_mut(&scale),
+        }
+    }
+
+    /// The decimal expansion of self correctly rounded to `digits` digits after the decimal point.
+    /// Exact half way cases, which only occur for rational numbers, are rounded away from zero.
+    pub fn to_decimal_string(&self, digits: usize) -> String {
+        scaled_integer_to_decimal_string(&self.round_decimal(digits), digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqrt(n: i64) -> RealAlgebraic {
+        Polynomial::from_coeffs(vec![Integer::from(-n), Integer::ZERO, Integer::ONE])
+            .all_real_roots()
+            .into_iter()
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_real_decimal_string() {
+        assert_eq!(sqrt(2).to_decimal_string(0), "1");
+        assert_eq!(sqrt(2).to_decimal_string(5), "1.41421");
+        assert_eq!(
+            sqrt(2).to_decimal_string(50),
+            "1.41421356237309504880168872420969807856967187537695"
+        );
+        assert_eq!(RealAlgebraic::neg(&sqrt(3)).to_decimal_string(3), "-1.732");
+        assert_eq!(sqrt(11).to_decimal_string(1), "3.3");
+        assert_eq!(sqrt(11).to_decimal_string(2), "3.32");
+        assert_eq!(sqrt(11).to_decimal_string(4), "3.3166");
+
+        let rat = |n: i64, d: i64| RealAlgebraic::Rational(Rational::from_signeds(n, d));
+        assert_eq!(rat(1, 8).to_decimal_string(2), "0.13");
+        assert_eq!(rat(-1, 8).to_decimal_string(2), "-0.13");
+        assert_eq!(rat(1, 8).to_decimal_string(3), "0.125");
+        assert_eq!(rat(1, 8).to_decimal_string(5), "0.12500");
+        assert_eq!(rat(-1, 1000).to_decimal_string(2), "0.00");
+        assert_eq!(rat(2, 3).to_decimal_string(4), "0.6667");
+        assert_eq!(rat(-5, 2).to_decimal_string(0), "-3");
+        assert_eq!(rat(123, 1).to_decimal_string(1), "123.0");
+
+        // a root of x^3 - x - 1 (the plastic number)
+        let x = Polynomial::from_coeffs(vec![
+            Integer::from(-1),
+            Integer::from(-1),
+            Integer::ZERO,
+            Integer::ONE,
+        ])
+        .all_real_roots()
+        .into_iter()
+        .next()
+        .unwrap();
+        assert_eq!(x.to_decimal_string(20), "1.32471795724474602596");
+    }
+
+    #[test]
+    fn test_real_rational_enclosure() {
+        let x = sqrt(5);
+        let eps = Rational::from_signeds(1, 1000000000000i64);
+        let (a, b) = x.rational_enclosure(&eps);
+        assert!(&b - &a <= eps);
+        assert!(&a * &a < 5 && 5 < &b * &b);
+        let (a, b) = RealAlgebraic::Rational(Rational::from_signeds(3, 7)).rational_enclosure(&eps);
+        assert_eq!(a, Rational::from_signeds(3, 7));
+        assert_eq!(b, Rational::from_signeds(3, 7));
+    }
+}
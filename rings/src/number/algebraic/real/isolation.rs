@@ -0,0 +1,495 @@
+use malachite_base::num::logic::traits::SignificantBits;
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+use std::cmp::Ordering;
+
+use super::*;
+
+/// The algorithm used to isolate the real roots of a squarefree integer polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RealRootIsolationAlgorithm {
+    /// Bisection using Descartes' rule of signs in exact arithmetic (Collins and Akritas).
+    Bisection,
+    /// The continued fraction method of Vincent, Collins and Akritas using lower bounds on the positive roots.
+    /// Much faster than bisection when the roots are clustered or of very different magnitudes.
+    #[default]
+    ContinuedFraction,
+    /// Bisection using Descartes' rule of signs on approximations of the coefficients.
+    /// The precision is only increased where the signs of the approximate coefficients are not determined.
+    BitstreamDescartes,
+}
+
+fn sign_variations(coeffs: &[Integer]) -> usize {
+    let mut v = 0;
+    let mut prev: Option<bool> = None;
+    for c in coeffs {
+        if c != &Integer::ZERO {
+            let neg = c < &Integer::ZERO;
+            if prev.is_some_and(|p| p != neg) {
+                v += 1;
+            }
+            prev = Some(neg);
+        }
+    }
+    v
+}
+
+//replace p(x) by p(x + s)
+fn taylor_shift(coeffs: &mut [Integer], s: &Integer) {
+    let n = coeffs.len();
+    for i in 0..n {
+        for j in (i..n - 1).rev() {
+            let t = s * &coeffs[j + 1];
+            coeffs[j] += t;
+        }
+    }
+}
+
+//replace p(x) by p(x + 1)
+fn taylor_shift_one(coeffs: &mut [Integer]) {
+    let n = coeffs.len();
+    for i in 0..n {
+        for j in (i..n - 1).rev() {
+            let t = coeffs[j + 1].clone();
+            coeffs[j] += t;
+        }
+    }
+}
+
+//replace p(x) by p(x + 2^e)
+fn taylor_shift_pow2(coeffs: &mut [Integer], e: u64) {
+    for (i, c) in coeffs.iter_mut().enumerate() {
+        *c <<= e * i as u64;
+    }
+    taylor_shift_one(coeffs);
+    for (i, c) in coeffs.iter_mut().enumerate() {
+        *c >>= e * i as u64;
+    }
+}
+
+//an exponent e such that every positive real root of p is less than 2^e, or None if p has no positive real roots
+//uses the bound of Kioustelidis: 2 max { |a_i / a_n|^(1/(n-i)) : a_i and a_n of opposite sign }
+fn positive_root_upper_bound_exponent(coeffs: &[Integer]) -> Option<i64> {
+    let n = coeffs.len() - 1;
+    let lc = &coeffs[n];
+    let lc_bits = lc.unsigned_abs_ref().significant_bits() as i64;
+    let mut exp: Option<i64> = None;
+    for (i, c) in coeffs.iter().enumerate().take(n) {
+        if c != &Integer::ZERO && ((c < &Integer::ZERO) != (lc < &Integer::ZERO)) {
+            // |a_i / a_n| < 2^(bits(a_i) - bits(a_n) + 1)
+            let diff = c.unsigned_abs_ref().significant_bits() as i64 - lc_bits + 1;
+            let m = (n - i) as i64;
+            let e = diff.div_euclid(m) + i64::from(diff.rem_euclid(m) != 0);
+            exp = Some(exp.map_or(e, |x| x.max(e)));
+        }
+    }
+    exp.map(|e| e + 1)
+}
+
+//a power of two strictly greater than the absolute value of every complex root of p
+//uses 2 max { |a_i / a_n|^(1/(n-i)) } which is at least the bound of Fujiwara
+pub(crate) fn root_absolute_value_bound(p: &Polynomial<Integer>) -> Rational {
+    let mut coeffs = p.coeffs().into_iter().cloned().collect::<Vec<_>>();
+    //make every coefficient have the opposite sign to the leading coefficient
+    let n = coeffs.len() - 1;
+    let lc_neg = coeffs[n] < Integer::ZERO;
+    for c in coeffs.iter_mut().take(n) {
+        if (*c < Integer::ZERO) == lc_neg {
+            *c = -&*c;
+        }
+    }
+    match positive_root_upper_bound_exponent(&coeffs) {
+        Some(e) => {
+            let e = e.max(0) as u64;
+            Rational::from(Integer::ONE << e)
+        }
+        None => Rational::ONE,
+    }
+}
+
+// A polynomial known only up to a positive scalar multiple, whose coefficients lie in the intervals [lo_i, hi_i].
+#[derive(Debug, Clone)]
+struct ApproximatePolynomial {
+    lo: Vec<Integer>,
+    hi: Vec<Integer>,
+}
+
+impl ApproximatePolynomial {
+    fn new(coeffs: Vec<Integer>, precision: u64) -> Self {
+        let mut p = Self {
+            lo: coeffs.clone(),
+            hi: coeffs,
+        };
+        p.truncate(precision);
+        p
+    }
+
+    //discard low bits so that the largest coefficient bound has at most `precision` bits
+    fn truncate(&mut self, precision: u64) {
+        let bits = self
+            .lo
+            .iter()
+            .chain(self.hi.iter())
+            .map(|c| c.unsigned_abs_ref().significant_bits())
+            .max()
+            .unwrap();
+        if bits > precision {
+            let s = bits - precision;
+            for c in &mut self.lo {
+                *c >>= s;
+            }
+            for c in &mut self.hi {
+                *c = -(-&*c >> s);
+            }
+        }
+    }
+
+    fn sign(&self, i: usize) -> Option<Ordering> {
+        if self.lo[i] > Integer::ZERO {
+            Some(Ordering::Greater)
+        } else if self.hi[i] < Integer::ZERO {
+            Some(Ordering::Less)
+        } else if self.lo[i] == Integer::ZERO && self.hi[i] == Integer::ZERO {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+
+    //the number of sign variations, or None if the signs of the coefficients are not all known
+    fn sign_variations(&self) -> Option<usize> {
+        let mut v = 0;
+        let mut prev = Ordering::Equal;
+        for i in 0..self.lo.len() {
+            match self.sign(i)? {
+                Ordering::Equal => {}
+                s => {
+                    if prev != Ordering::Equal && prev != s {
+                        v += 1;
+                    }
+                    prev = s;
+                }
+            }
+        }
+        Some(v)
+    }
+
+    fn divide_by_var(&mut self) {
+        debug_assert_eq!(self.sign(0), Some(Ordering::Equal));
+        self.lo.remove(0);
+        self.hi.remove(0);
+    }
+
+    fn reversed(&self) -> Self {
+        Self {
+            lo: self.lo.iter().rev().cloned().collect(),
+            hi: self.hi.iter().rev().cloned().collect(),
+        }
+    }
+
+    fn taylor_shift_one(&mut self) {
+        taylor_shift_one(&mut self.lo);
+        taylor_shift_one(&mut self.hi);
+    }
+
+    //replace p(x) by 2^n p(x/2)
+    fn halve(&mut self) {
+        let n = self.lo.len() - 1;
+        for i in 0..=n {
+            self.lo[i] <<= (n - i) as u64;
+            self.hi[i] <<= (n - i) as u64;
+        }
+    }
+}
+
+impl Polynomial<Integer> {
+    //Vincent, Collins and Akritas continued fraction algorithm https://en.wikipedia.org/wiki/Real-root_isolation
+    pub(super) fn isolate_real_roots_by_continued_fractions(
+        &self,
+    ) -> Vec<(Rational, Option<Rational>)> {
+        //input: p(x), a square-free polynomial, such that p(0) p(1) ≠ 0, for which the roots in the interval [0, 1] are searched
+        //output: a sorted list of pairs (a, Some(b)) such that [a, b] is isolating and a, b are not roots, and (a, None) where a is a rational root
+        debug_assert_ne!(self.evaluate(&Integer::zero()), Integer::zero());
+        debug_assert_ne!(self.evaluate(&Integer::one()), Integer::zero());
+
+        //the roots of p in (0, 1) are the images of the positive roots of q(t) = (t+1)^n p(1/(t+1)) under t -> 1/(t+1)
+        let mut q = self.reversed().into_coeffs();
+        taylor_shift_one(&mut q);
+
+        //each item is a polynomial q and a mobius transformation t -> (at+b)/(ct+d) mapping its positive roots to the roots of p
+        let mut l = vec![(q, [Integer::ZERO, Integer::ONE, Integer::ONE, Integer::ONE])];
+        let mut isol = vec![];
+        while let Some((mut q, [a, mut b, c, mut d])) = l.pop() {
+            if q[0] == Integer::ZERO {
+                q.remove(0);
+                isol.push((Rational::from_integers(b.clone(), d.clone()), None));
+            }
+            match sign_variations(&q) {
+                0 => {}
+                1 => {
+                    let x = Rational::from_integers(b, d);
+                    let y = Rational::from_integers(a, c);
+                    if x < y {
+                        isol.push((x, Some(y)));
+                    } else {
+                        isol.push((y, Some(x)));
+                    }
+                }
+                _ => {
+                    //move past the region without positive roots
+                    let mut rev_q = q.clone();
+                    rev_q.reverse();
+                    if let Some(e) = positive_root_upper_bound_exponent(&rev_q) {
+                        //every positive root of q is at least 2^(-e)
+                        if e <= 0 {
+                            let e = (-e) as u64;
+                            taylor_shift_pow2(&mut q, e);
+                            b += &a << e;
+                            d += &c << e;
+                            if q[0] == Integer::ZERO {
+                                q.remove(0);
+                                isol.push((Rational::from_integers(b.clone(), d.clone()), None));
+                            }
+                        }
+                    }
+
+                    //roots greater than 1
+                    let mut q1 = q.clone();
+                    taylor_shift_one(&mut q1);
+                    //roots between 0 and 1
+                    let mut q2 = q;
+                    q2.reverse();
+                    taylor_shift_one(&mut q2);
+                    if q1[0] == Integer::ZERO {
+                        //1 is a root
+                        debug_assert_eq!(q2[0], Integer::ZERO);
+                        q1.remove(0);
+                        q2.remove(0);
+                        isol.push((Rational::from_integers(&a + &b, &c + &d), None));
+                    }
+                    let m1 = [a.clone(), &a + &b, c.clone(), &c + &d];
+                    let m2 = [b.clone(), &a + &b, d.clone(), c + &d];
+                    l.push((q1, m1));
+                    l.push((q2, m2));
+                }
+            }
+        }
+        isol.sort_by(|(x, _), (y, _)| x.cmp(y));
+        isol
+    }
+
+    //Descartes' method with approximate coefficients in the style of the bitstream Descartes algorithm of Eigenwillig et al.
+    pub(super) fn isolate_real_roots_by_bitstream_descartes(&self) -> Vec<(Natural, usize, bool)> {
+        //input: p(x), a square-free polynomial, such that p(0) p(1) ≠ 0, for which the roots in the interval [0, 1] are searched
+        //output: a list of triples (c, k, h) representing isolating intervals of the form [c/2^k, (c+h)/2^k]
+        debug_assert_ne!(self.evaluate(&Integer::zero()), Integer::zero());
+        debug_assert_ne!(self.evaluate(&Integer::one()), Integer::zero());
+
+        //p with the rational roots found so far removed
+        let mut p = self.clone();
+        //2^(kn) p((x+c)/2^k) approximated to the given precision
+        let node = |p: &Self, c: &Natural, k: usize, precision: u64| {
+            let n = p.degree().unwrap();
+            let mut coeffs = p
+                .coeffs()
+                .into_iter()
+                .enumerate()
+                .map(|(i, a)| a << (k * (n - i)) as u64)
+                .collect::<Vec<_>>();
+            taylor_shift(&mut coeffs, &Integer::from(c));
+            ApproximatePolynomial::new(coeffs, precision)
+        };
+
+        let initial_precision = 2 * self.degree().unwrap() as u64 + 64;
+        let mut l = vec![(
+            Natural::from(0u8),
+            0,
+            initial_precision,
+            node(&p, &Natural::from(0u8), 0, initial_precision),
+        )];
+        let mut isol = vec![];
+        while let Some((c, k, precision, mut q)) = l.pop() {
+            let v = match q.sign(0) {
+                None => None,
+                Some(s) => {
+                    if s == Ordering::Equal {
+                        //q = q/x
+                        q.divide_by_var();
+                        p = Self::div(
+                            &p,
+                            &Self::from_coeffs(vec![-Integer::from(&c), Integer::ONE << k as u64]),
+                        )
+                        .unwrap();
+                        isol.push((c.clone(), k, false)); //rational root
+                    }
+                    let mut t = q.reversed();
+                    t.taylor_shift_one();
+                    t.sign_variations()
+                }
+            };
+            match v {
+                None => {
+                    //not enough precision to decide, so start again at this node with more
+                    let precision = 2 * precision;
+                    let q = node(&p, &c, k, precision);
+                    l.push((c, k, precision, q));
+                }
+                Some(0) => {}
+                Some(1) => {
+                    isol.push((c, k, true)); //root
+                }
+                Some(_) => {
+                    //bisect
+                    let mut q_small = q;
+                    q_small.halve();
+                    q_small.truncate(precision);
+                    let mut q_small_shift = q_small.clone();
+                    q_small_shift.taylor_shift_one();
+                    q_small_shift.truncate(precision);
+                    l.push((
+                        (c.clone() << 1) + Natural::from(1u8),
+                        k + 1,
+                        precision,
+                        q_small_shift,
+                    ));
+                    l.push((c << 1, k + 1, precision, q_small));
+                }
+            }
+        }
+        isol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(coeffs: Vec<i64>) -> Polynomial<Integer> {
+        Polynomial::from_coeffs(coeffs.into_iter().map(Integer::from).collect())
+    }
+
+    //check that every algorithm finds the same roots of the product of some distinct irreducible polynomials and return how many there are
+    fn check_algorithms_agree(
+        factors: Vec<Polynomial<Integer>>,
+        opt_a: Option<&Rational>,
+        opt_b: Option<&Rational>,
+    ) -> usize {
+        let f = Polynomial::product(factors.iter().collect());
+        let mut all_roots = vec![];
+        for algorithm in [
+            RealRootIsolationAlgorithm::Bisection,
+            RealRootIsolationAlgorithm::ContinuedFraction,
+            RealRootIsolationAlgorithm::BitstreamDescartes,
+        ] {
+            let isolated = f
+                .clone()
+                .real_roots_squarefree_with(opt_a, opt_b, true, true, algorithm);
+            isolated.check_invariants().unwrap();
+            let mut roots = factors
+                .iter()
+                .flat_map(|g| {
+                    g.clone()
+                        .real_roots_squarefree_with(opt_a, opt_b, true, true, algorithm)
+                        .to_real_roots()
+                })
+                .collect::<Vec<_>>();
+            roots.sort();
+            assert_eq!(isolated.intervals_len(), roots.len());
+            all_roots.push(roots);
+        }
+        assert_eq!(all_roots[0], all_roots[1]);
+        assert_eq!(all_roots[0], all_roots[2]);
+        all_roots[0].len()
+    }
+
+    #[test]
+    fn test_isolation_algorithms_agree() {
+        //rational roots, some of them on dyadic points, and irrational roots
+        let factors = vec![
+            poly(vec![-1, 4]),
+            poly(vec![3, 8]),
+            poly(vec![-1, 3]),
+            poly(vec![-2, 0, 1]),
+            poly(vec![1, -3, 1]),
+            poly(vec![2, -4, 0, 0, 0, 1]),
+            poly(vec![1, -3, 0, 1]),
+        ];
+        assert_eq!(check_algorithms_agree(factors.clone(), None, None), 13);
+        let a = Rational::from_signeds(-3, 8);
+        let b = Rational::from_signeds(1, 3);
+        assert_eq!(
+            check_algorithms_agree(factors.clone(), Some(&a), Some(&b)),
+            3
+        );
+        assert_eq!(check_algorithms_agree(factors.clone(), Some(&a), None), 10);
+        assert_eq!(check_algorithms_agree(factors.clone(), None, Some(&b)), 6);
+
+        //the Mignotte polynomial x^6 - 2(10x - 1)^2 has two close roots
+        assert_eq!(
+            check_algorithms_agree(vec![poly(vec![-2, 40, -200, 0, 0, 0, 1])], None, None),
+            4
+        );
+
+        assert_eq!(
+            check_algorithms_agree(vec![poly(vec![1, 0, 1])], None, None),
+            0
+        );
+    }
+
+    #[test]
+    fn test_isolation_sparse_high_degree() {
+        //x^200 - 3x^101 + 2x - 1 has a root just above 1/2
+        let mut coeffs = vec![0; 201];
+        coeffs[0] = -1;
+        coeffs[1] = 2;
+        coeffs[101] = -3;
+        coeffs[200] = 1;
+        let f = poly(coeffs);
+        for algorithm in [
+            RealRootIsolationAlgorithm::ContinuedFraction,
+            RealRootIsolationAlgorithm::BitstreamDescartes,
+        ] {
+            let roots = f
+                .clone()
+                .real_roots_squarefree_with(None, None, false, false, algorithm);
+            roots.check_invariants().unwrap();
+            assert_eq!(roots.intervals_len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_isolation_zimmermann_degree_156() {
+        //bisection takes minutes on this polynomial even in release builds
+        let f = crate::number::integer::zimmermann_polys::p1();
+        let m = root_absolute_value_bound(&f);
+        //the roots in (-m, m) moved to (0, 1)
+        let (_, g) = Polynomial::compose(
+            &f.apply_map(|c| Rational::from(c)),
+            &Polynomial::from_coeffs(vec![-m.clone(), Rational::TWO * m]),
+        )
+        .factor_primitive_fof();
+        assert_eq!(g.isolate_real_roots_by_continued_fractions().len(), 24);
+        assert_eq!(g.isolate_real_roots_by_bitstream_descartes().len(), 24);
+    }
+
+    #[test]
+    fn test_all_real_roots_with() {
+        //(x^2 - 2)^2 (2x - 1)
+        let f = Polynomial::product(vec![
+            &poly(vec![-2, 0, 1]),
+            &poly(vec![-2, 0, 1]),
+            &poly(vec![-1, 2]),
+        ]);
+        let mut roots = f.all_real_roots();
+        roots.sort();
+        for algorithm in [
+            RealRootIsolationAlgorithm::ContinuedFraction,
+            RealRootIsolationAlgorithm::BitstreamDescartes,
+        ] {
+            let mut other = f.all_real_roots_with(algorithm);
+            other.sort();
+            assert_eq!(roots, other);
+        }
+        assert_eq!(roots.len(), 5);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{number::anf::number_field::new_anf, polynomial::polynomial::*, structure::structure::*};
+use crate::polynomial::multiplication::karatsuba_mul;
 use algebraeon_sets::structure::*;
 use bounds::*;
 use interval::*;
-use malachite_base::num::basic::traits::{One, Two, Zero};
+use isolation::*;
+use malachite_base::num::arithmetic::traits::{Ceiling, Floor};
+use malachite_base::num::basic::traits::{One, OneHalf, Two, Zero};
 use malachite_nz::{integer::Integer, natural::Natural};
 use malachite_q::Rational;
 use polynomial::*;
@@ -11,7 +14,11 @@ use std::{fmt::Display, rc::Rc};
 use super::{bisection_gen::RationalSimpleBetweenGenerator, poly_tools::*, rat_to_string};
 
 mod bounds;
+pub mod cad;
+pub mod continued_fraction;
+mod decimal;
 mod interval;
+pub mod isolation;
 pub mod polynomial;
 
 #[derive(Debug, Clone)]
@@ -599,6 +606,10 @@ impl SemiRingStructure for CannonicalStructure<RealAlgebraic> {
             }
         }
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        karatsuba_mul(self, a, b)
+    }
 }
 
 impl RingStructure for CannonicalStructure<RealAlgebraic> {
@@ -717,14 +728,30 @@ impl RealToFloatStructure for CannonicalStructure<RealAlgebraic> {
 }
 
 impl RealRoundingStructure for CannonicalStructure<RealAlgebraic> {
-    fn floor(&self, _x: &Self::Set) -> Integer {
-        todo!()
+    fn floor(&self, x: &Self::Set) -> Integer {
+        match x {
+            RealAlgebraic::Rational(x) => Floor::floor(x.clone()),
+            RealAlgebraic::Real(x) => x.clone().floor_mut(),
+        }
     }
-    fn ceil(&self, _x: &Self::Set) -> Integer {
-        todo!()
+    fn ceil(&self, x: &Self::Set) -> Integer {
+        match x {
+            RealAlgebraic::Rational(x) => Rational::ceiling(x.clone()),
+            RealAlgebraic::Real(x) => x.clone().floor_mut() + Integer::ONE,
+        }
     }
-    fn round(&self, _x: &Self::Set) -> Integer {
-        todo!()
+    fn round(&self, x: &Self::Set) -> Integer {
+        match x {
+            RealAlgebraic::Rational(x) => Floor::floor(x + Rational::ONE_HALF),
+            RealAlgebraic::Real(x) => {
+                let mut x = x.clone();
+                let a = x.floor_mut();
+                match x.cmp_rat_mut(&(Rational::from(&a) + Rational::ONE_HALF)) {
+                    std::cmp::Ordering::Less => a,
+                    _ => a + Integer::ONE,
+                }
+            }
+        }
     }
 }
 
@@ -940,4 +967,21 @@ mod tests {
         });
         assert_eq!(all_roots, all_roots_sorted_by_lower_tight_bound);
     }
+
+    #[test]
+    fn test_real_rounding() {
+        let x =
+            Polynomial::from_coeffs(vec![Integer::from(-13), Integer::from(0), Integer::from(1)]);
+        let roots = x.all_real_roots();
+        let (a, b) = (&roots[0], &roots[1]);
+        assert_eq!(a.floor(), Integer::from(-4));
+        assert_eq!(a.ceil(), Integer::from(-3));
+        assert_eq!(a.round(), Integer::from(-4));
+        assert_eq!(b.floor(), Integer::from(3));
+        assert_eq!(b.ceil(), Integer::from(4));
+        assert_eq!(b.round(), Integer::from(4));
+        let half = RealAlgebraic::Rational(Rational::from_signeds(-7, 2));
+        assert_eq!(half.floor(), Integer::from(-4));
+        assert_eq!(half.ceil(), Integer::from(-3));
+    }
 }
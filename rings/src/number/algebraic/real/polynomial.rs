@@ -655,6 +655,24 @@ impl Polynomial<Integer> {
         opt_b: Option<&Rational>,
         include_a: bool,
         include_b: bool,
+    ) -> SquarefreePolyRealRoots {
+        self.real_roots_squarefree_with(
+            opt_a,
+            opt_b,
+            include_a,
+            include_b,
+            RealRootIsolationAlgorithm::default(),
+        )
+    }
+
+    //isolate all real roots of a squarefree (no repeated roots) polynomial between a and b using the given algorithm
+    pub fn real_roots_squarefree_with(
+        self,
+        opt_a: Option<&Rational>,
+        opt_b: Option<&Rational>,
+        include_a: bool,
+        include_b: bool,
+        algorithm: RealRootIsolationAlgorithm,
     ) -> SquarefreePolyRealRoots {
         assert_ne!(self, Self::zero());
         //poly should be squarefree
@@ -715,29 +733,51 @@ impl Polynomial<Integer> {
             if opt_a.is_none() || opt_b.is_none() {
                 //compute a bound M on the absolute value of any root
                 //m = (Cauchy's bound + 1) https://captainblack.wordpress.com/2009/03/08/cauchys-upper-bound-for-the-roots-of-a-polynomial/
-                let m = Rational::from(2)
-                    + Rational::from_naturals(
-                        itertools::max((0..d).map(|i| self.coeff(i).unsigned_abs_ref().clone()))
-                            .unwrap(),
-                        self.coeff(d).unsigned_abs_ref().clone(),
-                    );
+                //the other algorithms use a power of two bound which is usually much smaller
+                let m = match algorithm {
+                    RealRootIsolationAlgorithm::Bisection => {
+                        Rational::from(2)
+                            + Rational::from_naturals(
+                                itertools::max(
+                                    (0..d).map(|i| self.coeff(i).unsigned_abs_ref().clone()),
+                                )
+                                .unwrap(),
+                                self.coeff(d).unsigned_abs_ref().clone(),
+                            )
+                    }
+                    _ => root_absolute_value_bound(&self),
+                };
 
                 debug_assert!(m > Rational::ZERO);
 
                 return match opt_a {
                     Some(a_val) => match opt_b {
                         Some(_b_val) => panic!(),
-                        None => {
-                            self.real_roots_squarefree(Some(a_val), Some(&m), include_a, include_b)
-                        }
+                        None => self.real_roots_squarefree_with(
+                            Some(a_val),
+                            Some(&m),
+                            include_a,
+                            include_b,
+                            algorithm,
+                        ),
                     },
                     None => match opt_b {
-                        Some(b_val) => {
-                            self.real_roots_squarefree(Some(&-m), Some(b_val), include_a, include_b)
-                        }
+                        Some(b_val) => self.real_roots_squarefree_with(
+                            Some(&-m),
+                            Some(b_val),
+                            include_a,
+                            include_b,
+                            algorithm,
+                        ),
                         None => {
                             let neg_m = -m.clone();
-                            self.real_roots_squarefree(Some(&neg_m), Some(&m), include_a, include_b)
+                            self.real_roots_squarefree_with(
+                                Some(&neg_m),
+                                Some(&m),
+                                include_a,
+                                include_b,
+                                algorithm,
+                            )
                         }
                     },
                 };
@@ -792,12 +832,33 @@ impl Polynomial<Integer> {
             )
             .factor_primitive_fof();
 
-            'interval_loop: for (c, k, h) in trans_poly.isolate_real_roots_by_collin_akritas() {
+            //isolating intervals [s, Some(t)] and rational roots (s, None) of trans_poly in (0, 1)
+            let dyadic = |(c, k, h): (Natural, usize, bool)| {
                 let d = Natural::from(1u8) << k;
-                let mut interval_a = (b - a) * Rational::from_naturals(c.clone(), d.clone()) + a;
-                if h {
-                    let mut interval_b =
-                        (b - a) * Rational::from_naturals(&c + Natural::from(1u8), d.clone()) + a;
+                let s = Rational::from_naturals(c.clone(), d.clone());
+                let t = h.then(|| Rational::from_naturals(c + Natural::from(1u8), d));
+                (s, t)
+            };
+            let unit_intervals: Vec<(Rational, Option<Rational>)> = match algorithm {
+                RealRootIsolationAlgorithm::Bisection => trans_poly
+                    .isolate_real_roots_by_collin_akritas()
+                    .into_iter()
+                    .map(dyadic)
+                    .collect(),
+                RealRootIsolationAlgorithm::ContinuedFraction => {
+                    trans_poly.isolate_real_roots_by_continued_fractions()
+                }
+                RealRootIsolationAlgorithm::BitstreamDescartes => trans_poly
+                    .isolate_real_roots_by_bitstream_descartes()
+                    .into_iter()
+                    .map(dyadic)
+                    .collect(),
+            };
+
+            'interval_loop: for (s, t) in unit_intervals {
+                let mut interval_a = (b - a) * s + a;
+                if let Some(t) = t {
+                    let mut interval_b = (b - a) * t + a;
 
                     //at the moment, interval_a and interval_b might be rational roots
                     //we need to strink them a little bit if so
@@ -888,12 +949,34 @@ impl Polynomial<Integer> {
         b: Option<&Rational>,
         include_a: bool,
         include_b: bool,
+    ) -> Vec<RealAlgebraic> {
+        self.real_roots_with(
+            a,
+            b,
+            include_a,
+            include_b,
+            RealRootIsolationAlgorithm::default(),
+        )
+    }
+
+    //get the real roots with multiplicity of poly, isolating them using the given algorithm
+    pub fn real_roots_with(
+        &self,
+        a: Option<&Rational>,
+        b: Option<&Rational>,
+        include_a: bool,
+        include_b: bool,
+        algorithm: RealRootIsolationAlgorithm,
     ) -> Vec<RealAlgebraic> {
         assert_ne!(self, &Self::zero());
         let factors = self.factor().unwrap();
         let mut roots = vec![];
         for (factor, k) in factors.factors() {
-            for root in factor.real_roots_irreducible(a, b, include_a, include_b) {
+            for root in factor
+                .clone()
+                .real_roots_squarefree_with(a, b, include_a, include_b, algorithm)
+                .to_real_roots()
+            {
                 let mut i = Natural::from(0u8);
                 while &i < k {
                     roots.push(root.clone());
@@ -907,6 +990,10 @@ impl Polynomial<Integer> {
     pub fn all_real_roots(&self) -> Vec<RealAlgebraic> {
         self.real_roots(None, None, false, false)
     }
+
+    pub fn all_real_roots_with(&self, algorithm: RealRootIsolationAlgorithm) -> Vec<RealAlgebraic> {
+        self.real_roots_with(None, None, false, false, algorithm)
+    }
 }
 
 pub fn nth_root(x: &RealAlgebraic, n: usize) -> Result<RealAlgebraic, ()> {
@@ -47,6 +47,224 @@ impl ComplexAlgebraic {
     }
 }
 
+// rectangles [a, b] x [c, d] in the complex plane and their arithmetic
+type ComplexBox = ((Rational, Rational), (Rational, Rational));
+
+fn interval_mul(a: &(Rational, Rational), b: &(Rational, Rational)) -> (Rational, Rational) {
+    let prods = [&a.0 * &b.0, &a.0 * &b.1, &a.1 * &b.0, &a.1 * &b.1];
+    (
+        prods.iter().min().unwrap().clone(),
+        prods.iter().max().unwrap().clone(),
+    )
+}
+
+fn box_add(a: &ComplexBox, b: &ComplexBox) -> ComplexBox {
+    (
+        (&a.0 .0 + &b.0 .0, &a.0 .1 + &b.0 .1),
+        (&a.1 .0 + &b.1 .0, &a.1 .1 + &b.1 .1),
+    )
+}
+
+fn box_mul(a: &ComplexBox, b: &ComplexBox) -> ComplexBox {
+    let re_re = interval_mul(&a.0, &b.0);
+    let im_im = interval_mul(&a.1, &b.1);
+    let re_im = interval_mul(&a.0, &b.1);
+    let im_re = interval_mul(&a.1, &b.0);
+    (
+        (&re_re.0 - &im_im.1, &re_re.1 - &im_im.0),
+        (&re_im.0 + &im_re.0, &re_im.1 + &im_re.1),
+    )
+}
+
+fn box_rat(x: &Rational) -> ComplexBox {
+    ((x.clone(), x.clone()), (Rational::ZERO, Rational::ZERO))
+}
+
+// evaluate a polynomial with box coefficients at a box using Horner's method
+fn box_evaluate(coeffs: &[ComplexBox], x: &ComplexBox) -> ComplexBox {
+    let mut val = box_rat(&Rational::ZERO);
+    for c in coeffs.iter().rev() {
+        val = box_add(&box_mul(&val, x), c);
+    }
+    val
+}
+
+impl EmbeddedAnf {
+    pub fn anf(&self) -> Rc<ANFStructure> {
+        self.anf.clone()
+    }
+
+    pub fn generator(&self) -> &ComplexAlgebraic {
+        &self.gen
+    }
+
+    /// The image of an element of the number field under the embedding
+    pub fn embed(&self, a: &Polynomial<Rational>) -> ComplexAlgebraic {
+        self.gen.clone().apply_poly(&self.anf.reduce(a))
+    }
+
+    // Given the roots of an irreducible polynomial q over Q and a divisor h of q in K[x],
+    // return those roots which are roots of the image of h under the embedding.
+    // Roots of q which are not roots of h are excluded by evaluating h using interval arithmetic until exactly deg(h) remain.
+    fn select_roots(
+        &self,
+        h: &Polynomial<Polynomial<Rational>>,
+        candidates: Vec<ComplexAlgebraic>,
+    ) -> Vec<ComplexAlgebraic> {
+        let target = h.degree().unwrap();
+        let mut gen = self.gen.clone();
+        let mut alive = candidates;
+        let mut accuracy = Rational::ONE;
+        while alive.len() > target {
+            let gen_box = gen.rational_enclosure_mut(&accuracy);
+            let h_boxes = h
+                .coeffs()
+                .into_iter()
+                .map(|c| {
+                    box_evaluate(
+                        &c.coeffs().into_iter().map(box_rat).collect::<Vec<_>>(),
+                        &gen_box,
+                    )
+                })
+                .collect::<Vec<_>>();
+            alive.retain_mut(|z| {
+                let val = box_evaluate(&h_boxes, &z.rational_enclosure_mut(&accuracy));
+                val.0 .0 <= Rational::ZERO
+                    && Rational::ZERO <= val.0 .1
+                    && val.1 .0 <= Rational::ZERO
+                    && Rational::ZERO <= val.1 .1
+            });
+            debug_assert!(alive.len() >= target);
+            accuracy /= Rational::from(2);
+        }
+        alive
+    }
+
+    // The roots of the image of f under the embedding, with multiplicity, only the real ones if real_only.
+    //
+    // The roots of the norm N(f) in Q[x] are the roots of all the conjugates of f. For each irreducible factor q of N(f)
+    // the common roots of q and f of multiplicity greater than k are the roots of h_k where h_0 = gcd(f, q) and h_k = gcd(f^(k), h_(k-1)) in K[x].
+    fn roots_impl(
+        &self,
+        f: &Polynomial<Polynomial<Rational>>,
+        real_only: bool,
+    ) -> Vec<ComplexAlgebraic> {
+        let anf_poly = PolynomialStructure::new(self.anf.clone());
+        let f = f.apply_map(|c| self.anf.reduce(c));
+        assert!(!anf_poly.is_zero(&f));
+        let norm = anf_poly.polynomial_norm(&f);
+        let mut roots = vec![];
+        for (q, _) in norm.primitive_part_fof().factor().unwrap().factors() {
+            let deg_q = q.degree().unwrap();
+            let mut q_roots: Option<Vec<ComplexAlgebraic>> = None;
+            let all_q_roots = |q_roots: &mut Option<Vec<ComplexAlgebraic>>| {
+                q_roots
+                    .get_or_insert_with(|| q.all_complex_roots_irreducible())
+                    .clone()
+            };
+            // None while every root of q is a root of h_k
+            let mut level: Option<Vec<ComplexAlgebraic>> = None;
+            let mut fk = f.clone();
+            let mut hk = anf_poly.gcd_by_modular_algorithm(
+                &f,
+                &q.apply_map(|c| Polynomial::constant(Rational::from(c))),
+            );
+            while hk.degree().unwrap() > 0 {
+                if hk.degree().unwrap() < deg_q {
+                    let candidates = match level.take() {
+                        Some(candidates) => candidates,
+                        None => all_q_roots(&mut q_roots),
+                    };
+                    level = Some(self.select_roots(&hk, candidates));
+                }
+                match &level {
+                    Some(level) => roots.extend(
+                        level
+                            .iter()
+                            .filter(|z| !real_only || matches!(z, ComplexAlgebraic::Real(_)))
+                            .cloned(),
+                    ),
+                    None => {
+                        if real_only && q_roots.is_none() {
+                            roots.extend(
+                                q.real_roots_irreducible(None, None, false, false)
+                                    .into_iter()
+                                    .map(ComplexAlgebraic::Real),
+                            );
+                        } else {
+                            roots.extend(
+                                all_q_roots(&mut q_roots).into_iter().filter(|z| {
+                                    !real_only || matches!(z, ComplexAlgebraic::Real(_))
+                                }),
+                            );
+                        }
+                    }
+                }
+                fk = anf_poly.derivative(fk);
+                hk = anf_poly.gcd_by_modular_algorithm(&fk, &hk);
+            }
+        }
+        roots
+    }
+
+    /// The complex roots, with multiplicity, of the image of f under the embedding
+    pub fn all_complex_roots(&self, f: &Polynomial<Polynomial<Rational>>) -> Vec<ComplexAlgebraic> {
+        self.roots_impl(f, false)
+    }
+
+    /// The real roots, with multiplicity, of the image of f under the embedding
+    pub fn all_real_roots(&self, f: &Polynomial<Polynomial<Rational>>) -> Vec<RealAlgebraic> {
+        self.roots_impl(f, true)
+            .into_iter()
+            .map(|z| match z {
+                ComplexAlgebraic::Real(x) => x,
+                ComplexAlgebraic::Complex(_) => unreachable!(),
+            })
+            .collect()
+    }
+}
+
+impl PolynomialStructure<CannonicalStructure<ComplexAlgebraic>> {
+    // Write f as the image of a polynomial over a number field under an embedding
+    fn as_embedded_anf_polynomial(
+        &self,
+        f: &Polynomial<ComplexAlgebraic>,
+    ) -> (EmbeddedAnf, Polynomial<Polynomial<Rational>>) {
+        assert!(!self.is_zero(f));
+        let (gen, coeffs) = anf_multi_primitive_element_theorem(f.coeffs());
+        (
+            gen.embedded_generated_algebraic_number_field(),
+            Polynomial::from_coeffs(coeffs),
+        )
+    }
+
+    /// The complex roots of f with multiplicity
+    pub fn all_complex_roots(&self, f: &Polynomial<ComplexAlgebraic>) -> Vec<ComplexAlgebraic> {
+        let (anf, f) = self.as_embedded_anf_polynomial(f);
+        anf.all_complex_roots(&f)
+    }
+
+    /// The real roots of f with multiplicity
+    pub fn all_real_roots(&self, f: &Polynomial<ComplexAlgebraic>) -> Vec<RealAlgebraic> {
+        let (anf, f) = self.as_embedded_anf_polynomial(f);
+        anf.all_real_roots(&f)
+    }
+}
+
+impl PolynomialStructure<CannonicalStructure<RealAlgebraic>> {
+    /// The complex roots of f with multiplicity
+    pub fn all_complex_roots(&self, f: &Polynomial<RealAlgebraic>) -> Vec<ComplexAlgebraic> {
+        Polynomial::<ComplexAlgebraic>::structure()
+            .all_complex_roots(&f.apply_map(|c| ComplexAlgebraic::Real(c.clone())))
+    }
+
+    /// The real roots of f with multiplicity
+    pub fn all_real_roots(&self, f: &Polynomial<RealAlgebraic>) -> Vec<RealAlgebraic> {
+        Polynomial::<ComplexAlgebraic>::structure()
+            .all_real_roots(&f.apply_map(|c| ComplexAlgebraic::Real(c.clone())))
+    }
+}
+
 //TODO
 // use complex::*;
 // use  real::*;
@@ -330,4 +548,114 @@ mod tests {
         assert_eq!(sqrt_three, g.apply_poly(&p[1]));
         assert_eq!(sqrt_six, g.apply_poly(&p[2]));
     }
+
+    #[test]
+    fn test_embedded_anf_roots() {
+        // K = Q[θ] with θ^3 = 2 and f = x^2 - θ
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+        let anf = new_anf((x.pow(3) - 2).into_verbose());
+        let theta = Polynomial::<Rational>::var();
+        let f = Polynomial::from_coeffs(vec![
+            Polynomial::neg(&theta),
+            Polynomial::zero(),
+            Polynomial::one(),
+        ]);
+        let sixth_root_two = RealAlgebraic::Rational(Rational::from(2))
+            .nth_root(6)
+            .unwrap();
+        let embeddings = anf.all_complex_embeddings();
+        assert_eq!(embeddings.len(), 3);
+        for emb in embeddings {
+            let roots = emb.all_complex_roots(&f);
+            assert_eq!(roots.len(), 2);
+            for root in &roots {
+                assert_eq!(ComplexAlgebraic::mul(root, root), emb.embed(&theta));
+            }
+            let real_roots = emb.all_real_roots(&f);
+            match emb.generator() {
+                ComplexAlgebraic::Real(_) => {
+                    assert_eq!(real_roots.len(), 2);
+                    assert!(real_roots.contains(&sixth_root_two));
+                    assert!(real_roots.contains(&RealAlgebraic::neg(&sixth_root_two)));
+                }
+                ComplexAlgebraic::Complex(_) => {
+                    assert_eq!(real_roots.len(), 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_real_algebraic_coefficient_roots() {
+        let rat = |n: i64| RealAlgebraic::Rational(Rational::from(n));
+        let sqrt_two = rat(2).nth_root(2).unwrap();
+        let sqrt_three = rat(3).nth_root(2).unwrap();
+        let poly_structure = Polynomial::<RealAlgebraic>::structure();
+
+        // x^2 - √2
+        let f = Polynomial::from_coeffs(vec![RealAlgebraic::neg(&sqrt_two), rat(0), rat(1)]);
+        let fourth_root_two = rat(2).nth_root(4).unwrap();
+        let mut roots = poly_structure.all_real_roots(&f);
+        roots.sort();
+        assert_eq!(
+            roots,
+            vec![
+                RealAlgebraic::neg(&fourth_root_two),
+                fourth_root_two.clone()
+            ]
+        );
+        assert_eq!(poly_structure.all_complex_roots(&f).len(), 2);
+
+        // (x - √2)^2 (x + √3)
+        let f = Polynomial::mul(
+            &Polynomial::from_coeffs(vec![RealAlgebraic::neg(&sqrt_two), rat(1)]),
+            &Polynomial::mul(
+                &Polynomial::from_coeffs(vec![RealAlgebraic::neg(&sqrt_two), rat(1)]),
+                &Polynomial::from_coeffs(vec![sqrt_three.clone(), rat(1)]),
+            ),
+        );
+        let mut roots = poly_structure.all_real_roots(&f);
+        roots.sort();
+        assert_eq!(
+            roots,
+            vec![
+                RealAlgebraic::neg(&sqrt_three),
+                sqrt_two.clone(),
+                sqrt_two.clone()
+            ]
+        );
+
+        // x^2 + 1 has no real roots
+        let f = Polynomial::from_coeffs(vec![rat(1), rat(0), rat(1)]);
+        assert_eq!(poly_structure.all_real_roots(&f), vec![]);
+    }
+
+    #[test]
+    fn test_complex_algebraic_coefficient_roots() {
+        let i = ComplexAlgebraic::i();
+        let poly_structure = Polynomial::<ComplexAlgebraic>::structure();
+        // x^2 - 2ix - 1 = (x - i)^2
+        let f = Polynomial::from_coeffs(vec![
+            ComplexAlgebraic::one().neg(),
+            ComplexAlgebraic::mul(&i, &ComplexAlgebraic::from_int(&Integer::from(-2))),
+            ComplexAlgebraic::one(),
+        ]);
+        assert_eq!(
+            poly_structure.all_complex_roots(&f),
+            vec![i.clone(), i.clone()]
+        );
+        assert_eq!(poly_structure.all_real_roots(&f), vec![]);
+
+        // (x - i)(x - 2) = x^2 - (2 + i)x + 2i
+        let f = Polynomial::from_coeffs(vec![
+            ComplexAlgebraic::mul(&i, &ComplexAlgebraic::from_int(&Integer::from(2))),
+            ComplexAlgebraic::add(&i, &ComplexAlgebraic::from_int(&Integer::from(2))).neg(),
+            ComplexAlgebraic::one(),
+        ]);
+        assert_eq!(
+            poly_structure.all_real_roots(&f),
+            vec![RealAlgebraic::Rational(Rational::from(2))]
+        );
+        assert_eq!(poly_structure.all_complex_roots(&f).len(), 2);
+    }
 }
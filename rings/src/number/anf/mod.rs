@@ -1,2 +1,3 @@
+pub mod modular_gcd;
 pub mod number_field;
 pub mod embedded_anf;
@@ -0,0 +1,273 @@
+use malachite_base::num::arithmetic::traits::Lcm;
+use malachite_base::num::basic::traits::One;
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+
+use super::number_field::ANFStructure;
+use crate::number::integer::modular_gcd::{
+    inv_mod, quorem_mod_p, residue, trim_mod_p, word_primes,
+};
+use crate::number::natural::functions::{chinese_remainder, rational_reconstruction};
+use crate::polynomial::polynomial::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+//arithmetic in (Z/p)[z]/(m(z)) for a monic m of degree d, whose elements are coefficient vectors of length d
+
+fn sub_mod_p(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+    let mut c = (0..std::cmp::max(a.len(), b.len()))
+        .map(|i| (a.get(i).unwrap_or(&0) + p - b.get(i).unwrap_or(&0)) % p)
+        .collect();
+    trim_mod_p(&mut c);
+    c
+}
+
+fn mul_mod_p(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut c = vec![0; a.len() + b.len() - 1];
+    for (i, ai) in a.iter().enumerate() {
+        for (j, bj) in b.iter().enumerate() {
+            c[i + j] = (c[i + j] + ai * bj) % p;
+        }
+    }
+    trim_mod_p(&mut c);
+    c
+}
+
+fn ext_mul(x: &[u64], y: &[u64], m: &[u64], p: u64) -> Vec<u64> {
+    let d = m.len() - 1;
+    let mut r = quorem_mod_p(&mul_mod_p(x, y, p), m, p).1;
+    r.resize(d, 0);
+    r
+}
+
+fn ext_sub(x: &[u64], y: &[u64], p: u64) -> Vec<u64> {
+    x.iter().zip(y).map(|(a, b)| (a + p - b) % p).collect()
+}
+
+//None if x is a zero divisor
+fn ext_inv(x: &[u64], m: &[u64], p: u64) -> Option<Vec<u64>> {
+    let mut x = x.to_vec();
+    trim_mod_p(&mut x);
+    let (mut r0, mut r1) = (m.to_vec(), x);
+    let (mut t0, mut t1) = (vec![], vec![1]);
+    while !r1.is_empty() {
+        let (q, r) = quorem_mod_p(&r0, &r1, p);
+        let t = sub_mod_p(&t0, &mul_mod_p(&q, &t1, p), p);
+        (r0, r1) = (r1, r);
+        (t0, t1) = (t1, t);
+    }
+    if r0.len() != 1 {
+        return None;
+    }
+    let c = inv_mod(r0[0], p);
+    let mut inv = t0.into_iter().map(|t| t * c % p).collect::<Vec<_>>();
+    inv.resize(m.len() - 1, 0);
+    Some(inv)
+}
+
+//the monic gcd over (Z/p)[z]/(m(z)) of nonzero a and b, or None if a zero divisor is met
+fn ext_monic_gcd(
+    mut a: Vec<Vec<u64>>,
+    mut b: Vec<Vec<u64>>,
+    m: &[u64],
+    p: u64,
+) -> Option<Vec<Vec<u64>>> {
+    let trim = |f: &mut Vec<Vec<u64>>| {
+        while f.last().is_some_and(|c| c.iter().all(|x| *x == 0)) {
+            f.pop();
+        }
+    };
+    trim(&mut a);
+    trim(&mut b);
+    while !b.is_empty() {
+        let lc_inv = ext_inv(b.last().unwrap(), m, p)?;
+        let mut r = a;
+        while r.len() >= b.len() {
+            let shift = r.len() - b.len();
+            let c = ext_mul(r.last().unwrap(), &lc_inv, m, p);
+            for (i, bi) in b.iter().enumerate() {
+                r[shift + i] = ext_sub(&r[shift + i], &ext_mul(&c, bi, m, p), p);
+            }
+            trim(&mut r);
+        }
+        (a, b) = (b, r);
+    }
+    let lc_inv = ext_inv(a.last().unwrap(), m, p)?;
+    Some(a.iter().map(|c| ext_mul(c, &lc_inv, m, p)).collect())
+}
+
+impl PolynomialStructure<ANFStructure> {
+    /// The monic gcd of a and b by the modular algorithm of Langemyr, McCallum and Encarnación.
+    /// Images of the gcd over (Z/p)\[α\]/(m(α)) for primes p are combined by the chinese remainder theorem and the rational coordinates of its coefficients are recovered by rational reconstruction.
+    pub fn gcd_by_modular_algorithm(
+        &self,
+        a: &Polynomial<Polynomial<Rational>>,
+        b: &Polynomial<Polynomial<Rational>>,
+    ) -> Polynomial<Polynomial<Rational>> {
+        if self.is_zero(a) {
+            return self.factor_fav_assoc(b).1;
+        }
+        if self.is_zero(b) {
+            return self.factor_fav_assoc(a).1;
+        }
+        let anf = self.coeff_ring();
+        let a = self.factor_fav_assoc(a).1;
+        let b = self.factor_fav_assoc(b).1;
+        if self.degree(&a) == Some(0) || self.degree(&b) == Some(0) {
+            return self.one();
+        }
+        let m = anf.modulus();
+        let m_lc = m.leading_coeff().unwrap();
+        let m = m.apply_map(|c| c / &m_lc);
+        let d = m.degree().unwrap();
+
+        //avoid primes dividing a denominator or the discriminant of m, so that reduction modulo p is well defined
+        //and the image of the gcd divides the gcd of the images
+        let disc = m.clone().discriminant().unwrap_or(Rational::ONE);
+        let denominators = m
+            .coeffs()
+            .into_iter()
+            .chain(
+                a.coeffs()
+                    .into_iter()
+                    .chain(b.coeffs())
+                    .flat_map(|c| c.coeffs()),
+            )
+            .fold(Natural::ONE, |den, c| den.lcm(c.denominator_ref()));
+        let bad = Integer::from(denominators) * Integer::from(disc.numerator_ref());
+
+        //the degree, the coordinates of the coefficients modulo the product of the primes used so far, and that product
+        let mut image: Option<(usize, Vec<Vec<Integer>>, Natural)> = None;
+        let mut previous: Option<Polynomial<Polynomial<Rational>>> = None;
+        for p in word_primes() {
+            if residue(&bad, p) == 0 {
+                continue;
+            }
+            let reduce_rat = |c: &Rational| {
+                let n = Integer::from_sign_and_abs_ref(*c >= 0, c.numerator_ref());
+                residue(&n, p) * inv_mod(residue(&Integer::from(c.denominator_ref()), p), p) % p
+            };
+            let reduce_elem = |c: &Polynomial<Rational>| {
+                let mut v = c.coeffs().into_iter().map(reduce_rat).collect::<Vec<_>>();
+                v.resize(d, 0);
+                v
+            };
+            let reduce = |f: &Polynomial<Polynomial<Rational>>| {
+                f.coeffs().into_iter().map(reduce_elem).collect::<Vec<_>>()
+            };
+            let m_p = m.coeffs().into_iter().map(reduce_rat).collect::<Vec<_>>();
+            let g = match ext_monic_gcd(reduce(&a), reduce(&b), &m_p, p) {
+                Some(g) => g,
+                None => {
+                    continue;
+                }
+            };
+            let e = g.len() - 1;
+            if e == 0 {
+                return self.one();
+            }
+            let g = g
+                .into_iter()
+                .map(|c| c.into_iter().map(Integer::from).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            match &mut image {
+                Some((f, _, _)) if e > *f => {
+                    continue;
+                }
+                Some((f, coeffs, modulus)) if e == *f => {
+                    for (c, gc) in coeffs.iter_mut().zip(g) {
+                        for (x, y) in c.iter_mut().zip(gc) {
+                            let (z, _) = chinese_remainder(&[
+                                (x.clone(), modulus.clone()),
+                                (y, Natural::from(p)),
+                            ])
+                            .unwrap();
+                            *x = Integer::from(z);
+                        }
+                    }
+                    *modulus *= Natural::from(p);
+                }
+                _ => {
+                    image = Some((e, g, Natural::from(p)));
+                }
+            }
+
+            let (_e, coeffs, modulus) = image.as_ref().unwrap();
+            let candidate = coeffs
+                .iter()
+                .map(|c| {
+                    Some(
+                        anf.reduce(&Polynomial::from_coeffs(
+                            c.iter()
+                                .map(|x| rational_reconstruction(x, modulus))
+                                .collect::<Option<Vec<_>>>()?,
+                        )),
+                    )
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(Polynomial::from_coeffs);
+            if let (Some(candidate), Some(previous)) = (&candidate, &previous) {
+                if self.equal(candidate, previous)
+                    && self.div(&a, candidate).is_ok()
+                    && self.div(&b, candidate).is_ok()
+                {
+                    return candidate.clone();
+                }
+            }
+            previous = candidate;
+        }
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::anf::number_field::new_anf;
+    use crate::structure::elements::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_gcd_by_modular_algorithm() {
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+        //K = Q(a) with a^3 = 2
+        let anf = Rc::new(new_anf((x.pow(3) - 2).into_verbose()));
+        let poly_ring = PolynomialStructure::new(anf.clone());
+        let a = Polynomial::<Rational>::var();
+        let c = |v: Vec<Rational>| anf.reduce(&Polynomial::from_coeffs(v));
+        let r = |n: i64, d: i64| Rational::from_integers(Integer::from(n), Integer::from(d));
+
+        //(y - a)(y + a/3 + 1/2), (y - a)(y^2 + 5), (y^2 + a^2 y - 7/2)
+        let f = Polynomial::from_coeffs(vec![anf.neg(&a), anf.one()]);
+        let g = Polynomial::from_coeffs(vec![c(vec![r(1, 2), r(1, 3)]), anf.one()]);
+        let h = Polynomial::from_coeffs(vec![c(vec![r(5, 1)]), anf.zero(), anf.one()]);
+        let k = Polynomial::from_coeffs(vec![
+            c(vec![r(-7, 2)]),
+            c(vec![r(0, 1), r(0, 1), r(1, 1)]),
+            anf.one(),
+        ]);
+
+        let fg = poly_ring.mul(&f, &g);
+        let fh = poly_ring.mul(&f, &h);
+        assert!(poly_ring.equal(&poly_ring.gcd_by_modular_algorithm(&fg, &fh), &f));
+        let fgk = poly_ring.mul(&fg, &k);
+        let gk = poly_ring.mul(&poly_ring.mul_scalar(&g, &c(vec![r(3, 1), r(-1, 4)])), &k);
+        let expected = poly_ring.mul(&g, &k);
+        assert!(poly_ring.equal(&poly_ring.gcd_by_modular_algorithm(&fgk, &gk), &expected));
+        assert!(poly_ring.equal(
+            &poly_ring.gcd_by_modular_algorithm(&fgk, &gk),
+            &poly_ring.euclidean_gcd(fgk.clone(), gk.clone())
+        ));
+        assert!(poly_ring.equal(
+            &poly_ring.gcd_by_modular_algorithm(&g, &h),
+            &poly_ring.one()
+        ));
+        assert!(poly_ring.equal(
+            &poly_ring.gcd_by_modular_algorithm(&poly_ring.zero(), &gk),
+            &expected
+        ));
+    }
+}
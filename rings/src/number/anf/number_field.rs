@@ -159,6 +159,54 @@ impl ANFStructure {
         debug_assert!(self.is_algebraic_integer(&b));
         b
     }
+
+    /// For a monic irreducible f over this field K, the field L = K(b) where f(b) = 0 given by a primitive element,
+    /// together with the image of the generator of K in L and the root b in L.
+    pub fn adjoin_root(
+        &self,
+        f: &Polynomial<Polynomial<Rational>>,
+    ) -> (ANFStructure, Polynomial<Rational>, Polynomial<Rational>) {
+        let modulus = self.modulus();
+        if self.degree() == 1 {
+            let alpha = Polynomial::constant(-modulus.coeff(0) / modulus.coeff(1));
+            let g = f.apply_map(|c| Polynomial::evaluate(c, &alpha.coeff(0)));
+            return (new_anf(g), alpha, Polynomial::var());
+        }
+        let poly_ring = PolynomialStructure::new(self.clone().into());
+        let alpha = self.reduce(&Polynomial::var());
+        //find s such that the norm of f(x - s alpha) is squarefree, so that it is the minimal polynomial of g = b + s alpha
+        for s in (0..).flat_map(|k: i64| [k, -k - 1]) {
+            let s = Rational::from(s);
+            let shift = Polynomial::from_coeffs(vec![
+                self.neg(&Polynomial::mul(&alpha, &Polynomial::constant(s.clone()))),
+                self.one(),
+            ]);
+            let norm = poly_ring.polynomial_norm(&poly_ring.compose(f, &shift));
+            if Polynomial::gcd(&norm, &norm.clone().derivative()).degree() != Some(0) {
+                continue;
+            }
+            let l = new_anf(norm);
+            let l_poly = PolynomialStructure::new(l.clone().into());
+            //alpha is the unique common root z of the minimal polynomial of alpha and f^z(g - s z), where f^z is f with alpha replaced by z
+            let g_minus_sz = Polynomial::from_coeffs(vec![
+                l.reduce(&Polynomial::var()),
+                Polynomial::constant(-&s),
+            ]);
+            let f_z = f.apply_map(|c| c.apply_map(|x| Polynomial::constant(x.clone())));
+            let common = l_poly.gcd_by_modular_algorithm(
+                &modulus.apply_map(|x| Polynomial::constant(x.clone())),
+                &PolynomialStructure::new(l_poly.clone().into()).evaluate(&f_z, &g_minus_sz),
+            );
+            debug_assert_eq!(common.degree(), Some(1));
+            let alpha_img = l.neg(&common.coeff(0));
+            let root = l.add(
+                &l.reduce(&Polynomial::var()),
+                &l.mul(&Polynomial::constant(-s), &alpha_img),
+            );
+            return (l, alpha_img, root);
+        }
+        unreachable!()
+    }
 }
 
 impl CharZeroStructure for ANFStructure {}
@@ -371,9 +419,9 @@ impl PolynomialStructure<ANFStructure> {
             // println!("ti = {}", ti);
             debug_assert_eq!(ti_pow, &Natural::ONE);
             p_factors.push(
-                self.euclidean_gcd(
-                    p.clone(),
-                    ti.apply_map(|c| Polynomial::constant(Polynomial::constant(c.clone())))
+                self.gcd_by_modular_algorithm(
+                    p,
+                    &ti.apply_map(|c| Polynomial::constant(Polynomial::constant(c.clone())))
                         .evaluate(&Polynomial::from_coeffs(vec![
                             anf.mul(&anf.from_int(&Integer::from(k)), &theta),
                             anf.one(),
@@ -863,4 +911,18 @@ mod tests {
             12
         );
     }
+
+    #[test]
+    fn test_adjoin_root() {
+        //a square root of sqrt(2) over Q(sqrt(2))
+        let y = &Polynomial::<Rational>::var().into_ergonomic();
+        let k = new_anf((y.pow(2) - 2).into_verbose());
+        let sqrt2 = k.reduce(&Polynomial::var());
+        let f = Polynomial::from_coeffs(vec![k.neg(&sqrt2), k.zero(), k.one()]);
+        let (l, alpha, b) = k.adjoin_root(&f);
+        assert_eq!(l.degree(), 4);
+        let two = Polynomial::constant(Rational::from(2));
+        assert!(l.equal(&l.mul(&alpha, &alpha), &two));
+        assert!(l.equal(&l.mul(&b, &b), &alpha));
+    }
 }
@@ -0,0 +1,292 @@
+use std::rc::Rc;
+
+use algebraeon_sets::structure::*;
+use malachite_base::num::arithmetic::traits::Pow;
+use malachite_base::num::basic::traits::{One, Zero};
+use malachite_nz::{integer::Integer, natural::Natural};
+
+use crate::{
+    number::natural::{factor::factor, nat_to_usize},
+    polynomial::{polynomial::*, quotient::ModularComposition},
+    structure::{quotient::QuotientStructure, structure::*},
+};
+
+impl<FS: FiniteFieldStructure> PolynomialStructure<FS> {
+    fn field_size(&self) -> Natural {
+        let (p, k) = self.coeff_ring().characteristic_and_power();
+        p.nat_pow(&k)
+    }
+
+    //x^(q^i) mod f for i = 0, 1, ..., n, each obtained from the previous one by modular composition with x^q
+    fn frobenius_powers_of_var(
+        &self,
+        f: &Polynomial<FS::Set>,
+        n: usize,
+    ) -> Vec<Polynomial<FS::Set>> {
+        let modulo_f = QuotientStructure::new_ring(Rc::new(self.clone()), f.clone());
+        let xq = modulo_f.nat_pow(&self.var(), &self.field_size());
+        let frobenius = ModularComposition::new(&modulo_f, &xq);
+        let mut powers = vec![modulo_f.reduce(&self.var())];
+        for i in 0..n {
+            powers.push(frobenius.compose(&powers[i]));
+        }
+        powers
+    }
+
+    //whether gcd(f, x^(q^i) - x) = 1 given x^(q^i) mod f
+    fn coprime_to_frobenius_difference(
+        &self,
+        f: &Polynomial<FS::Set>,
+        xqi: &Polynomial<FS::Set>,
+    ) -> bool {
+        let g = self.gcd(f, &self.add(xqi, &self.neg(&self.var())));
+        self.degree(&g) == Some(0)
+    }
+
+    /// Rabin's irreducibility test: f of degree n is irreducible if and only if f divides x^(q^n) - x
+    /// and f is coprime to x^(q^(n/r)) - x for each prime r dividing n.
+    pub fn is_irreducible_rabin(&self, f: &Polynomial<FS::Set>) -> bool {
+        let n = match self.degree(f) {
+            None | Some(0) => return false,
+            Some(n) => n,
+        };
+        let powers = self.frobenius_powers_of_var(f, n);
+        if !self.equal(&powers[n], &powers[0]) {
+            return false;
+        }
+        factor(Natural::from(n))
+            .unwrap()
+            .distinct_prime_factors()
+            .into_iter()
+            .all(|r| self.coprime_to_frobenius_difference(f, &powers[n / nat_to_usize(r).unwrap()]))
+    }
+
+    /// Ben-Or's irreducibility test: f of degree n is irreducible if and only if it is coprime to x^(q^i) - x for each i <= n/2.
+    /// It stops early at the smallest degree of an irreducible factor, so it is fast on random reducible polynomials.
+    pub fn is_irreducible_ben_or(&self, f: &Polynomial<FS::Set>) -> bool {
+        let n = match self.degree(f) {
+            None | Some(0) => return false,
+            Some(n) => n,
+        };
+        let modulo_f = QuotientStructure::new_ring(Rc::new(self.clone()), f.clone());
+        let xq = modulo_f.nat_pow(&self.var(), &self.field_size());
+        let frobenius = ModularComposition::new(&modulo_f, &xq);
+        let mut xqi = modulo_f.reduce(&self.var());
+        for _i in 1..=(n / 2) {
+            xqi = frobenius.compose(&xqi);
+            if !self.coprime_to_frobenius_difference(f, &xqi) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The number of monic irreducible polynomials of degree n, which is (1/n) sum over d dividing n of μ(d) q^(n/d).
+    pub fn count_monic_irreducibles(&self, n: usize) -> Natural {
+        if n == 0 {
+            return Natural::ZERO;
+        }
+        let q = Integer::from(self.field_size());
+        let mut total = Integer::ZERO;
+        for d in (1..=n).filter(|d| n.is_multiple_of(*d)) {
+            let mu = factor(Natural::from(d)).unwrap().mobius();
+            total += Integer::from(mu) * q.clone().pow((n / d) as u64);
+        }
+        Natural::try_from(total / Integer::from(n)).unwrap()
+    }
+
+    /// Whether f is a primitive polynomial: monic, irreducible, and such that x generates the multiplicative group of F_q\[x\]/f,
+    /// that is x^((q^n - 1)/r) is not 1 modulo f for each prime r dividing q^n - 1.
+    pub fn is_primitive_polynomial(&self, f: &Polynomial<FS::Set>) -> bool {
+        let field = self.coeff_ring();
+        if !self.is_monic(f) || field.is_zero(self.coeff(f, 0)) || !self.is_irreducible_rabin(f) {
+            return false;
+        }
+        let n = self.degree(f).unwrap();
+        let order = self.field_size().nat_pow(&Natural::from(n)) - Natural::ONE;
+        let modulo_f = QuotientStructure::new_ring(Rc::new(self.clone()), f.clone());
+        let x = modulo_f.reduce(&self.var());
+        factor(order.clone())
+            .unwrap()
+            .distinct_prime_factors()
+            .into_iter()
+            .all(|r| !modulo_f.equal(&modulo_f.nat_pow(&x, &(&order / r)), &modulo_f.one()))
+    }
+
+    /// The first primitive polynomial of degree n >= 1, in the order which lists the monic polynomials x^n + c_(n-1) x^(n-1) + ... + c_0
+    /// by reading c_(n-1) ... c_0 as digits, with the elements of the field ordered as in all_elements().
+    pub fn first_primitive_polynomial(&self, n: usize) -> Polynomial<FS::Set> {
+        assert!(n >= 1);
+        let field = self.coeff_ring();
+        let elements = field.all_elements();
+        let q = elements.len();
+        let mut digits = vec![0; n];
+        loop {
+            let mut coeffs = digits
+                .iter()
+                .map(|i| elements[*i].clone())
+                .collect::<Vec<_>>();
+            coeffs.push(field.one());
+            let f = Polynomial::from_coeffs(coeffs);
+            if self.is_primitive_polynomial(&f) {
+                return f;
+            }
+            //primitive polynomials of every degree exist, so the counter does not overflow
+            let mut i = 0;
+            while digits[i] + 1 == q {
+                digits[i] = 0;
+                i += 1;
+            }
+            digits[i] += 1;
+        }
+    }
+
+    /// A random primitive polynomial of degree n >= 1, from monic polynomials with random coefficients.
+    pub fn random_primitive_polynomial(&self, n: usize, seed: u64) -> Polynomial<FS::Set> {
+        assert!(n >= 1);
+        let field = self.coeff_ring();
+        let mut random_elements = field.generate_random_elements(seed);
+        loop {
+            let mut coeffs = (0..n)
+                .map(|_i| random_elements.next().unwrap())
+                .collect::<Vec<_>>();
+            coeffs.push(field.one());
+            let f = Polynomial::from_coeffs(coeffs);
+            if self.is_primitive_polynomial(&f) {
+                return f;
+            }
+        }
+    }
+}
+
+impl<F: MetaType> Polynomial<F>
+where
+    F::Structure: FiniteFieldStructure,
+{
+    pub fn is_irreducible_rabin(&self) -> bool {
+        Self::structure().is_irreducible_rabin(self)
+    }
+
+    pub fn is_irreducible_ben_or(&self) -> bool {
+        Self::structure().is_irreducible_ben_or(self)
+    }
+
+    pub fn is_primitive_polynomial(&self) -> bool {
+        Self::structure().is_primitive_polynomial(self)
+    }
+
+    pub fn first_primitive_polynomial(n: usize) -> Self {
+        Self::structure().first_primitive_polynomial(n)
+    }
+
+    pub fn random_primitive_polynomial(n: usize, seed: u64) -> Self {
+        Self::structure().random_primitive_polynomial(n, seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::{modulo::Modulo, quaternary_field::QuaternaryField};
+    use crate::structure::elements::*;
+
+    #[test]
+    fn test_irreducibility_tests() {
+        let x = &Polynomial::<Modulo<2>>::var().into_ergonomic();
+        let irreducible = vec![
+            x.clone(),
+            x + 1,
+            x.pow(2) + x + 1,
+            x.pow(4) + x + 1,
+            x.pow(8) + x.pow(4) + x.pow(3) + x + 1,
+        ];
+        let reducible = vec![
+            x.pow(0),
+            x.pow(2) + 1,
+            x.pow(4) + x.pow(2) + 1,
+            (x.pow(2) + x + 1).pow(2),
+            (x.pow(3) + x + 1) * (x.pow(3) + x.pow(2) + 1),
+        ];
+        for f in irreducible {
+            let f = f.into_verbose();
+            assert!(f.is_irreducible_rabin());
+            assert!(f.is_irreducible_ben_or());
+        }
+        for f in reducible {
+            let f = f.into_verbose();
+            assert!(!f.is_irreducible_rabin());
+            assert!(!f.is_irreducible_ben_or());
+        }
+
+        //the tests agree with factorization
+        let ring = Polynomial::<Modulo<3>>::structure();
+        let elements = Modulo::<3>::structure().all_elements();
+        for a in &elements {
+            for b in &elements {
+                for c in &elements {
+                    let f = Polynomial::from_coeffs(vec![
+                        c.clone(),
+                        b.clone(),
+                        a.clone(),
+                        Modulo::<3>::from(1usize),
+                    ]);
+                    let expected = ring.is_irreducible(&f);
+                    assert_eq!(f.is_irreducible_rabin(), expected);
+                    assert_eq!(f.is_irreducible_ben_or(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_monic_irreducibles() {
+        let ring = Polynomial::<Modulo<2>>::structure();
+        let counts = (0..9)
+            .map(|n| ring.count_monic_irreducibles(n))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![0u32, 2, 1, 2, 3, 6, 9, 18, 30]
+                .into_iter()
+                .map(Natural::from)
+                .collect::<Vec<_>>()
+        );
+        let ring = Polynomial::<QuaternaryField>::structure();
+        assert_eq!(ring.count_monic_irreducibles(2), Natural::from(6u32));
+        let ring = Polynomial::<Modulo<3>>::structure();
+        assert_eq!(ring.count_monic_irreducibles(3), Natural::from(8u32));
+    }
+
+    #[test]
+    fn test_primitive_polynomials() {
+        let x = &Polynomial::<Modulo<2>>::var().into_ergonomic();
+        assert_eq!(
+            Polynomial::<Modulo<2>>::first_primitive_polynomial(4),
+            (x.pow(4) + x + 1).into_verbose()
+        );
+        //x^4 + x^3 + x^2 + x + 1 is irreducible but x has order 5
+        let f = (x.pow(4) + x.pow(3) + x.pow(2) + x + 1).into_verbose();
+        assert!(f.is_irreducible_rabin());
+        assert!(!f.is_primitive_polynomial());
+        assert!(!x.clone().into_verbose().is_primitive_polynomial());
+
+        //x + 2 has the primitive root 3 modulo 5
+        let y = &Polynomial::<Modulo<5>>::var().into_ergonomic();
+        assert_eq!(
+            Polynomial::<Modulo<5>>::first_primitive_polynomial(1),
+            (y + 2).into_verbose()
+        );
+        assert_eq!(
+            Polynomial::<Modulo<5>>::first_primitive_polynomial(2),
+            (y.pow(2) + y + 2).into_verbose()
+        );
+
+        for seed in 0..3 {
+            let f = Polynomial::<Modulo<7>>::random_primitive_polynomial(3, seed);
+            assert_eq!(f.degree(), Some(3));
+            assert!(f.is_primitive_polynomial());
+        }
+        let f = Polynomial::<QuaternaryField>::random_primitive_polynomial(3, 0);
+        assert!(f.is_primitive_polynomial());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod irreducible;
 pub mod modulo;
 pub mod polynomial;
 pub mod quaternary_field;
+pub mod roots;
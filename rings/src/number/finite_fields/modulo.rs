@@ -1,5 +1,6 @@
 use malachite_nz::{integer::Integer, natural::Natural};
 
+use crate::polynomial::multiplication::*;
 use crate::polynomial::polynomial::*;
 use crate::structure::factorization::*;
 use crate::structure::structure::*;
@@ -153,6 +154,17 @@ impl<const N: usize> SemiRingStructure for CannonicalStructure<Modulo<N>> {
             x: (&a.x * &b.x) % N,
         }
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        if a.len().min(b.len()) < NTT_THRESHOLD {
+            return schoolbook_mul(self, a, b);
+        }
+        let lift = |x: &[Modulo<N>]| x.iter().map(|c| c.lift_int()).collect::<Vec<_>>();
+        integer_polynomial_mul(&lift(a), &lift(b))
+            .into_iter()
+            .map(Modulo::from)
+            .collect()
+    }
 }
 
 impl<const N: usize> RingStructure for CannonicalStructure<Modulo<N>> {
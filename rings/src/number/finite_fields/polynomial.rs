@@ -2,13 +2,12 @@ use std::rc::Rc;
 
 use algebraeon_sets::structure::*;
 use itertools::Itertools;
-use malachite_base::num::basic::traits::{One, Two};
+use malachite_base::num::basic::traits::{One, Two, Zero};
 use malachite_nz::natural::Natural;
 
 use crate::{
     linear::{matrix::*, subspace::*},
-    number::natural::nat_to_usize,
-    polynomial::polynomial::*,
+    polynomial::{polynomial::*, quotient::ModularComposition},
     structure::{factorization::*, quotient::QuotientStructure, structure::*},
 };
 
@@ -380,6 +379,123 @@ where
     }
 }
 
+impl<FS: FiniteFieldStructure> PolynomialStructure<FS>
+where
+    PolynomialStructure<FS>: Structure<Set = Polynomial<FS::Set>>,
+{
+    // x^q modulo f
+    fn frobenius_of_var(&self, f: &Polynomial<FS::Set>) -> Polynomial<FS::Set> {
+        let (p, k) = self.coeff_ring().characteristic_and_power();
+        QuotientStructure::new_ring(self.clone().into(), f.clone())
+            .nat_pow(&self.var(), &p.nat_pow(&k))
+    }
+
+    fn monic_gcd(&self, a: &Polynomial<FS::Set>, b: &Polynomial<FS::Set>) -> Polynomial<FS::Set> {
+        self.factorize_monic(&self.gcd(a, b)).unwrap().monic
+    }
+
+    // the sum and the product of a, a^q, ..., a^(q^(d-1)) modulo f given composition with x^q modulo f
+    // since a^(q^i) = a(x^(q^i)) modulo f these are found by repeated doubling with modular composition
+    fn frobenius_trace_and_norm(
+        &self,
+        mod_f: &QuotientStructure<PolynomialStructure<FS>, false>,
+        frobenius: &ModularComposition<FS>,
+        a: &Polynomial<FS::Set>,
+        d: usize,
+    ) -> (Polynomial<FS::Set>, Polynomial<FS::Set>) {
+        debug_assert!(d >= 1);
+        // trace and norm are over a, a^q, ..., a^(q^(e-1)) and xqe = x^(q^e)
+        let a = mod_f.reduce(a);
+        let mut trace = a.clone();
+        let mut norm = a.clone();
+        let mut xqe = frobenius.compose(&self.var());
+        for bit in (0..(usize::BITS - d.leading_zeros() - 1)).rev() {
+            let frobenius_e = ModularComposition::new(mod_f, &xqe);
+            trace = self.add(&trace, &frobenius_e.compose(&trace));
+            norm = frobenius_e.mul(&norm, &frobenius_e.compose(&norm));
+            xqe = frobenius_e.compose(&xqe);
+            if (d >> bit) & 1 == 1 {
+                trace = self.add(&a, &frobenius.compose(&trace));
+                norm = frobenius.mul(&a, &frobenius.compose(&norm));
+                xqe = frobenius.compose(&xqe);
+            }
+        }
+        (trace, norm)
+    }
+
+    // the distinct degree factorization of a monic squarefree polynomial of degree at least 1
+    // by the baby-step giant-step algorithm of Kaltofen and Shoup
+    fn distinct_degree_factors_by_baby_step_giant_step(
+        &self,
+        f: &Polynomial<FS::Set>,
+    ) -> Vec<DistinctDegreeFactor<FS>> {
+        let n = self.degree(f).unwrap();
+        debug_assert!(n >= 1);
+        let mod_f = QuotientStructure::new_ring(self.clone().into(), f.clone());
+
+        // the baby steps are x^(q^i) for 0 <= i < l and the giant steps are x^(q^(lj)) for 1 <= j <= m where lm >= n/2
+        // each is obtained from the previous one by modular composition with x^q or x^(q^l)
+        let mut l = 1;
+        while 2 * l * l < n {
+            l += 1;
+        }
+        let m = n.div_ceil(2 * l);
+        let xq = self.frobenius_of_var(f);
+        let frobenius = ModularComposition::with_block_length(&mod_f, &xq, (l * n).isqrt());
+        let mut baby_steps = vec![mod_f.reduce(&self.var()), xq];
+        while baby_steps.len() <= l {
+            baby_steps.push(frobenius.compose(baby_steps.last().unwrap()));
+        }
+        let xql = baby_steps.pop().unwrap();
+        let frobenius_l = ModularComposition::with_block_length(&mod_f, &xql, (m * n).isqrt());
+        let mut giant_steps = vec![xql];
+        while giant_steps.len() < m {
+            giant_steps.push(frobenius_l.compose(giant_steps.last().unwrap()));
+        }
+
+        let mut factors = vec![];
+        let mut rest = f.clone();
+        for (j, giant_step) in (1..).zip(&giant_steps) {
+            // every irreducible factor of rest has degree more than l(j-1), so if there is only room for one then rest is irreducible
+            if self.degree(&rest).unwrap() < 2 * (l * (j - 1) + 1) {
+                break;
+            }
+            // an irreducible factor of degree d divides x^(q^(lj)) - x^(q^i) if and only if d divides lj - i,
+            // so the product over the baby steps picks out the irreducible factors with degree in the interval l(j-1) < d <= lj
+            let interval_product = baby_steps.iter().fold(mod_f.one(), |product, baby_step| {
+                frobenius.mul(&product, &self.add(giant_step, &self.neg(baby_step)))
+            });
+            let mut g = self.monic_gcd(&rest, &interval_product);
+            if self.degree(&g).unwrap() == 0 {
+                continue;
+            }
+            rest = self.div(&rest, &g).unwrap();
+            // split g by degree in increasing order, so that factors of degree dividing lj - i are removed before they are seen again
+            for (i, baby_step) in baby_steps.iter().enumerate().rev() {
+                if self.degree(&g).unwrap() == 0 {
+                    break;
+                }
+                let h = self.monic_gcd(&g, &self.add(giant_step, &self.neg(baby_step)));
+                if self.degree(&h).unwrap() > 0 {
+                    g = self.div(&g, &h).unwrap();
+                    factors.push(DistinctDegreeFactor {
+                        irreducible_factor_degree: l * j - i,
+                        polynomial: h,
+                    });
+                }
+            }
+            debug_assert_eq!(self.degree(&g), Some(0));
+        }
+        if self.degree(&rest).unwrap() > 0 {
+            factors.push(DistinctDegreeFactor {
+                irreducible_factor_degree: self.degree(&rest).unwrap(),
+                polynomial: rest,
+            });
+        }
+        factors
+    }
+}
+
 impl<FS: FiniteFieldStructure> SquarefreeFactored<FS>
 where
     PolynomialStructure<FS>: Structure<Set = Polynomial<FS::Set>>,
@@ -387,113 +503,22 @@ where
     /// distinct degree factorization
     pub fn factorize_distinct_degree(&self) -> DistinctDegreeFactored<FS> {
         // https://en.wikipedia.org/wiki/Factorization_of_polynomials_over_finite_fields#Distinct-degree_factorization
-        let (p, k) = self.poly_ring.coeff_ring().characteristic_and_power();
-        let q = p.nat_pow(&k);
+        // a key step in this algorithm is the computation of gcd(f, x^{q^i} - x) for each i
+        // rather than computing x^{q^1}, x^{q^2}, ... one after another, only about sqrt(deg f) of them are computed,
+        // and the gcds are combined into products over intervals of degrees, see distinct_degree_factors_by_baby_step_giant_step
         let mut distinct_degree_factors = vec![];
         for (poly, sqfree_poly_multiplicity) in &self.squarefree_factors {
-            // a key step in this algorithm is the computation of g = gcd(f, x^{q^i} - x)
-            // the naive approach isn't great since x^{q^i} gets quite big
-            // instead the GCD can be computed after first reducing x^{q^i} modulo poly, since f divides poly
-            // to quickly compute x^{q^i} we can compute x^{q^1}, x^{q^2}, ... in sucessive loops, each time raising the previous value to the power of q
-            // raising polynomials mod poly to the power of q is a linear map (freshmans dream over finite field)
-            // so the qth power can be obtained by pre-computing the qth power matrix for polynomials mod poly
-
-            let n = self.poly_ring.degree(poly).unwrap();
-            debug_assert!(n >= 1);
-
-            let mod_poly_ring =
-                QuotientStructure::new_ring(self.poly_ring.clone().into(), poly.clone());
-            let mat_structure = MatrixStructure::new(self.poly_ring.coeff_ring());
-            let xq = mod_poly_ring.nat_pow(&self.poly_ring.var(), &q);
-            let qth_power_matrix = Matrix::join_cols(
-                n,
-                (0..n)
-                    .map(|c| {
-                        //compute (x^c)^q mod poly as a length n column vector of coefficients
-                        mod_poly_ring
-                            .to_col_vector(&mod_poly_ring.nat_pow(&self.poly_ring.var_pow(c), &q))
-                    })
-                    .collect(),
-            );
-
-            let mut i = 1;
-            let mut xqi = xq.clone(); // x^{q^i} mod poly
-            let mut f = poly.clone();
-            while self.poly_ring.degree(&f).unwrap() >= 2 * i {
-                debug_assert!(self.poly_ring.is_monic(&f));
-
-                let g = self
-                    .poly_ring
-                    .factorize_monic(
-                        &self.poly_ring.gcd_by_primitive_subresultant(
-                            f.clone(),
-                            self.poly_ring
-                                .add(&xqi, &self.poly_ring.neg(&self.poly_ring.var())),
-                        ),
-                    )
-                    .unwrap()
-                    .monic;
-                debug_assert!(self.poly_ring.is_monic(&g));
-
-                #[cfg(debug_assertions)]
-                {
-                    debug_assert!(mod_poly_ring.equal(
-                        &xqi,
-                        &self
-                            .poly_ring
-                            .var_pow(nat_to_usize(&q.nat_pow(&i.into())).unwrap())
-                    ));
-                    let g_naive = self
-                        .poly_ring
-                        .factorize_monic(
-                            &self.poly_ring.gcd_by_primitive_subresultant(
-                                f.clone(),
-                                self.poly_ring.add(
-                                    &self
-                                        .poly_ring
-                                        .var_pow(nat_to_usize(&q.nat_pow(&i.into())).unwrap()),
-                                    &self.poly_ring.neg(&self.poly_ring.var()),
-                                ),
-                            ),
-                        )
-                        .unwrap()
-                        .monic;
-                    // This GCD was the naive and expensive way to do it. Use it to verify the fast and fancy way.
-                    debug_assert!(self.poly_ring.is_monic(&g_naive));
-                    debug_assert!(self.poly_ring.equal(&g, &g_naive));
-                }
-
-                if !self.poly_ring.equal(&g, &self.poly_ring.one()) {
-                    f = self.poly_ring.div(&f, &g).unwrap();
-                    distinct_degree_factors.push((
-                        DistinctDegreeFactor {
-                            irreducible_factor_degree: i,
-                            polynomial: g,
-                        },
-                        sqfree_poly_multiplicity.clone(),
-                    ));
-                }
-                i += 1;
-                xqi = mod_poly_ring.from_col_vector(
-                    mat_structure
-                        .mul(&qth_power_matrix, &mod_poly_ring.to_col_vector(&xqi))
-                        .unwrap(),
-                );
-            }
-            if !self.poly_ring.equal(&f, &self.poly_ring.one()) {
-                distinct_degree_factors.push((
-                    DistinctDegreeFactor {
-                        irreducible_factor_degree: self.poly_ring.degree(&f).unwrap(),
-                        polynomial: f,
-                    },
-                    sqfree_poly_multiplicity.clone(),
-                ));
+            for ddf in self
+                .poly_ring
+                .distinct_degree_factors_by_baby_step_giant_step(poly)
+            {
+                distinct_degree_factors.push((ddf, sqfree_poly_multiplicity.clone()));
             }
         }
         DistinctDegreeFactored {
             poly_ring: self.poly_ring.clone(),
             unit: self.unit.clone(),
-            distinct_degree_factors: distinct_degree_factors,
+            distinct_degree_factors,
         }
     }
 }
@@ -522,16 +547,15 @@ where
             let q = p.nat_pow(&k);
             let mut prand_elements = finite_field.generate_random_elements(0);
 
+            let poly_mod_f =
+                QuotientStructure::new_ring(self.poly_ring.clone().into(), ddf.polynomial.clone());
+            let frobenius = ModularComposition::new(
+                &poly_mod_f,
+                &self.poly_ring.frobenius_of_var(&ddf.polynomial),
+            );
+
             let mut to_factor = vec![ddf.polynomial.clone()];
             loop {
-                // println!(
-                //     "{} {:?}",
-                //     d,
-                //     to_factor
-                //         .iter()
-                //         .map(|u| self.poly_ring.degree(u).unwrap())
-                //         .collect::<Vec<_>>()
-                // );
                 // Any polynomial in to_factor of degree d is irreducible.
                 to_factor = to_factor
                     .into_iter()
@@ -558,38 +582,36 @@ where
                 let h = Polynomial::<FS::Set>::from_coeffs(
                     (0..n).map(|_| prand_elements.next().unwrap()).collect(),
                 );
+                // following von zur Gathen and Shoup, the sum and product of h, h^q, ..., h^{q^{d-1}} are found with modular composition
+                let (trace, norm) =
+                    self.poly_ring
+                        .frobenius_trace_and_norm(&poly_mod_f, &frobenius, &h, d);
                 let g = if p == Natural::TWO {
-                    // when char = 2 use h + h^2 + h^4 + ... + h^{2^{kd-1}}
+                    // when char = 2 use h + h^2 + h^4 + ... + h^{2^{kd-1}} mod f
+                    // which is t + t^2 + ... + t^{2^{k-1}} for t = h + h^q + ... + h^{q^{d-1}}
                     // https://math.stackexchange.com/questions/1636518/how-do-i-apply-the-cantor-zassenhaus-algorithm-to-mathbbf-2
-                    let mut sum = self.poly_ring.zero();
-                    let mut square_powers = h.clone();
-                    let mut square_pow = 0usize;
-                    while Natural::from(square_pow) < &k * Natural::from(d) {
-                        self.poly_ring.add_mut(&mut sum, &square_powers);
-                        square_powers = self.poly_ring.mul(&square_powers, &square_powers);
-                        square_pow += 1;
+                    let mut sum = poly_mod_f.zero();
+                    let mut square_powers = trace;
+                    let mut square_pow = Natural::ZERO;
+                    while square_pow < k {
+                        poly_mod_f.add_mut(&mut sum, &square_powers);
+                        square_powers = poly_mod_f.mul(&square_powers, &square_powers);
+                        square_pow += Natural::ONE;
                     }
                     sum
                 } else {
                     // when char != 2 use h^{(q^d-1)/2}-1 mod f
-                    let poly_mod_f = QuotientStructure::new_ring(
-                        self.poly_ring.clone().into(),
-                        ddf.polynomial.clone(),
-                    );
-                    let a = (q.nat_pow(&d.into()) - Natural::ONE) / Natural::TWO;
+                    // which is n^{(q-1)/2}-1 for n = h h^q ... h^{q^{d-1}}
+                    let a = (&q - Natural::ONE) / Natural::TWO;
                     poly_mod_f.add(
-                        &poly_mod_f.nat_pow(&h, &a),
+                        &poly_mod_f.nat_pow(&norm, &a),
                         &poly_mod_f.neg(&poly_mod_f.one()),
                     )
                 };
                 to_factor = to_factor
                     .into_iter()
-                    .map(|u| {
-                        let gcd = self
-                            .poly_ring
-                            .factorize_monic(&self.poly_ring.subresultant_gcd(u.clone(), g.clone()))
-                            .unwrap()
-                            .monic;
+                    .flat_map(|u| {
+                        let gcd = self.poly_ring.monic_gcd(&u, &g);
                         let gcd_deg = self.poly_ring.degree(&gcd).unwrap();
                         if gcd_deg == 0 || gcd_deg == self.poly_ring.degree(&u).unwrap() {
                             vec![u]
@@ -597,7 +619,6 @@ where
                             vec![self.poly_ring.div(&u, &gcd).unwrap(), gcd]
                         }
                     })
-                    .flatten()
                     .collect();
             }
         }
@@ -839,4 +860,96 @@ mod tests {
         println!("{} = {}", p, f);
         assert!(Factored::equal(&f, &ans));
     }
+
+    #[test]
+    fn test_distinct_degree_factorization() {
+        let ring = Polynomial::<Modulo<3>>::structure();
+        //monic irreducible polynomials with a repeated degree and degrees on both sides of the baby step size
+        let field = Modulo::<3>::structure();
+        let mut random_elements = field.generate_random_elements(1);
+        let mut irreducibles: Vec<Polynomial<Modulo<3>>> = vec![];
+        for d in [1, 1, 2, 3, 5, 5, 7, 12] {
+            loop {
+                let mut coeffs = (0..d)
+                    .map(|_| random_elements.next().unwrap())
+                    .collect::<Vec<_>>();
+                coeffs.push(Modulo::from(1usize));
+                let f = Polynomial::from_coeffs(coeffs);
+                if f.is_irreducible_rabin() && !irreducibles.contains(&f) {
+                    irreducibles.push(f);
+                    break;
+                }
+            }
+        }
+        let p = Polynomial::product(irreducibles.iter().collect());
+
+        let ddf = p
+            .factorize_monic()
+            .unwrap()
+            .factorize_squarefree()
+            .factorize_distinct_degree();
+        let mut degrees = vec![];
+        for (factor, mult) in &ddf.distinct_degree_factors {
+            assert_eq!(mult, &Natural::ONE);
+            let expected = Polynomial::product(
+                irreducibles
+                    .iter()
+                    .filter(|f| f.degree() == Some(factor.irreducible_factor_degree))
+                    .collect(),
+            );
+            assert!(ring.equal(&factor.polynomial, &expected));
+            degrees.push(factor.irreducible_factor_degree);
+        }
+        assert_eq!(degrees, vec![1, 2, 3, 5, 7, 12]);
+
+        let f = ddf.factorize_cantor_zassenhaus();
+        assert_eq!(f.factors().len(), irreducibles.len());
+        for (factor, _mult) in f.factors() {
+            assert!(irreducibles.contains(factor));
+        }
+    }
+
+    #[test]
+    fn test_factorize_high_degree() {
+        for (seed, n) in [(0, 300), (1, 301)] {
+            let field = Modulo::<101>::structure();
+            let mut coeffs = field
+                .generate_random_elements(seed)
+                .take(n)
+                .collect::<Vec<_>>();
+            coeffs.push(Modulo::from(1usize));
+            let p = Polynomial::<Modulo<101>>::from_coeffs(coeffs);
+            let f = p.factor().unwrap();
+            assert_eq!(f.expand(), p);
+            for (factor, _mult) in f.factors() {
+                assert!(factor.is_irreducible_ben_or());
+            }
+        }
+
+        let x = &Polynomial::<Modulo<2>>::var().into_ergonomic();
+        let p = (x.pow(255) + x.pow(3) + 1).into_verbose();
+        let f = p.factor().unwrap();
+        assert_eq!(f.expand(), p);
+        for (factor, _mult) in f.factors() {
+            assert!(factor.is_irreducible_rabin());
+        }
+    }
+
+    //takes a few seconds in release builds, run with cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn test_factorize_degree_1000() {
+        let field = Modulo::<101>::structure();
+        let mut coeffs = field
+            .generate_random_elements(2)
+            .take(1000)
+            .collect::<Vec<_>>();
+        coeffs.push(Modulo::from(1usize));
+        let p = Polynomial::<Modulo<101>>::from_coeffs(coeffs);
+        let f = p.factor().unwrap();
+        assert_eq!(f.expand(), p);
+        for (factor, _mult) in f.factors() {
+            assert!(factor.is_irreducible_ben_or());
+        }
+    }
 }
@@ -0,0 +1,166 @@
+use std::rc::Rc;
+
+use algebraeon_sets::structure::*;
+use malachite_base::num::basic::traits::{One, Two};
+use malachite_nz::natural::Natural;
+
+use crate::{
+    polynomial::polynomial::*,
+    structure::{quotient::QuotientStructure, structure::*},
+};
+
+impl<FS: FiniteFieldStructure> PolynomialStructure<FS> {
+    //a polynomial u such that gcd(g, u) is a proper factor of g for about half the choices of a,
+    //when g is a product of at least two distinct monic linear factors
+    fn linear_splitting_polynomial(
+        &self,
+        g: &Polynomial<FS::Set>,
+        a: &FS::Set,
+    ) -> Polynomial<FS::Set> {
+        let field = self.coeff_ring();
+        let (p, k) = field.characteristic_and_power();
+        let modulo_g = QuotientStructure::new_ring(Rc::new(self.clone()), g.clone());
+        if p == Natural::TWO {
+            //the trace a x + (a x)^2 + ... + (a x)^(2^(k-1)) takes values in F_2 at the roots of g
+            let ax = self.mul_scalar(&self.var(), a);
+            let mut trace = self.zero();
+            let mut power = ax;
+            let mut i = Natural::from(0u8);
+            while i < k {
+                trace = modulo_g.add(&trace, &power);
+                power = modulo_g.mul(&power, &power);
+                i += Natural::ONE;
+            }
+            trace
+        } else {
+            //(x + a)^((q-1)/2) is 1 or -1 at each root of g other than -a
+            let q = p.nat_pow(&k);
+            let shifted = Polynomial::from_coeffs(vec![a.clone(), field.one()]);
+            modulo_g.add(
+                &modulo_g.nat_pow(&shifted, &((q - Natural::ONE) / Natural::TWO)),
+                &modulo_g.neg(&modulo_g.one()),
+            )
+        }
+    }
+
+    /// The distinct roots of f, found without factoring f:
+    /// gcd(f, x^q - x) is the product of x - a over the roots a of f, which is split into linear factors by equal degree splitting.
+    /// Returns None if f is zero, since then every element is a root.
+    pub fn roots(&self, f: &Polynomial<FS::Set>) -> Option<Vec<FS::Set>> {
+        if self.is_zero(f) {
+            return None;
+        }
+        let field = self.coeff_ring();
+        let (p, k) = field.characteristic_and_power();
+        let f = self.factor_fav_assoc(f).1;
+        if self.degree(&f) == Some(0) {
+            return Some(vec![]);
+        }
+        let modulo_f = QuotientStructure::new_ring(Rc::new(self.clone()), f.clone());
+        let xq = modulo_f.nat_pow(&self.var(), &p.nat_pow(&k));
+        let linear_part = self
+            .factor_fav_assoc(&self.gcd(&f, &self.add(&xq, &self.neg(&self.var()))))
+            .1;
+
+        let mut roots = vec![];
+        let mut random_elements = field.generate_random_elements(0);
+        let mut to_split = vec![linear_part];
+        while let Some(g) = to_split.pop() {
+            match self.degree(&g).unwrap() {
+                0 => {}
+                1 => roots.push(field.neg(self.coeff(&g, 0))),
+                n => {
+                    let a = random_elements.next().unwrap();
+                    let u = self.linear_splitting_polynomial(&g, &a);
+                    let h = self.factor_fav_assoc(&self.gcd(&g, &u)).1;
+                    let d = self.degree(&h).unwrap();
+                    if 0 < d && d < n {
+                        to_split.push(self.div(&g, &h).unwrap());
+                        to_split.push(h);
+                    } else {
+                        to_split.push(g);
+                    }
+                }
+            }
+        }
+        Some(roots)
+    }
+}
+
+impl<F: MetaType> Polynomial<F>
+where
+    F::Structure: FiniteFieldStructure,
+{
+    pub fn roots(&self) -> Option<Vec<F>> {
+        Self::structure().roots(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::{modulo::Modulo, quaternary_field::QuaternaryField};
+    use crate::structure::elements::*;
+
+    fn sorted<const N: usize>(roots: Vec<Modulo<N>>) -> Vec<usize> {
+        let mut roots = roots.into_iter().map(usize::from).collect::<Vec<_>>();
+        roots.sort();
+        roots
+    }
+
+    #[test]
+    fn test_roots_over_prime_field() {
+        let x = &Polynomial::<Modulo<31>>::var().into_ergonomic();
+        let f = ((x - 3).pow(2) * (x - 7) * (x + 1) * (x.pow(2) + 1) * x).into_verbose();
+        assert_eq!(sorted(f.roots().unwrap()), vec![0, 3, 7, 30]);
+
+        //x^2 + 1 has no roots modulo 31 but has roots modulo 5
+        assert_eq!(
+            sorted((x.pow(2) + 1).into_verbose().roots().unwrap()),
+            Vec::<usize>::new()
+        );
+        let y = &Polynomial::<Modulo<5>>::var().into_ergonomic();
+        assert_eq!(
+            sorted((y.pow(2) + 1).into_verbose().roots().unwrap()),
+            vec![2, 3]
+        );
+        //every element is a root of x^5 - x
+        assert_eq!(
+            sorted((y.pow(5) - y).into_verbose().roots().unwrap()),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            sorted((3 * y.pow(0)).into_verbose().roots().unwrap()),
+            Vec::<usize>::new()
+        );
+
+        let z = &Polynomial::<Modulo<2>>::var().into_ergonomic();
+        assert_eq!(
+            sorted((z.pow(3) + z).into_verbose().roots().unwrap()),
+            vec![0, 1]
+        );
+        assert_eq!(
+            sorted((z.pow(2) + z + 1).into_verbose().roots().unwrap()),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn test_roots_over_f4() {
+        //every element of F_4 is a root of x^4 - x
+        let x = &Polynomial::<QuaternaryField>::var().into_ergonomic();
+        let roots = (x.pow(4) - x).into_verbose().roots().unwrap();
+        assert_eq!(roots.len(), 4);
+        for a in QuaternaryField::structure().all_elements() {
+            assert!(roots.contains(&a));
+        }
+        //x^2 + x + 1 splits over F_4
+        assert_eq!((x.pow(2) + x + 1).into_verbose().roots().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_roots_of_zero() {
+        assert_eq!(Polynomial::<Modulo<7>>::zero().roots(), None);
+        assert_eq!(Polynomial::<QuaternaryField>::zero().roots(), None);
+    }
+}
@@ -9,12 +9,14 @@ use malachite_nz::integer::Integer;
 use malachite_nz::natural::Natural;
 
 use crate::number::natural::*;
+use crate::polynomial::multiplication::integer_polynomial_mul;
 use crate::structure::factorization::*;
 use crate::structure::structure::*;
 
 use algebraeon_sets::structure::*;
 
 pub mod berlekamp_zassenhaus;
+pub mod modular_gcd;
 pub mod modulo;
 pub mod polynomial;
 pub mod zimmermann_polys;
@@ -35,6 +37,10 @@ impl SemiRingStructure for CannonicalStructure<Integer> {
     fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
         a * b
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        integer_polynomial_mul(a, b)
+    }
 }
 
 impl RingStructure for CannonicalStructure<Integer> {
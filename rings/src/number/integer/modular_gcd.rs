@@ -0,0 +1,282 @@
+use std::sync::Mutex;
+
+use malachite_base::num::arithmetic::traits::{Mod, PowerOf2};
+use malachite_base::num::conversion::traits::ExactFrom;
+use malachite_nz::{integer::Integer, natural::Natural};
+
+use crate::number::natural::functions::chinese_remainder;
+use crate::number::natural::primes::is_prime;
+use crate::polynomial::polynomial::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+//the largest primes below 2^31 found so far, in decreasing order
+static WORD_PRIMES: Mutex<Vec<u64>> = Mutex::new(vec![]);
+
+/// The primes below 2^31 in decreasing order, so that products of residues fit in a u64.
+/// They are found once and cached, so that small gcds do not repeat the primality tests.
+pub(crate) fn word_primes() -> impl Iterator<Item = u64> {
+    (0..).map(|i| {
+        let mut primes = WORD_PRIMES.lock().unwrap();
+        while primes.len() <= i {
+            let mut p = primes.last().copied().unwrap_or(1 << 31) - 1;
+            while !is_prime(&Natural::from(p)) {
+                p -= 1;
+            }
+            assert!(p > 1 << 30);
+            primes.push(p);
+        }
+        primes[i]
+    })
+}
+
+pub(crate) fn residue(x: &Integer, p: u64) -> u64 {
+    u64::exact_from(&x.mod_op(Integer::from(p)))
+}
+
+pub(crate) fn inv_mod(a: u64, p: u64) -> u64 {
+    debug_assert_ne!(a % p, 0);
+    let mut r = 1;
+    let (mut a, mut e) = (a % p, p - 2);
+    while e > 0 {
+        if e & 1 == 1 {
+            r = r * a % p;
+        }
+        a = a * a % p;
+        e >>= 1;
+    }
+    r
+}
+
+//polynomials over Z/p are coefficient vectors without trailing zeros
+
+pub(crate) fn trim_mod_p(a: &mut Vec<u64>) {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+}
+
+//the quotient and remainder of a by a nonzero b
+pub(crate) fn quorem_mod_p(a: &[u64], b: &[u64], p: u64) -> (Vec<u64>, Vec<u64>) {
+    let mut r = a.to_vec();
+    trim_mod_p(&mut r);
+    if r.len() < b.len() {
+        return (vec![], r);
+    }
+    let lc_inv = inv_mod(*b.last().unwrap(), p);
+    let mut q = vec![0; r.len() + 1 - b.len()];
+    while r.len() >= b.len() {
+        let shift = r.len() - b.len();
+        let c = r.last().unwrap() * lc_inv % p;
+        for (i, bi) in b.iter().enumerate() {
+            r[shift + i] = (r[shift + i] + p - c * bi % p) % p;
+        }
+        q[shift] = c;
+        trim_mod_p(&mut r);
+    }
+    (q, r)
+}
+
+//the monic gcd of a and b, not both zero
+fn monic_gcd_mod_p(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    trim_mod_p(&mut a);
+    trim_mod_p(&mut b);
+    while !b.is_empty() {
+        let (_q, r) = quorem_mod_p(&a, &b, p);
+        (a, b) = (b, r);
+    }
+    let lc_inv = inv_mod(*a.last().unwrap(), p);
+    a.iter().map(|c| c * lc_inv % p).collect()
+}
+
+impl PolynomialStructure<CannonicalStructure<Integer>> {
+    /// The gcd of a and b with positive leading coefficient by the modular algorithm of Brown and Collins.
+    /// The gcd of the primitive parts is recovered from its images modulo primes by the chinese remainder theorem.
+    pub fn gcd_by_modular_algorithm(
+        &self,
+        a: &Polynomial<Integer>,
+        b: &Polynomial<Integer>,
+    ) -> Polynomial<Integer> {
+        if self.is_zero(a) {
+            return self.factor_fav_assoc(b).1;
+        }
+        if self.is_zero(b) {
+            return self.factor_fav_assoc(a).1;
+        }
+        let (a_content, a) = self.factor_primitive(a.clone()).unwrap();
+        let (b_content, b) = self.factor_primitive(b.clone()).unwrap();
+        let content = Polynomial::constant(Integer::structure().gcd(&a_content, &b_content));
+        let (da, db) = (self.degree(&a).unwrap(), self.degree(&b).unwrap());
+        if da == 0 || db == 0 {
+            return self.factor_fav_assoc(&content).1;
+        }
+
+        //the images are scaled so that the gcd has leading coefficient lc, which is then at most bound in absolute value by the Landau-Mignotte bound
+        let lc_a = self.leading_coeff(&a).unwrap();
+        let lc_b = self.leading_coeff(&b).unwrap();
+        let lc = Integer::structure().gcd(lc_a, lc_b);
+        let norm = |f: &Polynomial<Integer>| {
+            f.coeffs()
+                .into_iter()
+                .map(|c| Integer::from(c.unsigned_abs_ref()))
+                .fold(Integer::from(0), |s, c| s + c)
+        };
+        let bound = Integer::from(lc.unsigned_abs_ref())
+            * Integer::power_of_2(std::cmp::min(da, db) as u64)
+            * std::cmp::min(norm(&a), norm(&b));
+        let lc_ab = lc_a * lc_b;
+
+        //the degree, the coefficients modulo the product of the primes used so far, and that product
+        let mut image: Option<(usize, Vec<Integer>, Natural)> = None;
+        let mut previous: Option<Polynomial<Integer>> = None;
+        for p in word_primes() {
+            if residue(&lc_ab, p) == 0 {
+                continue;
+            }
+            let reduce = |f: &Polynomial<Integer>| {
+                f.coeffs()
+                    .into_iter()
+                    .map(|c| residue(c, p))
+                    .collect::<Vec<_>>()
+            };
+            let g = monic_gcd_mod_p(&reduce(&a), &reduce(&b), p);
+            let d = g.len() - 1;
+            if d == 0 {
+                return self.factor_fav_assoc(&content).1;
+            }
+            let lc_p = residue(&lc, p);
+            let g = g
+                .into_iter()
+                .map(|c| Integer::from(c * lc_p % p))
+                .collect::<Vec<_>>();
+            match &mut image {
+                //p divides a resultant and the image has too large a degree
+                Some((e, _, _)) if d > *e => {
+                    continue;
+                }
+                Some((e, coeffs, modulus)) if d == *e => {
+                    for (c, gc) in coeffs.iter_mut().zip(g) {
+                        let (x, _) = chinese_remainder(&[
+                            (c.clone(), modulus.clone()),
+                            (gc, Natural::from(p)),
+                        ])
+                        .unwrap();
+                        *c = Integer::from(x);
+                    }
+                    *modulus *= Natural::from(p);
+                }
+                //the previous primes were all unlucky
+                _ => {
+                    image = Some((d, g, Natural::from(p)));
+                }
+            }
+
+            let (_d, coeffs, modulus) = image.as_ref().unwrap();
+            let modulus = Integer::from(modulus);
+            let half_modulus = &modulus >> 1;
+            let candidate = Polynomial::from_coeffs(
+                coeffs
+                    .iter()
+                    .map(|c| {
+                        if c > &half_modulus {
+                            c - &modulus
+                        } else {
+                            c.clone()
+                        }
+                    })
+                    .collect(),
+            );
+            //check for termination once the image stabilizes or is certainly correct
+            if previous.as_ref() == Some(&candidate) || modulus > Integer::from(2) * &bound {
+                let g = self.primitive_part(candidate.clone()).unwrap();
+                if self.div(&a, &g).is_ok() && self.div(&b, &g).is_ok() {
+                    return self.factor_fav_assoc(&self.mul(&content, &g)).1;
+                }
+            }
+            previous = Some(candidate);
+        }
+        unreachable!()
+    }
+}
+
+impl Polynomial<Integer> {
+    pub fn gcd_by_modular_algorithm(a: &Self, b: &Self) -> Self {
+        Self::structure().gcd_by_modular_algorithm(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::elements::*;
+
+    #[test]
+    fn test_word_primes() {
+        let primes = word_primes().take(20).collect::<Vec<_>>();
+        assert_eq!(primes[0], 2147483647);
+        assert!(primes.windows(2).all(|w| w[0] > w[1]));
+        assert!(primes.iter().all(|p| is_prime(&Natural::from(*p))));
+        assert_eq!(word_primes().take(20).collect::<Vec<_>>(), primes);
+    }
+
+    #[test]
+    fn test_gcd_by_modular_algorithm() {
+        let x = &Polynomial::<Integer>::var().into_ergonomic();
+        let f = (6 * x.pow(4) - 3 * x + 12).into_verbose();
+        let g = (2 * x.pow(2) + 5 * x - 1).into_verbose();
+        let h = (4 * x.pow(3) - x - 7).into_verbose();
+        assert_eq!(
+            Polynomial::gcd_by_modular_algorithm(
+                &Polynomial::mul(&f, &g),
+                &Polynomial::mul(&f, &h)
+            ),
+            (3 * (2 * x.pow(4) - x + 4)).into_verbose()
+        );
+        assert_eq!(
+            Polynomial::gcd_by_modular_algorithm(
+                &Polynomial::mul(&f, &g).neg(),
+                &Polynomial::mul(&g, &h)
+            ),
+            g
+        );
+        assert_eq!(
+            Polynomial::gcd_by_modular_algorithm(&g, &h),
+            Polynomial::one()
+        );
+        assert_eq!(
+            Polynomial::gcd_by_modular_algorithm(
+                &(4 * x).into_verbose(),
+                &Polynomial::from_coeffs(vec![6])
+            ),
+            Polynomial::from_coeffs(vec![2])
+        );
+        assert_eq!(
+            Polynomial::gcd_by_modular_algorithm(&g.clone().neg(), &Polynomial::zero()),
+            g
+        );
+        assert_eq!(
+            Polynomial::gcd_by_modular_algorithm(&Polynomial::zero(), &Polynomial::zero()),
+            Polynomial::zero()
+        );
+    }
+
+    #[test]
+    fn test_gcd_by_modular_algorithm_large() {
+        //dense polynomials of degree 120 sharing a factor of degree 40 with large coefficients
+        let poly = |n: usize, seed: i64| {
+            Polynomial::<Integer>::from_coeffs(
+                (0..=n as i64)
+                    .map(|i| Integer::from((i * i * 7919 + seed * i + 3 * seed) % 100003 - 50000))
+                    .collect(),
+            )
+        };
+        let common = poly(40, 5);
+        let f = Polynomial::mul(&common, &poly(80, 11));
+        let g = Polynomial::mul(&common, &poly(80, 13));
+        let d = Polynomial::gcd_by_modular_algorithm(&f, &g);
+        assert_eq!(d.degree(), Some(40));
+        assert!(Polynomial::div(&common, &d).is_ok());
+        assert!(Polynomial::div(&d, &common).is_ok());
+    }
+}
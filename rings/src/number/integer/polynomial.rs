@@ -5,7 +5,7 @@ use crate::{number::integer::*, number::natural::functions::*, polynomial::polyn
 
 impl GreatestCommonDivisorStructure for PolynomialStructure<CannonicalStructure<Integer>> {
     fn gcd(&self, x: &Self::Set, y: &Self::Set) -> Self::Set {
-        self.gcd_by_primitive_subresultant(x.clone(), y.clone())
+        self.gcd_by_modular_algorithm(x, y)
     }
 }
 
@@ -3,4 +3,5 @@ pub mod anf;
 pub mod finite_fields;
 pub mod integer;
 pub mod natural;
+pub mod quadratic_forms;
 pub mod rational;
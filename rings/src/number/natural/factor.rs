@@ -1,4 +1,5 @@
 use malachite_base::num::arithmetic::traits::AbsDiff;
+use malachite_nz::integer::Integer;
 use primes::is_prime;
 
 use crate::polynomial::polynomial::Polynomial;
@@ -68,6 +69,197 @@ impl Factored {
     }
 }
 
+impl Factored {
+    /// The Mobius function mu(n)
+    pub fn mobius(&self) -> i8 {
+        if self.primes.values().any(|k| *k > Natural::ONE) {
+            0
+        } else if self.primes.len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// The Liouville function lambda(n) = (-1)^Omega(n) where Omega(n) counts prime factors with multiplicity
+    pub fn liouville(&self) -> i8 {
+        let mut omega = Natural::ZERO;
+        for k in self.primes.values() {
+            omega += k;
+        }
+        if omega % Natural::TWO == Natural::ZERO {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// The divisor function sigma_k(n), the sum of the kth powers of the divisors of n
+    pub fn divisor_sigma(&self, k: &Natural) -> Natural {
+        let mut t = Natural::ONE;
+        for (p, e) in &self.primes {
+            if *k == Natural::ZERO {
+                t *= e + Natural::ONE;
+            } else {
+                let pk = pow(p, k);
+                t *= (pow(&pk, &(e + Natural::ONE)) - Natural::ONE) / (pk - Natural::ONE);
+            }
+        }
+        t
+    }
+
+    /// The Carmichael function lambda(n), the exponent of the multiplicative group of integers modulo n
+    pub fn carmichael_lambda(&self) -> Natural {
+        let mut t = Natural::ONE;
+        for (p, k) in &self.primes {
+            let lambda_pk = if *p == Natural::TWO && *k >= 3 {
+                pow(p, &(k - Natural::TWO))
+            } else {
+                (p - &Natural::ONE) * pow(p, &(k - &Natural::ONE))
+            };
+            t = &t * &lambda_pk / gcd(t.clone(), lambda_pk);
+        }
+        t
+    }
+
+    /// Return all square roots of a modulo the value represented by self, in increasing order
+    pub fn all_sqrt_mod(&self, a: &Integer) -> Vec<Natural> {
+        use malachite_base::num::arithmetic::traits::{Mod, UnsignedAbs};
+        let mut roots = vec![Natural::ZERO];
+        let mut modulus = Natural::ONE;
+        for (p, k) in &self.primes {
+            let pk = pow(p, k);
+            let a_mod_pk = a.mod_op(Integer::from(&pk)).unsigned_abs();
+            let prime_power_roots = sqrt_mod_prime_power(&a_mod_pk, p, nat_to_usize(k).unwrap());
+            let mut new_roots = vec![];
+            for x in &roots {
+                for y in &prime_power_roots {
+                    new_roots.push(
+                        chinese_remainder(&[
+                            (Integer::from(x), modulus.clone()),
+                            (Integer::from(y), pk.clone()),
+                        ])
+                        .unwrap()
+                        .0,
+                    );
+                }
+            }
+            roots = new_roots;
+            modulus *= pk;
+        }
+        roots.sort();
+        roots
+    }
+
+    /// Return the smallest square root of a modulo the value represented by self if there is one
+    pub fn sqrt_mod(&self, a: &Integer) -> Option<Natural> {
+        self.all_sqrt_mod(a).into_iter().next()
+    }
+}
+
+// All square roots of a unit u modulo p^k
+fn sqrt_unit_mod_prime_power(u: &Natural, p: &Natural, k: usize) -> Vec<Natural> {
+    use malachite_base::num::arithmetic::traits::{Mod, ModInverse, ModNeg};
+    debug_assert!(k >= 1);
+    let pk = pow(p, &Natural::from(k));
+    if *p == Natural::TWO {
+        match k {
+            1 => vec![Natural::ONE],
+            2 => {
+                if u % Natural::from(4u8) == 1 {
+                    vec![Natural::ONE, Natural::from(3u8)]
+                } else {
+                    vec![]
+                }
+            }
+            _ => {
+                if u % Natural::from(8u8) != 1 {
+                    return vec![];
+                }
+                // lift a root one bit at a time
+                let mut r = Natural::ONE;
+                for i in 3..k {
+                    let two_i_plus_one = Natural::ONE << (i + 1);
+                    if (&r * &r).mod_op(&two_i_plus_one) != u.mod_op(&two_i_plus_one) {
+                        r += Natural::ONE << (i - 1);
+                    }
+                }
+                let half = Natural::ONE << (k - 1);
+                let neg_r = (&r).mod_neg(&pk);
+                let mut roots = vec![
+                    (&r + &half).mod_op(&pk),
+                    (&neg_r + &half).mod_op(&pk),
+                    r,
+                    neg_r,
+                ];
+                roots.sort();
+                roots
+            }
+        }
+    } else {
+        let r = match sqrt_mod_prime(u, p) {
+            Some(r) => r,
+            None => {
+                return vec![];
+            }
+        };
+        // Hensel lift r to a root modulo p^k, doubling the precision each step
+        let mut r = r;
+        let mut precision = 1;
+        while precision < k {
+            precision = std::cmp::min(2 * precision, k);
+            let m = pow(p, &Natural::from(precision));
+            let f = (&r * &r + &m - u.mod_op(&m)).mod_op(&m);
+            let df_inv = (Natural::TWO * &r).mod_op(&m).mod_inverse(&m).unwrap();
+            r = (&r + &m - (f * df_inv).mod_op(&m)).mod_op(&m);
+        }
+        let neg_r = (&r).mod_neg(&pk);
+        let mut roots = vec![r, neg_r];
+        roots.sort();
+        roots
+    }
+}
+
+// All square roots of a modulo p^k where 0 <= a < p^k
+fn sqrt_mod_prime_power(a: &Natural, p: &Natural, k: usize) -> Vec<Natural> {
+    let pk = pow(p, &Natural::from(k));
+    if *a == Natural::ZERO {
+        // x^2 = 0 mod p^k iff p^ceil(k/2) divides x
+        let step = pow(p, &Natural::from(k.div_ceil(2)));
+        let mut roots = vec![];
+        let mut x = Natural::ZERO;
+        while x < pk {
+            roots.push(x.clone());
+            x += &step;
+        }
+        return roots;
+    }
+    // a = p^v * u with u a unit
+    let mut v = 0;
+    let mut u = a.clone();
+    while &u % p == Natural::ZERO {
+        u /= p;
+        v += 1;
+    }
+    if v % 2 == 1 {
+        return vec![];
+    }
+    // x = p^(v/2) * y where y^2 = u mod p^(k-v) and y is taken modulo p^(k-v/2)
+    let unit_roots = sqrt_unit_mod_prime_power(&u, p, k - v);
+    let p_k_minus_v = pow(p, &Natural::from(k - v));
+    let p_half_v = pow(p, &Natural::from(v / 2));
+    let mut roots = vec![];
+    for y in unit_roots {
+        let mut y = y;
+        while y < &p_k_minus_v * &p_half_v {
+            roots.push((&p_half_v * &y) % &pk);
+            y += &p_k_minus_v;
+        }
+    }
+    roots.sort();
+    roots
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IsPrimitiveRootResult {
     NonUnit,
@@ -292,4 +484,39 @@ mod tests {
             IsPrimitiveRootResult::Yes,
         );
     }
+
+    #[test]
+    fn test_multiplicative_functions() {
+        let f = |n: u32| factor(Natural::from(n)).unwrap();
+        let mobius = [1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0];
+        let liouville = [1, -1, -1, 1, -1, 1, -1, -1, 1, 1, -1, -1];
+        let sigma_0 = [1u32, 2, 2, 3, 2, 4, 2, 4, 3, 4, 2, 6];
+        let sigma_1 = [1u32, 3, 4, 7, 6, 12, 8, 15, 13, 18, 12, 28];
+        let sigma_2 = [1u32, 5, 10, 21, 26, 50, 50, 85, 91, 130, 122, 210];
+        for n in 1..=12 {
+            let i = (n - 1) as usize;
+            assert_eq!(f(n).mobius(), mobius[i]);
+            assert_eq!(f(n).liouville(), liouville[i]);
+            assert_eq!(f(n).divisor_sigma(&Natural::from(0u32)), sigma_0[i]);
+            assert_eq!(f(n).divisor_sigma(&Natural::from(1u32)), sigma_1[i]);
+            assert_eq!(f(n).divisor_sigma(&Natural::from(2u32)), sigma_2[i]);
+        }
+    }
+
+    #[test]
+    fn test_carmichael_lambda() {
+        let lambda = [
+            1u32, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2, 12, 6, 4, 4, 16, 6, 18, 4, 6, 10, 22, 2,
+        ];
+        for n in 1..=24u32 {
+            assert_eq!(
+                factor(Natural::from(n)).unwrap().carmichael_lambda(),
+                lambda[(n - 1) as usize]
+            );
+        }
+        assert_eq!(
+            factor(Natural::from(561u32)).unwrap().carmichael_lambda(),
+            Natural::from(80u32)
+        );
+    }
 }
@@ -1,5 +1,8 @@
 use std::borrow::Borrow;
 
+use factor::factor;
+use malachite_nz::integer::Integer;
+use malachite_q::Rational;
 use primes::PrimeGenerator;
 
 use super::*;
@@ -167,6 +170,226 @@ pub fn gcd(mut x: Natural, mut y: Natural) -> Natural {
     x
 }
 
+/// The Jacobi symbol (a/n) for odd n
+pub fn jacobi_symbol(a: &Integer, n: &Natural) -> i8 {
+    use malachite_base::num::arithmetic::traits::Mod;
+    use malachite_base::num::arithmetic::traits::UnsignedAbs;
+    assert!(n % Natural::TWO == Natural::ONE, "n must be odd");
+    let mut a = a.mod_op(Integer::from(n)).unsigned_abs();
+    let mut n = n.clone();
+    let mut result = 1;
+    while a != Natural::ZERO {
+        while &a % Natural::TWO == Natural::ZERO {
+            a /= Natural::TWO;
+            let r = &n % Natural::from(8u8);
+            if r == 3 || r == 5 {
+                result = -result;
+            }
+        }
+        (a, n) = (n, a);
+        if &a % Natural::from(4u8) == 3 && &n % Natural::from(4u8) == 3 {
+            result = -result;
+        }
+        a = a.mod_op(&n);
+    }
+    if n == Natural::ONE {
+        result
+    } else {
+        0
+    }
+}
+
+/// The Legendre symbol (a/p) for an odd prime p
+pub fn legendre_symbol(a: &Integer, p: &Natural) -> i8 {
+    jacobi_symbol(a, p)
+}
+
+/// The Kronecker symbol (a/n), extending the Jacobi symbol to all integers n
+pub fn kronecker_symbol(a: &Integer, n: &Integer) -> i8 {
+    use malachite_base::num::arithmetic::traits::Mod;
+    use malachite_base::num::arithmetic::traits::UnsignedAbs;
+    if *n == Integer::ZERO {
+        return if *a == Integer::ONE || *a == -Integer::ONE {
+            1
+        } else {
+            0
+        };
+    }
+    let mut result = 1;
+    if *n < Integer::ZERO && *a < Integer::ZERO {
+        result = -result;
+    }
+    // |n| = 2^v * m with m odd
+    let mut m = n.unsigned_abs();
+    while &m % Natural::TWO == Natural::ZERO {
+        m /= Natural::TWO;
+        // the Kronecker symbol (a/2)
+        match a.mod_op(Integer::from(8)).unsigned_abs() {
+            r if r == 1 || r == 7 => {}
+            r if r == 3 || r == 5 => {
+                result = -result;
+            }
+            _ => {
+                return 0;
+            }
+        }
+    }
+    result * jacobi_symbol(a, &m)
+}
+
+/// Return a square root of a modulo an odd prime p if one exists
+pub fn sqrt_mod_prime(a: &Natural, p: &Natural) -> Option<Natural> {
+    use malachite_base::num::arithmetic::traits::{Mod, ModPow};
+    let a = a.mod_op(p);
+    if a == Natural::ZERO {
+        return Some(Natural::ZERO);
+    }
+    if *p == Natural::TWO {
+        return Some(a);
+    }
+    if jacobi_symbol(&Integer::from(&a), p) != 1 {
+        return None;
+    }
+    if p % Natural::from(4u8) == 3 {
+        return Some((&a).mod_pow((p + Natural::ONE) / Natural::from(4u8), p));
+    }
+    // Write p-1 = 2^s * q with q odd
+    let mut s = 0usize;
+    let mut q = p - Natural::ONE;
+    while &q % Natural::TWO == Natural::ZERO {
+        q /= Natural::TWO;
+        s += 1;
+    }
+    // Tonelli-Shanks takes O(s^2) multiplications whereas Cipolla takes O(log p) multiplications over F_{p^2}
+    if s * s < 8 * bitcount(p) {
+        Some(tonelli_shanks(&a, p, s, &q))
+    } else {
+        Some(cipolla(&a, p))
+    }
+}
+
+fn tonelli_shanks(a: &Natural, p: &Natural, s: usize, q: &Natural) -> Natural {
+    use malachite_base::num::arithmetic::traits::ModPow;
+    // find a quadratic non-residue
+    let mut z = Natural::TWO;
+    while jacobi_symbol(&Integer::from(&z), p) != -1 {
+        z += Natural::ONE;
+    }
+    let mut m = s;
+    let mut c = z.mod_pow(q, p);
+    let mut t = a.mod_pow(q, p);
+    let mut r = a.mod_pow((q + Natural::ONE) / Natural::TWO, p);
+    while t != Natural::ONE {
+        // find the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while t2 != Natural::ONE {
+            t2 = (&t2 * &t2) % p;
+            i += 1;
+        }
+        debug_assert!(i < m);
+        let mut b = c.clone();
+        for _ in 0..(m - i - 1) {
+            b = (&b * &b) % p;
+        }
+        m = i;
+        c = (&b * &b) % p;
+        t = (t * &c) % p;
+        r = (r * b) % p;
+    }
+    r
+}
+
+fn cipolla(a: &Natural, p: &Natural) -> Natural {
+    use malachite_base::num::arithmetic::traits::ModSub;
+    use malachite_base::num::logic::traits::BitIterable;
+    // find t such that t^2-a is a quadratic non-residue
+    let mut t = Natural::ONE;
+    let w = loop {
+        let w = (&t * &t).mod_sub(a, p);
+        if jacobi_symbol(&Integer::from(&w), p) == -1 {
+            break w;
+        }
+        t += Natural::ONE;
+    };
+    // compute (t + sqrt(w))^((p+1)/2) in F_p[sqrt(w)]
+    let mul = |(x1, y1): &(Natural, Natural), (x2, y2): &(Natural, Natural)| {
+        ((x1 * x2 + y1 * y2 % p * &w) % p, (x1 * y2 + x2 * y1) % p)
+    };
+    let mut result = (Natural::ONE, Natural::ZERO);
+    let mut power = (t, Natural::ONE);
+    for bit in ((p + Natural::ONE) / Natural::TWO).bits() {
+        if bit {
+            result = mul(&result, &power);
+        }
+        power = mul(&power, &power);
+    }
+    debug_assert_eq!(result.1, Natural::ZERO);
+    result.0
+}
+
+/// Return all square roots of a modulo n in increasing order
+pub fn all_sqrt_mod(a: &Integer, n: &Natural) -> Vec<Natural> {
+    factor(n.clone())
+        .expect("modulus must be non-zero")
+        .all_sqrt_mod(a)
+}
+
+/// Return the smallest square root of a modulo n if there is one
+pub fn sqrt_mod(a: &Integer, n: &Natural) -> Option<Natural> {
+    all_sqrt_mod(a, n).into_iter().next()
+}
+
+/// Given congruences x = a_i mod m_i, where the moduli need not be coprime, return (x, m) such that the solutions are exactly x mod m with 0 <= x < m.
+/// Return None if the congruences are inconsistent.
+pub fn chinese_remainder(congruences: &[(Integer, Natural)]) -> Option<(Natural, Natural)> {
+    use malachite_base::num::arithmetic::traits::Mod;
+    use malachite_base::num::arithmetic::traits::UnsignedAbs;
+    let mut x = Integer::ZERO;
+    let mut m = Natural::ONE;
+    for (a, n) in congruences {
+        assert_ne!(*n, Natural::ZERO, "moduli must be non-zero");
+        // solve x + m*k = a mod n
+        let (g, u, _v) = Integer::xgcd(&Integer::from(&m), &Integer::from(n));
+        let g_nat = g.unsigned_abs_ref().clone();
+        let diff = a - &x;
+        if (&diff).mod_op(&g) != Integer::ZERO {
+            return None;
+        }
+        let n_over_g = Integer::from(n / &g_nat);
+        // k = u * diff / g mod n/g
+        let k = (u * (diff / &g)).mod_op(&n_over_g);
+        x += Integer::from(&m) * k;
+        m = &m * (n / &g_nat);
+        x = x.mod_op(Integer::from(&m));
+    }
+    Some((x.unsigned_abs(), m))
+}
+
+/// Return the rational number n/d with |n|, d <= sqrt(m/2), gcd(n, d) = 1 and n = a * d mod m if there is one.
+/// It is unique when it exists.
+pub fn rational_reconstruction(a: &Integer, m: &Natural) -> Option<Rational> {
+    use malachite_base::num::arithmetic::traits::{FloorSqrt, Mod};
+    let m_int = Integer::from(m);
+    let bound = Integer::from((m >> 1u32).floor_sqrt());
+    //the extended euclidean algorithm on m and a, stopped once the remainder is at most the bound
+    let (mut r0, mut r1) = (m_int.clone(), a.mod_op(&m_int));
+    let (mut t0, mut t1) = (Integer::ZERO, Integer::ONE);
+    while r1 > bound {
+        let q = &r0 / &r1;
+        (r0, r1) = (r1.clone(), r0 - &q * &r1);
+        (t0, t1) = (t1.clone(), t0 - &q * &t1);
+    }
+    if t1 == Integer::ZERO
+        || *t1.unsigned_abs_ref() > *bound.unsigned_abs_ref()
+        || gcd(r1.unsigned_abs_ref().clone(), t1.unsigned_abs_ref().clone()) != Natural::ONE
+    {
+        None
+    } else {
+        Some(Rational::from_integers(r1, t1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -425,4 +648,149 @@ mod tests {
             Natural::from(3usize)
         );
     }
+
+    #[test]
+    fn test_jacobi_symbol() {
+        // the columns of the table of Jacobi symbols (a/n) for a = 0..8
+        let table: Vec<(u32, Vec<i8>)> = vec![
+            (1, vec![1, 1, 1, 1, 1, 1, 1, 1, 1]),
+            (3, vec![0, 1, -1, 0, 1, -1, 0, 1, -1]),
+            (5, vec![0, 1, -1, -1, 1, 0, 1, -1, -1]),
+            (9, vec![0, 1, 1, 0, 1, 1, 0, 1, 1]),
+            (15, vec![0, 1, 1, 0, 1, 0, 0, -1, 1]),
+        ];
+        for (n, row) in table {
+            for (a, j) in row.into_iter().enumerate() {
+                assert_eq!(jacobi_symbol(&Integer::from(a), &Natural::from(n)), j);
+            }
+        }
+        assert_eq!(jacobi_symbol(&Integer::from(-1), &Natural::from(7u32)), -1);
+        assert_eq!(jacobi_symbol(&Integer::from(-1), &Natural::from(13u32)), 1);
+        assert_eq!(
+            jacobi_symbol(&Integer::from(1001), &Natural::from(9907u32)),
+            -1
+        );
+    }
+
+    #[test]
+    fn test_kronecker_symbol() {
+        assert_eq!(kronecker_symbol(&Integer::from(1), &Integer::from(0)), 1);
+        assert_eq!(kronecker_symbol(&Integer::from(-1), &Integer::from(0)), 1);
+        assert_eq!(kronecker_symbol(&Integer::from(2), &Integer::from(0)), 0);
+        assert_eq!(kronecker_symbol(&Integer::from(3), &Integer::from(2)), -1);
+        assert_eq!(kronecker_symbol(&Integer::from(7), &Integer::from(2)), 1);
+        assert_eq!(kronecker_symbol(&Integer::from(4), &Integer::from(2)), 0);
+        assert_eq!(kronecker_symbol(&Integer::from(-5), &Integer::from(-1)), -1);
+        assert_eq!(kronecker_symbol(&Integer::from(5), &Integer::from(-1)), 1);
+        assert_eq!(kronecker_symbol(&Integer::from(5), &Integer::from(12)), -1);
+        assert_eq!(kronecker_symbol(&Integer::from(-3), &Integer::from(-12)), 0);
+        // agrees with the Jacobi symbol for odd positive n
+        for a in -20..20 {
+            for n in (1..30).step_by(2) {
+                assert_eq!(
+                    kronecker_symbol(&Integer::from(a), &Integer::from(n)),
+                    jacobi_symbol(&Integer::from(a), &Natural::from(n as u32))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_mod_prime() {
+        for p in [3u32, 5, 7, 13, 17, 41, 97, 257, 65537] {
+            let p = Natural::from(p);
+            let mut a = Natural::ZERO;
+            while a < p {
+                match sqrt_mod_prime(&a, &p) {
+                    Some(r) => assert_eq!((&r * &r) % &p, a),
+                    None => assert_eq!(jacobi_symbol(&Integer::from(&a), &p), -1),
+                }
+                a += Natural::ONE;
+            }
+        }
+        // a prime with large 2-adic valuation of p-1 so that Cipolla is used
+        let p = Natural::from(3u32) * (Natural::ONE << 30usize) + Natural::ONE;
+        let a = Natural::from(123456789u32);
+        let r = sqrt_mod_prime(&a, &p).unwrap();
+        assert_eq!((&r * &r) % &p, a);
+        let r = cipolla(&Natural::from(10u32), &Natural::from(13u32));
+        assert_eq!((&r * &r) % Natural::from(13u32), Natural::from(10u32));
+    }
+
+    #[test]
+    fn test_all_sqrt_mod() {
+        for n in 1u32..200 {
+            let n_nat = Natural::from(n);
+            for a in -3i32..(n as i32) {
+                let a_int = Integer::from(a);
+                let mut expected = vec![];
+                for x in 0..n {
+                    if (Integer::from(x * x) - &a_int) % Integer::from(n) == 0 {
+                        expected.push(Natural::from(x));
+                    }
+                }
+                assert_eq!(all_sqrt_mod(&a_int, &n_nat), expected);
+                assert_eq!(sqrt_mod(&a_int, &n_nat), expected.into_iter().next());
+            }
+        }
+    }
+
+    #[test]
+    fn test_chinese_remainder() {
+        assert_eq!(chinese_remainder(&[]), Some((Natural::ZERO, Natural::ONE)));
+        assert_eq!(
+            chinese_remainder(&[
+                (Integer::from(2), Natural::from(3u32)),
+                (Integer::from(3), Natural::from(5u32)),
+                (Integer::from(2), Natural::from(7u32)),
+            ]),
+            Some((Natural::from(23u32), Natural::from(105u32)))
+        );
+        // non-coprime moduli
+        assert_eq!(
+            chinese_remainder(&[
+                (Integer::from(3), Natural::from(4u32)),
+                (Integer::from(5), Natural::from(6u32)),
+            ]),
+            Some((Natural::from(11u32), Natural::from(12u32)))
+        );
+        assert_eq!(
+            chinese_remainder(&[
+                (Integer::from(1), Natural::from(4u32)),
+                (Integer::from(2), Natural::from(6u32)),
+            ]),
+            None
+        );
+        assert_eq!(
+            chinese_remainder(&[
+                (Integer::from(-1), Natural::from(10u32)),
+                (Integer::from(4), Natural::from(15u32)),
+            ]),
+            Some((Natural::from(19u32), Natural::from(30u32)))
+        );
+    }
+
+    #[test]
+    fn test_rational_reconstruction() {
+        let m = Natural::from(1000003u32);
+        for (n, d) in [(0, 1), (1, 1), (-3, 7), (22, 41), (-700, 1), (1, 706)] {
+            let x = Rational::from_integers(Integer::from(n), Integer::from(d));
+            //the image of n/d modulo m
+            let (a, _) = chinese_remainder(&[(Integer::from(n), m.clone())]).unwrap();
+            let d_inv = Integer::xgcd(&Integer::from(d), &Integer::from(&m)).1;
+            assert_eq!(
+                rational_reconstruction(&(Integer::from(a) * d_inv), &m),
+                Some(x)
+            );
+        }
+        assert_eq!(
+            rational_reconstruction(&Integer::from(500001), &Natural::from(1000003u32)),
+            Some(Rational::from_integers(Integer::from(-1), Integer::from(2)))
+        );
+        //no fraction with small enough numerator and denominator
+        assert_eq!(
+            rational_reconstruction(&Integer::from(271828), &Natural::from(1000003u32)),
+            None
+        );
+    }
 }
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 
 use algebraeon_sets::structure::*;
+use malachite_base::num::arithmetic::traits::UnsignedAbs;
 use malachite_base::num::basic::traits::{One, Two, Zero};
-use malachite_nz::natural::Natural;
+use malachite_nz::{integer::Integer, natural::Natural};
 
+use crate::polynomial::multiplication::*;
 use crate::structure::structure::*;
 
 pub mod factor;
 pub mod functions;
 pub mod primes;
+pub mod sieve;
 
 impl SemiRingStructure for CannonicalStructure<Natural> {
     fn zero(&self) -> Self::Set {
@@ -23,6 +26,16 @@ impl SemiRingStructure for CannonicalStructure<Natural> {
     fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
         a * b
     }
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        if a.len().min(b.len()) < NTT_THRESHOLD {
+            return schoolbook_mul(self, a, b);
+        }
+        let lift = |x: &[Natural]| x.iter().map(Integer::from).collect::<Vec<_>>();
+        integer_polynomial_mul(&lift(a), &lift(b))
+            .into_iter()
+            .map(|c| c.unsigned_abs())
+            .collect()
+    }
 }
 
 pub(crate) fn nat_to_usize(n: &Natural) -> Result<usize, ()> {
@@ -9,19 +9,24 @@ use malachite_q::Rational;
 use crate::structure::quotient::QuotientStructure;
 
 use super::functions::*;
+use super::sieve::*;
 use super::*;
 
+/// Iterate over all primes in increasing order, sieving one segment at a time
 #[derive(Debug)]
 pub struct PrimeGenerator {
-    n: Natural,
-    primes: Vec<Natural>,
+    // all primes below sieved_to
+    primes: Vec<usize>,
+    sieved_to: usize,
+    idx: usize,
 }
 
 impl PrimeGenerator {
     pub fn new() -> Self {
         Self {
-            n: Natural::from(2u8),
             primes: vec![],
+            sieved_to: 0,
+            idx: 0,
         }
     }
 }
@@ -30,19 +35,25 @@ impl Iterator for PrimeGenerator {
     type Item = Natural;
 
     fn next(&mut self) -> Option<Self::Item> {
-        'next_loop: loop {
-            //todo: only check primes up to sqrt n
-            for p in &self.primes {
-                if &self.n % p == 0 {
-                    self.n += Natural::from(1u8);
-                    continue 'next_loop;
+        while self.idx == self.primes.len() {
+            if self.sieved_to == 0 {
+                self.sieved_to = 1024;
+                self.primes = primes_below(self.sieved_to);
+            } else {
+                // Every prime up to sqrt(hi) is already known since hi <= sieved_to^2
+                let lo = self.sieved_to;
+                let hi = lo + std::cmp::min(lo, 1 << 18);
+                for (i, b) in sieve_segment(lo, hi, &self.primes).into_iter().enumerate() {
+                    if b {
+                        self.primes.push(lo + i);
+                    }
                 }
+                self.sieved_to = hi;
             }
-            let next_p = self.n.clone();
-            self.n += Natural::from(1u8);
-            self.primes.push(next_p.clone());
-            return Some(next_p);
         }
+        let p = self.primes[self.idx];
+        self.idx += 1;
+        Some(Natural::from(p))
     }
 }
 
@@ -529,6 +540,251 @@ pub fn is_prime(n: &Natural) -> bool {
     }
 }
 
+/// A Fenwick tree over a sieved segment, used to count the unsieved entries below a point
+struct SegmentCounter {
+    tree: Vec<i64>,
+}
+
+impl SegmentCounter {
+    fn new(is_set: &[bool]) -> Self {
+        let n = is_set.len();
+        let mut tree: Vec<i64> = is_set.iter().map(|b| *b as i64).collect();
+        for i in 0..n {
+            let j = i | (i + 1);
+            if j < n {
+                tree[j] += tree[i];
+            }
+        }
+        Self { tree }
+    }
+
+    fn remove(&mut self, mut i: usize) {
+        while i < self.tree.len() {
+            self.tree[i] -= 1;
+            i |= i + 1;
+        }
+    }
+
+    // The number of set entries with index at most i
+    fn count_to(&self, i: usize) -> i64 {
+        let mut i = i + 1;
+        let mut total = 0;
+        while i > 0 {
+            total += self.tree[i - 1];
+            i &= i - 1;
+        }
+        total
+    }
+}
+
+// Count the primes up to x using the Lagarias-Miller-Odlyzko variant of the Meissel-Lehmer method
+//
+// With y >= cbrt(x) and a = pi(y) we have pi(x) = phi(x, a) + a - 1 - P2(x, a) where phi(x, a) counts integers at most x with no prime factor among the first a primes.
+// phi(x, a) splits into ordinary leaves mu(n)*floor(x/n) for n <= y, and special leaves -mu(m)*phi(x/(p_b*m), b-1) for m <= y < m*p_b with lpf(m) > p_b.
+// The special leaves are all at most x/y and are found by a segmented sieve over [1, x/y] counting with a Fenwick tree.
+fn lmo_prime_count(x: usize) -> usize {
+    let x_f64 = x as f64;
+    let alpha = std::cmp::max(1, (x_f64.ln().powi(2) / 200.0) as usize);
+    let sqrt_x = isqrt_usize(x);
+    let y = std::cmp::min(icbrt_usize(x) * alpha, sqrt_x);
+    let z = x / y;
+
+    let primes = primes_below(sqrt_x + 1);
+    let a = primes.partition_point(|p| *p <= y);
+
+    // Least prime factor and Mobius function up to y. lpf(1) is taken to be infinite.
+    let mut lpf = vec![usize::MAX; y + 1];
+    let mut mu = vec![1i64; y + 1];
+    for &p in &primes[0..a] {
+        let mut k = p;
+        while k <= y {
+            if lpf[k] == usize::MAX {
+                lpf[k] = p;
+            }
+            mu[k] = -mu[k];
+            k += p;
+        }
+        let mut k = p * p;
+        while k <= y {
+            mu[k] = 0;
+            k += p * p;
+        }
+    }
+
+    // Ordinary leaves
+    let mut phi_x_a: i64 = 0;
+    for (n, mu_n) in mu.iter().enumerate().skip(1) {
+        phi_x_a += mu_n * (x / n) as i64;
+    }
+
+    // Special leaves
+    if a >= 2 {
+        let limit = z + 1;
+        let segment_size = std::cmp::max(isqrt_usize(limit), 1 << 14).next_power_of_two();
+        // phi_lo[k] = phi(lo - 1, k)
+        let mut phi_lo = vec![0i64; a];
+        let mut lo = 1;
+        while lo < limit {
+            let hi = std::cmp::min(lo + segment_size, limit);
+            let mut unsieved = vec![true; hi - lo];
+            let mut counter = SegmentCounter::new(&unsieved);
+            let mut unsieved_count = (hi - lo) as i64;
+            for k in 0..(a - 1) {
+                // The leaves for p = p_{k+1} need phi(v, k) where the first k primes have been sieved
+                let p = primes[k];
+                let m_min = std::cmp::max(y / p, x / (p * hi)) + 1;
+                let m_max = std::cmp::min(y, x / (p * lo));
+                if m_min <= m_max {
+                    let mut leaf = |m: usize| {
+                        let v = x / (p * m);
+                        debug_assert!(lo <= v && v < hi);
+                        phi_x_a -= mu[m] * (phi_lo[k] + counter.count_to(v - lo));
+                    };
+                    if p * p > y {
+                        // m has no prime factor at most p and m <= y < p^2 so m is prime
+                        let start = primes.partition_point(|q| *q < std::cmp::max(m_min, p + 1));
+                        for &m in primes[start..a].iter().take_while(|q| **q <= m_max) {
+                            leaf(m);
+                        }
+                    } else {
+                        for m in m_min..=m_max {
+                            if mu[m] != 0 && lpf[m] > p {
+                                leaf(m);
+                            }
+                        }
+                    }
+                }
+                phi_lo[k] += unsieved_count;
+                // sieve out p
+                let mut n = lo.div_ceil(p) * p;
+                while n < hi {
+                    if unsieved[n - lo] {
+                        unsieved[n - lo] = false;
+                        counter.remove(n - lo);
+                        unsieved_count -= 1;
+                    }
+                    n += p;
+                }
+            }
+            lo = hi;
+        }
+    }
+
+    // P2(x, a) = sum over primes y < p <= sqrt(x) of pi(x/p) - pi(p) + 1
+    let mut p2: i64 = 0;
+    {
+        let sieving_primes = primes_below(isqrt_usize(z) + 1);
+        let mut pi_lo = primes.len() as i64;
+        let mut lo = sqrt_x + 1;
+        let mut b = primes.len();
+        while b > a {
+            let target = x / primes[b - 1];
+            debug_assert!(target >= sqrt_x);
+            while lo <= target {
+                let hi = std::cmp::min(lo + SEGMENT_SIZE, z + 1);
+                let is_prime = sieve_segment(lo, hi, &sieving_primes);
+                // account for every target in this segment, which come in increasing order
+                let mut i = 0;
+                while b > a && x / primes[b - 1] < hi {
+                    let t = x / primes[b - 1];
+                    while lo + i <= t {
+                        if is_prime[i] {
+                            pi_lo += 1;
+                        }
+                        i += 1;
+                    }
+                    p2 += pi_lo - b as i64 + 1;
+                    b -= 1;
+                }
+                pi_lo += is_prime[i..].iter().filter(|b| **b).count() as i64;
+                lo = hi;
+            }
+            if b > a && x / primes[b - 1] < lo {
+                // target lies before the first segment, so it is at most sqrt(x)
+                let t = x / primes[b - 1];
+                let pi_t = primes.partition_point(|q| *q <= t) as i64;
+                p2 += pi_t - b as i64 + 1;
+                b -= 1;
+            }
+        }
+    }
+
+    (phi_x_a + a as i64 - 1 - p2) as usize
+}
+
+pub(crate) fn prime_count_usize(x: usize) -> usize {
+    if x < 1 << 20 {
+        primes_below(x + 1).len()
+    } else {
+        lmo_prime_count(x)
+    }
+}
+
+/// The number of primes p with p <= x, or an error if x is more than `usize::MAX / 4`
+pub fn prime_count(x: &Natural) -> Result<Natural, &'static str> {
+    match nat_to_usize(x) {
+        Ok(x) if x <= SIEVE_LIMIT => Ok(Natural::from(prime_count_usize(x))),
+        _ => Err("too large to count the primes up to"),
+    }
+}
+
+/// The nth prime, indexed from zero so that `nth_prime(0) = 2` in agreement with `PrimeGenerator::new().nth(n)`,
+/// or an error if the primes up to twice its estimate would exceed `usize::MAX / 4`
+pub fn nth_prime(n: usize) -> Result<Natural, &'static str> {
+    // k is the 1-indexed position of the prime we want
+    let k = n.checked_add(1).ok_or("too large to find the nth prime")?;
+    if k < 1000 {
+        return Ok(Natural::from(primes_below(8000)[n]));
+    }
+    let kf = k as f64;
+    let (ln_k, ln_ln_k) = (kf.ln(), kf.ln().ln());
+    // the kth prime is less than k(ln k + ln ln k) for k >= 6 (Rosser), and the walk below may sieve up to twice the estimate
+    if 2.0 * kf * (ln_k + ln_ln_k) > SIEVE_LIMIT as f64 {
+        return Err("too large to find the nth prime");
+    }
+    // An approximation to the kth prime due to Cipolla
+    let x = (kf * (ln_k + ln_ln_k - 1.0 + (ln_ln_k - 2.0) / ln_k)) as usize;
+    let c = prime_count_usize(x);
+    let base_primes = primes_below(isqrt_usize(2 * x) + 1);
+    if c < k {
+        // walk up from x
+        let mut remaining = k - c;
+        let mut lo = x + 1;
+        loop {
+            let hi = lo + SEGMENT_SIZE;
+            for (i, b) in sieve_segment(lo, hi, &base_primes).into_iter().enumerate() {
+                if b {
+                    remaining -= 1;
+                    if remaining == 0 {
+                        return Ok(Natural::from(lo + i));
+                    }
+                }
+            }
+            lo = hi;
+        }
+    } else {
+        // walk down from x
+        let mut remaining = c - k;
+        let mut hi = x + 1;
+        loop {
+            let lo = hi.saturating_sub(SEGMENT_SIZE);
+            for (i, b) in sieve_segment(lo, hi, &base_primes)
+                .into_iter()
+                .enumerate()
+                .rev()
+            {
+                if b {
+                    if remaining == 0 {
+                        return Ok(Natural::from(lo + i));
+                    }
+                    remaining -= 1;
+                }
+            }
+            hi = lo;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -634,4 +890,55 @@ mod tests {
         //     PrimalityTestResult::Prime
         // );
     }
+
+    #[test]
+    fn test_prime_generator() {
+        let primes = PrimeGenerator::new().take(200000).collect::<Vec<_>>();
+        assert_eq!(primes[0], Natural::from(2u32));
+        assert_eq!(primes[9999], Natural::from(104729u32));
+        assert_eq!(primes[199999], Natural::from(2750159u32));
+        for p in &primes[0..1000] {
+            assert!(is_prime(p));
+        }
+    }
+
+    #[test]
+    fn test_prime_count() {
+        for x in 0usize..200 {
+            assert_eq!(
+                prime_count(&Natural::from(x)).unwrap(),
+                Natural::from(PrimeGenerator::new().take_while(|p| p <= &x).count())
+            );
+        }
+        assert_eq!(
+            prime_count(&Natural::from(1000000u32)).unwrap(),
+            Natural::from(78498u32)
+        );
+        assert_eq!(lmo_prime_count(1000000), 78498);
+        for x in [1048575usize, 1048576, 1048583, 1234567, 4999999] {
+            assert_eq!(lmo_prime_count(x), primes_below(x + 1).len());
+        }
+        assert_eq!(
+            prime_count(&Natural::from(10000000u32)).unwrap(),
+            Natural::from(664579u32)
+        );
+        assert_eq!(
+            prime_count(&Natural::from(100000000u32)).unwrap(),
+            Natural::from(5761455u32)
+        );
+        assert!(prime_count(&(Natural::ONE << 64)).is_err());
+    }
+
+    #[test]
+    fn test_nth_prime() {
+        let primes = PrimeGenerator::new().take(2000).collect::<Vec<_>>();
+        for (n, p) in primes.iter().enumerate() {
+            assert_eq!(&nth_prime(n).unwrap(), p);
+        }
+        assert_eq!(nth_prime(9999).unwrap(), Natural::from(104729u32));
+        assert_eq!(nth_prime(99999).unwrap(), Natural::from(1299709u32));
+        assert_eq!(nth_prime(999999).unwrap(), Natural::from(15485863u32));
+        assert!(nth_prime(usize::MAX).is_err());
+        assert!(nth_prime(usize::MAX >> 6).is_err());
+    }
 }
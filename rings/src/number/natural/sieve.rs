@@ -0,0 +1,197 @@
+use super::*;
+
+/// Return all primes p with p < n using a sieve of Eratosthenes
+pub(crate) fn primes_below(n: usize) -> Vec<usize> {
+    if n <= 2 {
+        return vec![];
+    }
+    let mut is_prime = vec![true; n];
+    is_prime[0] = false;
+    is_prime[1] = false;
+    let mut p = 2;
+    while p * p < n {
+        if is_prime[p] {
+            let mut k = p * p;
+            while k < n {
+                is_prime[k] = false;
+                k += p;
+            }
+        }
+        p += 1;
+    }
+    is_prime
+        .into_iter()
+        .enumerate()
+        .filter_map(|(k, b)| if b { Some(k) } else { None })
+        .collect()
+}
+
+/// Sieve the segment [lo, hi). Entry i of the result is true iff lo+i is prime.
+/// base_primes must contain every prime p with p*p < hi.
+pub(crate) fn sieve_segment(lo: usize, hi: usize, base_primes: &[usize]) -> Vec<bool> {
+    debug_assert!(lo <= hi);
+    let mut is_prime = vec![true; hi - lo];
+    for n in lo..std::cmp::min(2, hi) {
+        is_prime[n - lo] = false;
+    }
+    for &p in base_primes {
+        if p * p >= hi {
+            break;
+        }
+        // the first multiple of p in the segment, skipping p itself
+        let mut k = std::cmp::max(p * p, lo.div_ceil(p) * p);
+        while k < hi {
+            is_prime[k - lo] = false;
+            k += p;
+        }
+    }
+    is_prime
+}
+
+/// The largest integer r with r*r <= n
+pub(crate) fn isqrt_usize(n: usize) -> usize {
+    let mut r = (n as f64).sqrt() as usize;
+    while r * r > n {
+        r -= 1;
+    }
+    while (r + 1) * (r + 1) <= n {
+        r += 1;
+    }
+    r
+}
+
+/// The largest integer r with r*r*r <= n
+pub(crate) fn icbrt_usize(n: usize) -> usize {
+    let mut r = (n as f64).cbrt() as usize;
+    while r * r * r > n {
+        r -= 1;
+    }
+    while (r + 1) * (r + 1) * (r + 1) <= n {
+        r += 1;
+    }
+    r
+}
+
+pub(crate) const SEGMENT_SIZE: usize = 1 << 16;
+
+/// The largest bound the sieves accept, leaving room for the usize arithmetic near the bound
+pub(crate) const SIEVE_LIMIT: usize = usize::MAX >> 2;
+
+/// Iterate over the primes p with a <= p < b in increasing order using a segmented sieve of Eratosthenes.
+///
+/// Only the primes up to sqrt(b) and a single segment of the range are held in memory at any time, so this is suitable for windows far from zero.
+#[derive(Debug, Clone)]
+pub struct SegmentedPrimeSieve {
+    base_primes: Vec<usize>,
+    lo: usize,
+    end: usize,
+    segment: Vec<usize>,
+    segment_idx: usize,
+}
+
+impl SegmentedPrimeSieve {
+    /// The sieve of [a, b), or an error if b is more than `usize::MAX / 4`
+    pub fn new(a: &Natural, b: &Natural) -> Result<Self, &'static str> {
+        let end = match nat_to_usize(b) {
+            Ok(b) if b <= SIEVE_LIMIT => b,
+            _ => return Err("too large to sieve up to"),
+        };
+        let a = std::cmp::min(nat_to_usize(a).unwrap_or(end), end);
+        Ok(Self {
+            base_primes: primes_below(isqrt_usize(end) + 1),
+            lo: a,
+            end,
+            segment: vec![],
+            segment_idx: 0,
+        })
+    }
+}
+
+impl Iterator for SegmentedPrimeSieve {
+    type Item = Natural;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.segment_idx == self.segment.len() {
+            if self.lo >= self.end {
+                return None;
+            }
+            let hi = std::cmp::min(self.lo.saturating_add(SEGMENT_SIZE), self.end);
+            self.segment = sieve_segment(self.lo, hi, &self.base_primes)
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, b)| if b { Some(self.lo + i) } else { None })
+                .collect();
+            self.segment_idx = 0;
+            self.lo = hi;
+        }
+        let p = self.segment[self.segment_idx];
+        self.segment_idx += 1;
+        Some(Natural::from(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::natural::primes::*;
+
+    #[test]
+    fn test_primes_below() {
+        assert_eq!(primes_below(0), Vec::<usize>::new());
+        assert_eq!(primes_below(2), Vec::<usize>::new());
+        assert_eq!(primes_below(3), vec![2]);
+        assert_eq!(primes_below(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_below(31), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(
+            primes_below(32),
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31]
+        );
+    }
+
+    #[test]
+    fn test_segmented_sieve() {
+        assert_eq!(
+            SegmentedPrimeSieve::new(&Natural::from(0u32), &Natural::from(20u32))
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![2u32, 3, 5, 7, 11, 13, 17, 19]
+                .into_iter()
+                .map(Natural::from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            SegmentedPrimeSieve::new(&Natural::from(7u32), &Natural::from(7u32))
+                .unwrap()
+                .count(),
+            0
+        );
+        assert_eq!(
+            SegmentedPrimeSieve::new(&Natural::from(0u32), &Natural::from(1000000u32))
+                .unwrap()
+                .count(),
+            78498
+        );
+
+        // a window far from zero which spans several segments
+        let a = Natural::from(1000000000000u64);
+        let b = Natural::from(1000000200000u64);
+        let sieved = SegmentedPrimeSieve::new(&a, &b)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(sieved[0], Natural::from(1000000000039u64));
+        for p in &sieved {
+            assert!(&a <= p && p < &b);
+        }
+        let mut n = a.clone();
+        let end = Natural::from(1000000001000u64);
+        let mut expected = vec![];
+        while n < end {
+            if is_prime(&n) {
+                expected.push(n.clone());
+            }
+            n += Natural::ONE;
+        }
+        assert_eq!(sieved[0..expected.len()], expected);
+        assert!(SegmentedPrimeSieve::new(&Natural::ZERO, &(Natural::ONE << 64)).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+
+use malachite_base::num::arithmetic::traits::Mod;
+use malachite_base::num::basic::traits::{One, Two, Zero};
+use malachite_nz::{integer::Integer, natural::Natural};
+
+use super::BinaryQuadraticForm;
+use crate::linear::matrix::Matrix;
+use crate::number::natural::functions::sqrt_floor;
+
+/// The form class group of a non-square discriminant D: the proper equivalence classes of primitive forms of discriminant D under composition.
+/// For D < 0 only positive definite forms are considered, and for D > 0 this is the narrow class group.
+///
+/// All reduced forms are enumerated, so this is suitable for discriminants up to about 10^8 when D < 0 and 10^6 when D > 0.
+#[derive(Debug, Clone)]
+pub struct FormClassGroup {
+    discriminant: Integer,
+    //a canonical representative of each class, the reduced form when D < 0 and the least form in the cycle when D > 0
+    representatives: Vec<BinaryQuadraticForm>,
+    //the index of the class of each reduced form
+    classes: HashMap<BinaryQuadraticForm, usize>,
+}
+
+impl FormClassGroup {
+    pub fn new(discriminant: Integer) -> Result<Self, &'static str> {
+        let d = discriminant;
+        let r = (&d).mod_op(Integer::from(4));
+        if r != Integer::ZERO && r != Integer::ONE {
+            return Err("the discriminant must be 0 or 1 modulo 4");
+        }
+        if d >= Integer::ZERO {
+            let s = Integer::from(sqrt_floor(d.unsigned_abs_ref()));
+            if &s * &s == d {
+                return Err("the discriminant must not be a square");
+            }
+        }
+        let reduced = if d < Integer::ZERO {
+            Self::reduced_positive_definite_forms(&d)
+        } else {
+            Self::reduced_indefinite_forms(&d)
+        };
+        let mut representatives = vec![];
+        let mut classes = HashMap::new();
+        for f in reduced {
+            if classes.contains_key(&f) {
+                continue;
+            }
+            let class = if d < Integer::ZERO {
+                vec![f]
+            } else {
+                f.cycle()
+            };
+            let idx = representatives.len();
+            representatives.push(class.iter().min().unwrap().clone());
+            for g in class {
+                classes.insert(g, idx);
+            }
+        }
+        Ok(Self {
+            discriminant: d,
+            representatives,
+            classes,
+        })
+    }
+
+    //primitive reduced forms (a, b, c) with |b| <= a <= c, and so 3a^2 <= |D|
+    fn reduced_positive_definite_forms(d: &Integer) -> Vec<BinaryQuadraticForm> {
+        let mut forms = vec![];
+        let mut a = Integer::ONE;
+        while Integer::from(3) * &a * &a <= -d {
+            let mut b = -&a + Integer::ONE;
+            while b <= a {
+                if let Ok(f) = BinaryQuadraticForm::from_discriminant(a.clone(), b.clone(), d) {
+                    if f.is_reduced() && f.is_primitive() {
+                        forms.push(f);
+                    }
+                }
+                b += Integer::ONE;
+            }
+            a += Integer::ONE;
+        }
+        forms
+    }
+
+    //primitive reduced forms (a, b, c) with 0 < b < sqrt(D) and sqrt(D) - b < 2|a| < sqrt(D) + b
+    fn reduced_indefinite_forms(d: &Integer) -> Vec<BinaryQuadraticForm> {
+        let s = Integer::from(sqrt_floor(d.unsigned_abs_ref()));
+        let mut forms = vec![];
+        let mut b = d.mod_op(Integer::TWO);
+        if b == Integer::ZERO {
+            b = Integer::TWO;
+        }
+        while b <= s {
+            let mut a = (&s - &b) / Integer::TWO + Integer::ONE;
+            while Integer::TWO * &a <= &s + &b {
+                for a in [a.clone(), -&a] {
+                    if let Ok(f) = BinaryQuadraticForm::from_discriminant(a, b.clone(), d) {
+                        if f.is_reduced() && f.is_primitive() {
+                            forms.push(f);
+                        }
+                    }
+                }
+                a += Integer::ONE;
+            }
+            b += Integer::TWO;
+        }
+        forms
+    }
+
+    pub fn discriminant(&self) -> &Integer {
+        &self.discriminant
+    }
+
+    pub fn class_number(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// The canonical representative of each class.
+    pub fn representatives(&self) -> &Vec<BinaryQuadraticForm> {
+        &self.representatives
+    }
+
+    /// The canonical representative of the class of the principal form.
+    pub fn identity(&self) -> BinaryQuadraticForm {
+        self.class_representative(&BinaryQuadraticForm::principal(&self.discriminant).unwrap())
+            .unwrap()
+    }
+
+    fn class_index(&self, f: &BinaryQuadraticForm) -> Result<usize, &'static str> {
+        if f.discriminant() != self.discriminant {
+            return Err("the form has a different discriminant");
+        }
+        if !f.is_primitive() {
+            return Err("the form is not primitive");
+        }
+        if self.discriminant < Integer::ZERO && !f.is_positive_definite() {
+            return Err("the form is not positive definite");
+        }
+        Ok(*self.classes.get(&f.reduce()).unwrap())
+    }
+
+    /// The canonical representative of the class of a primitive form f of discriminant D, positive definite if D < 0.
+    pub fn class_representative(
+        &self,
+        f: &BinaryQuadraticForm,
+    ) -> Result<BinaryQuadraticForm, &'static str> {
+        Ok(self.representatives[self.class_index(f)?].clone())
+    }
+
+    /// The canonical representative of the composition of the classes of f and g.
+    pub fn compose(
+        &self,
+        f: &BinaryQuadraticForm,
+        g: &BinaryQuadraticForm,
+    ) -> Result<BinaryQuadraticForm, &'static str> {
+        self.class_index(f)?;
+        self.class_index(g)?;
+        self.class_representative(&f.compose(g))
+    }
+
+    fn compose_indices(&self, i: usize, j: usize) -> usize {
+        self.class_index(&self.representatives[i].compose(&self.representatives[j]))
+            .unwrap()
+    }
+
+    /// The order of the class of f in the class group.
+    pub fn order(&self, f: &BinaryQuadraticForm) -> Result<usize, &'static str> {
+        let i = self.class_index(f)?;
+        let identity = self.class_index(&self.identity()).unwrap();
+        let mut power = i;
+        let mut order = 1;
+        while power != identity {
+            power = self.compose_indices(power, i);
+            order += 1;
+        }
+        Ok(order)
+    }
+
+    /// The invariant factors d_1 | d_2 | ... | d_k with each d_i > 1 such that the class group is isomorphic to the product of the cyclic groups of orders d_i.
+    pub fn structure(&self) -> Vec<Natural> {
+        let h = self.class_number();
+        let identity = self.class_index(&self.identity()).unwrap();
+        //each element of the subgroup generated so far, with its exponents in terms of the generators
+        let mut exponents: HashMap<usize, Vec<Integer>> = HashMap::from([(identity, vec![])]);
+        //the relations between the generators, which span the whole relation lattice since each row is lower triangular with the order of the generator modulo the previous subgroup on the diagonal
+        let mut relations: Vec<Vec<Integer>> = vec![];
+        for g in 0..h {
+            if exponents.contains_key(&g) {
+                continue;
+            }
+            let mut k = 1usize;
+            let mut power = g;
+            while !exponents.contains_key(&power) {
+                power = self.compose_indices(power, g);
+                k += 1;
+            }
+            let mut relation = exponents[&power].iter().map(|e| -e).collect::<Vec<_>>();
+            relation.push(Integer::from(k));
+            for row in &mut relations {
+                row.push(Integer::ZERO);
+            }
+            relations.push(relation);
+            let subgroup = exponents.drain().collect::<Vec<_>>();
+            for (element, element_exponents) in subgroup {
+                let mut element = element;
+                for i in 0..k {
+                    let mut element_exponents = element_exponents.clone();
+                    element_exponents.push(Integer::from(i));
+                    exponents.insert(element, element_exponents);
+                    element = self.compose_indices(element, g);
+                }
+            }
+        }
+        debug_assert_eq!(exponents.len(), h);
+        if relations.is_empty() {
+            return vec![];
+        }
+        let n = relations.len();
+        let (_u, s, _v, _k) = Matrix::<Integer>::from_rows(relations).smith_algorithm();
+        (0..n)
+            .map(|i| s.at(i, i).unwrap().unsigned_abs_ref().clone())
+            .filter(|d| d > &Natural::ONE)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naturals(ns: Vec<u32>) -> Vec<Natural> {
+        ns.into_iter().map(Natural::from).collect()
+    }
+
+    #[test]
+    fn test_class_numbers() {
+        for (d, h) in [
+            (-3, 1),
+            (-4, 1),
+            (-7, 1),
+            (-12, 1),
+            (-15, 2),
+            (-20, 2),
+            (-23, 3),
+            (-36, 2),
+            (-47, 5),
+            (-71, 7),
+            (-163, 1),
+        ] {
+            let group = FormClassGroup::new(Integer::from(d)).unwrap();
+            assert_eq!(group.class_number(), h);
+            assert!(group.representatives().iter().all(|f| f.is_reduced()));
+        }
+        //narrow class numbers
+        for (d, h) in [(5, 1), (8, 1), (12, 2), (21, 2), (60, 4), (65, 2), (145, 4)] {
+            let group = FormClassGroup::new(Integer::from(d)).unwrap();
+            assert_eq!(group.class_number(), h);
+        }
+        assert!(FormClassGroup::new(Integer::from(-5)).is_err());
+        assert!(FormClassGroup::new(Integer::from(9)).is_err());
+        assert!(FormClassGroup::new(Integer::from(0)).is_err());
+    }
+
+    #[test]
+    fn test_class_group_structure() {
+        for (d, structure) in [
+            (-3, vec![]),
+            (-23, vec![3]),
+            (-56, vec![4]),
+            (-84, vec![2, 2]),
+            (-420, vec![2, 2, 2]),
+            (-3299, vec![3, 9]),
+            (60, vec![2, 2]),
+            (145, vec![4]),
+        ] {
+            let group = FormClassGroup::new(Integer::from(d)).unwrap();
+            assert_eq!(group.structure(), naturals(structure));
+        }
+    }
+
+    #[test]
+    fn test_class_group_operations() {
+        let group = FormClassGroup::new(Integer::from(-56)).unwrap();
+        let f = BinaryQuadraticForm::new(Integer::from(3), Integer::from(2), Integer::from(5));
+        assert_eq!(group.order(&f), Ok(4));
+        assert_eq!(group.order(&group.identity()), Ok(1));
+        let f2 = group.compose(&f, &f).unwrap();
+        assert_eq!(
+            f2,
+            group
+                .class_representative(&f.nat_pow(&Natural::TWO))
+                .unwrap()
+        );
+        assert_eq!(group.order(&f2), Ok(2));
+        assert_eq!(group.compose(&f, &f.inverse()).unwrap(), group.identity());
+        assert!(group
+            .class_representative(&BinaryQuadraticForm::new(
+                Integer::from(2),
+                Integer::ZERO,
+                Integer::from(14)
+            ))
+            .is_err());
+
+        let group = FormClassGroup::new(Integer::from(12)).unwrap();
+        let f = BinaryQuadraticForm::new(-Integer::ONE, Integer::ZERO, Integer::from(3));
+        assert_eq!(group.order(&f), Ok(2));
+        assert_ne!(group.class_representative(&f).unwrap(), group.identity());
+    }
+}
@@ -0,0 +1,717 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use algebraeon_groups::composition_table::group::Group;
+use malachite_base::num::arithmetic::traits::{Ceiling, Floor};
+use malachite_base::num::basic::traits::{One, Zero};
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+
+use crate::linear::matrix::Matrix;
+use crate::number::natural::functions::sqrt_floor;
+
+//a square integer matrix stored as its columns
+type Columns = Vec<Vec<Integer>>;
+
+fn columns_to_matrix(m: &Columns) -> Matrix<Integer> {
+    let n = m.len();
+    Matrix::construct(n, n, |r, c| m[c][r].clone())
+}
+
+fn identity_columns(n: usize) -> Columns {
+    (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| if i == j { Integer::ONE } else { Integer::ZERO })
+                .collect()
+        })
+        .collect()
+}
+
+//m x where x is given in coordinates with respect to the basis
+fn apply_columns(m: &Columns, x: &[Integer]) -> Vec<Integer> {
+    let n = x.len();
+    let mut y = vec![Integer::ZERO; n];
+    for (x_j, col) in x.iter().zip(m) {
+        for i in 0..n {
+            y[i] += x_j * &col[i];
+        }
+    }
+    y
+}
+
+fn dot(x: &[Integer], y: &[Integer]) -> Integer {
+    let mut total = Integer::ZERO;
+    for (a, b) in x.iter().zip(y) {
+        total += a * b;
+    }
+    total
+}
+
+fn mul_columns(m: &Columns, n: &Columns) -> Columns {
+    n.iter().map(|col| apply_columns(m, col)).collect()
+}
+
+/// A positive definite integral lattice, given by its Gram matrix G with respect to a basis b_0, ..., b_(n-1).
+/// Lattice vectors are written in coordinates x in Z^n, with inner product x^T G y and norm x^T G x.
+#[derive(Debug, Clone)]
+pub struct IntegralLattice {
+    gram: Vec<Vec<Integer>>,
+    //the quadratic completion x^T G x = sum of d_i (x_i + sum over j > i of r_ij x_j)^2
+    completion_diagonal: Vec<Rational>,
+    completion_upper: Vec<Vec<Rational>>,
+}
+
+impl IntegralLattice {
+    /// Returns an error if the Gram matrix is not square, symmetric and positive definite.
+    pub fn new(gram: Matrix<Integer>) -> Result<Self, &'static str> {
+        let n = gram.rows();
+        if gram.cols() != n {
+            return Err("the Gram matrix must be square");
+        }
+        let gram = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| gram.at(i, j).unwrap().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        if !(0..n).all(|i| (0..i).all(|j| gram[i][j] == gram[j][i])) {
+            return Err("the Gram matrix must be symmetric");
+        }
+        let mut q = gram
+            .iter()
+            .map(|row| row.iter().map(Rational::from).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for i in 0..n {
+            if q[i][i] <= Rational::ZERO {
+                return Err("the Gram matrix must be positive definite");
+            }
+            for j in (i + 1)..n {
+                q[j][i] = q[i][j].clone();
+                q[i][j] = &q[i][j] / &q[i][i];
+            }
+            for k in (i + 1)..n {
+                for l in k..n {
+                    let sub = &q[k][i] * &q[i][l];
+                    q[k][l] -= sub;
+                }
+            }
+        }
+        let completion_diagonal = (0..n).map(|i| q[i][i].clone()).collect();
+        let completion_upper = q;
+        Ok(Self {
+            gram,
+            completion_diagonal,
+            completion_upper,
+        })
+    }
+
+    pub fn rank(&self) -> usize {
+        self.gram.len()
+    }
+
+    pub fn gram(&self) -> Matrix<Integer> {
+        let n = self.rank();
+        Matrix::construct(n, n, |r, c| self.gram[r][c].clone())
+    }
+
+    pub fn inner_product(&self, x: &[Integer], y: &[Integer]) -> Integer {
+        let n = self.rank();
+        assert_eq!(x.len(), n);
+        assert_eq!(y.len(), n);
+        //the Gram matrix is symmetric, so its rows are also its columns
+        dot(x, &apply_columns(&self.gram, y))
+    }
+
+    pub fn norm(&self, x: &[Integer]) -> Integer {
+        self.inner_product(x, x)
+    }
+
+    //call visit on every vector of norm at most the bound, including the zero vector, by the Fincke–Pohst method
+    //the coordinates are chosen from the last to the first, and at each step the quadratic completion bounds the current coordinate to an interval
+    fn fincke_pohst(&self, bound: &Integer, visit: &mut impl FnMut(&Vec<Integer>)) {
+        let n = self.rank();
+        let mut x = vec![Integer::ZERO; n];
+        self.fincke_pohst_step(n, Rational::from(bound), &mut x, visit);
+    }
+
+    //choose x_(i-1) given x_i, ..., x_(n-1) and the remaining bound on the first i terms of the completion
+    fn fincke_pohst_step(
+        &self,
+        i: usize,
+        remaining: Rational,
+        x: &mut Vec<Integer>,
+        visit: &mut impl FnMut(&Vec<Integer>),
+    ) {
+        if i == 0 {
+            visit(x);
+            return;
+        }
+        let i = i - 1;
+        let mut centre = Rational::ZERO;
+        for (r, x_j) in self.completion_upper[i].iter().zip(x.iter()).skip(i + 1) {
+            centre -= r * Rational::from(x_j);
+        }
+        let d = &self.completion_diagonal[i];
+        let radius_sq = &remaining / d;
+        //the integers t with d (t - centre)^2 <= remaining lie within floor(sqrt(radius_sq)) + 1 of the centre
+        let radius = Integer::from(sqrt_floor(&Natural::try_from(radius_sq.floor()).unwrap()))
+            + Integer::ONE;
+        let mut t = (&centre).floor() - &radius;
+        let last = (&centre).ceiling() + &radius;
+        while t <= last {
+            let diff = Rational::from(&t) - &centre;
+            let used = d * &diff * &diff;
+            if used <= remaining {
+                x[i] = t.clone();
+                self.fincke_pohst_step(i, &remaining - used, x, visit);
+            }
+            t += Integer::ONE;
+        }
+        x[i] = Integer::ZERO;
+    }
+
+    /// All non-zero vectors of norm at most the bound, together with their norms.
+    /// Both x and -x are included.
+    pub fn short_vectors(&self, bound: &Integer) -> Vec<(Vec<Integer>, Integer)> {
+        let mut vectors = vec![];
+        self.fincke_pohst(bound, &mut |x| {
+            if x.iter().any(|x_i| x_i != &Integer::ZERO) {
+                vectors.push((x.clone(), self.norm(x)));
+            }
+        });
+        vectors
+    }
+
+    /// The least norm of a non-zero vector. The lattice must have positive rank.
+    pub fn minimum(&self) -> Integer {
+        assert!(self.rank() >= 1);
+        //the norm of a basis vector bounds the minimum
+        let bound = (0..self.rank())
+            .map(|i| self.gram[i][i].clone())
+            .min()
+            .unwrap();
+        self.short_vectors(&bound)
+            .into_iter()
+            .map(|(_x, norm)| norm)
+            .min()
+            .unwrap()
+    }
+
+    /// The non-zero vectors of minimal norm, of which there are none if the rank is zero.
+    pub fn minimal_vectors(&self) -> Vec<Vec<Integer>> {
+        if self.rank() == 0 {
+            return vec![];
+        }
+        let minimum = self.minimum();
+        self.short_vectors(&minimum)
+            .into_iter()
+            .filter(|(_x, norm)| norm == &minimum)
+            .map(|(x, _norm)| x)
+            .collect()
+    }
+
+    /// The number of non-zero vectors of minimal norm.
+    pub fn kissing_number(&self) -> usize {
+        self.minimal_vectors().len()
+    }
+
+    /// The coefficients a_0, ..., a_k of the theta series sum over lattice vectors x of q^(norm of x), so a_m is the number of vectors of norm m.
+    pub fn theta_series(&self, k: usize) -> Vec<Natural> {
+        let mut coeffs = vec![Natural::ZERO; k + 1];
+        self.fincke_pohst(&Integer::from(k), &mut |x| {
+            let norm = usize::try_from(&self.norm(x)).unwrap();
+            coeffs[norm] += Natural::ONE;
+        });
+        coeffs
+    }
+
+    /// An isometry from other to self: an integer matrix u with u^T G u = H where G and H are the Gram matrices of self and other.
+    /// The columns of u are the coordinates of the images of the basis vectors of other.
+    /// It is found by a backtrack search over short vectors, pruned as in [`IntegralLattice::automorphism_group`].
+    pub fn isometry(&self, other: &Self) -> Option<Matrix<Integer>> {
+        if self.rank() != other.rank() {
+            return None;
+        }
+        if self.rank() > 0 && self.gram().det().unwrap() != other.gram().det().unwrap() {
+            return None;
+        }
+        let search = IsometrySearch::new(self, other);
+        let mut images = vec![];
+        if search.extend(&mut images) {
+            Some(columns_to_matrix(&search.columns(&images)))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_isometric(&self, other: &Self) -> bool {
+        self.isometry(other).is_some()
+    }
+
+    /// The group of integer matrices u with u^T G u = G, by a stabilizer chain search in the style of Plesken and Souvignier.
+    ///
+    /// Let G_i be the subgroup fixing b_0, ..., b_(i-1). Working from i = n - 1 down to 0, the orbit of b_i under G_i is computed from the generators found so far,
+    /// and for each candidate image outside the orbit a backtrack search looks for an element of G_i taking b_i to it, which is added as a new generator.
+    /// Candidate images are pruned by the fingerprints of Plesken and Souvignier, but not by their vector sums,
+    /// so the search can still take exponential time for lattices with many vectors of the same norm and few automorphisms.
+    pub fn automorphism_group(&self) -> LatticeAutomorphismGroup {
+        let n = self.rank();
+        let search = IsometrySearch::new(self, self);
+        let basis = (0..n)
+            .map(|i| search.index[&identity_columns(n)[i]])
+            .collect::<Vec<_>>();
+        let mut generators: Vec<Columns> = vec![];
+        let mut transversals = vec![HashMap::new(); n];
+        for i in (0..n).rev() {
+            let mut orbit = search.orbit(basis[i], &generators);
+            let mut impossible = HashSet::new();
+            for &v in &search.candidates[i] {
+                if orbit.contains_key(&v) || impossible.contains(&v) {
+                    continue;
+                }
+                let mut images = basis[0..i].to_vec();
+                images.push(v);
+                if (0..i).all(|j| search.inner_product(basis[j], v) == self.gram[j][i])
+                    && search.extend(&mut images)
+                {
+                    generators.push(search.columns(&images));
+                    orbit = search.orbit(basis[i], &generators);
+                } else {
+                    //nothing in G_i takes b_i into the orbit of v under a subgroup of G_i
+                    impossible.extend(search.orbit(v, &generators).into_keys());
+                }
+            }
+            transversals[i] = orbit;
+        }
+        LatticeAutomorphismGroup {
+            rank: n,
+            gram: self.gram.clone(),
+            generators,
+            transversals: transversals
+                .into_iter()
+                .map(|transversal| transversal.into_values().collect())
+                .collect(),
+        }
+    }
+}
+
+//backtrack search for isometries from a source lattice to a target lattice
+struct IsometrySearch<'a> {
+    source: &'a IntegralLattice,
+    //the vectors of the target of norm at most the largest norm of a basis vector of the source
+    vectors: Vec<Vec<Integer>>,
+    //the target Gram matrix times each vector
+    gram_vectors: Vec<Vec<Integer>>,
+    index: HashMap<Vec<Integer>, usize>,
+    //for each basis vector of the source, the vectors of the target of the same norm
+    candidates: Vec<Vec<usize>>,
+}
+
+impl<'a> IsometrySearch<'a> {
+    fn new(target: &IntegralLattice, source: &'a IntegralLattice) -> Self {
+        let n = source.rank();
+        let bound = (0..n)
+            .map(|i| source.gram[i][i].clone())
+            .max()
+            .unwrap_or(Integer::ZERO);
+        let short_vectors = target.short_vectors(&bound);
+        let vectors = short_vectors
+            .iter()
+            .map(|(x, _norm)| x.clone())
+            .collect::<Vec<_>>();
+        let gram_vectors = vectors
+            .iter()
+            .map(|x| apply_columns(&target.gram, x))
+            .collect::<Vec<_>>();
+        let index = vectors
+            .iter()
+            .enumerate()
+            .map(|(k, x)| (x.clone(), k))
+            .collect();
+
+        //the fingerprint of a vector v as a candidate image of b_i counts, for each j, the vectors w of norm G_jj with <v, w> = G_ij
+        //isometries preserve these counts, so a candidate must have the same fingerprint as b_i itself
+        let count_inner_products =
+            |gram_v: &Vec<Integer>, vectors: &Vec<(Vec<Integer>, Integer)>| {
+                let mut counts: HashMap<(Integer, Integer), usize> = HashMap::new();
+                for (w, norm) in vectors {
+                    *counts.entry((norm.clone(), dot(gram_v, w))).or_insert(0) += 1;
+                }
+                counts
+            };
+        let fingerprint = |i: usize, counts: &HashMap<(Integer, Integer), usize>| {
+            (0..n)
+                .map(|j| {
+                    counts
+                        .get(&(source.gram[j][j].clone(), source.gram[i][j].clone()))
+                        .copied()
+                        .unwrap_or(0)
+                })
+                .collect::<Vec<_>>()
+        };
+        let source_short_vectors = source.short_vectors(&bound);
+        let target_counts = gram_vectors
+            .iter()
+            .map(|gram_v| count_inner_products(gram_v, &short_vectors))
+            .collect::<Vec<_>>();
+        let candidates = (0..n)
+            .map(|i| {
+                let b_i = identity_columns(n).swap_remove(i);
+                let source_fingerprint = fingerprint(
+                    i,
+                    &count_inner_products(
+                        &apply_columns(&source.gram, &b_i),
+                        &source_short_vectors,
+                    ),
+                );
+                (0..vectors.len())
+                    .filter(|k| {
+                        short_vectors[*k].1 == source.gram[i][i]
+                            && fingerprint(i, &target_counts[*k]) == source_fingerprint
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            source,
+            vectors,
+            gram_vectors,
+            index,
+            candidates,
+        }
+    }
+
+    fn inner_product(&self, u: usize, v: usize) -> Integer {
+        dot(&self.gram_vectors[u], &self.vectors[v])
+    }
+
+    fn columns(&self, images: &[usize]) -> Columns {
+        images.iter().map(|k| self.vectors[*k].clone()).collect()
+    }
+
+    //extend consistent images of the first basis vectors of the source to an isometry, returning whether this is possible
+    fn extend(&self, images: &mut Vec<usize>) -> bool {
+        let k = images.len();
+        if k == self.source.rank() {
+            return true;
+        }
+        for &v in &self.candidates[k] {
+            if (0..k).all(|j| self.inner_product(images[j], v) == self.source.gram[j][k]) {
+                images.push(v);
+                if self.extend(images) {
+                    return true;
+                }
+                images.pop();
+            }
+        }
+        false
+    }
+
+    //the orbit of a vector under the group generated by automorphisms, with an element taking the vector to each point of the orbit
+    fn orbit(&self, start: usize, generators: &[Columns]) -> HashMap<usize, Columns> {
+        let n = self.source.rank();
+        let mut orbit = HashMap::from([(start, identity_columns(n))]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(v) = queue.pop_front() {
+            for g in generators {
+                let w = self.index[&apply_columns(g, &self.vectors[v])];
+                if !orbit.contains_key(&w) {
+                    let element = mul_columns(g, &orbit[&v]);
+                    orbit.insert(w, element);
+                    queue.push_back(w);
+                }
+            }
+        }
+        orbit
+    }
+}
+
+/// The automorphism group of an integral lattice as a group of integer matrices u with u^T G u = G.
+///
+/// It is stored as a chain of stabilizers: the transversal at level i is a set of coset representatives of G_(i+1) in G_i, where G_i is the subgroup fixing the first i basis vectors.
+/// Every element is then uniquely a product t_0 t_1 ... t_(n-1) of elements of the transversals.
+#[derive(Debug, Clone)]
+pub struct LatticeAutomorphismGroup {
+    rank: usize,
+    gram: Vec<Vec<Integer>>,
+    generators: Vec<Columns>,
+    transversals: Vec<Vec<Columns>>,
+}
+
+impl LatticeAutomorphismGroup {
+    pub fn generators(&self) -> Vec<Matrix<Integer>> {
+        self.generators.iter().map(columns_to_matrix).collect()
+    }
+
+    pub fn order(&self) -> Natural {
+        self.transversals
+            .iter()
+            .map(|transversal| Natural::from(transversal.len()))
+            .product()
+    }
+
+    /// Whether u is an automorphism of the lattice.
+    pub fn contains(&self, u: &Matrix<Integer>) -> bool {
+        let n = self.rank;
+        if u.rows() != n || u.cols() != n {
+            return false;
+        }
+        let columns = (0..n)
+            .map(|j| {
+                (0..n)
+                    .map(|i| u.at(i, j).unwrap().clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        (0..n).all(|i| {
+            let gram_column = apply_columns(&self.gram, &columns[i]);
+            (0..n).all(|j| dot(&gram_column, &columns[j]) == self.gram[i][j])
+        })
+    }
+
+    fn element_columns(&self) -> Vec<Columns> {
+        let mut elements = vec![identity_columns(self.rank)];
+        for transversal in self.transversals.iter().rev() {
+            elements = transversal
+                .iter()
+                .flat_map(|t| elements.iter().map(|g| mul_columns(t, g)))
+                .collect();
+        }
+        elements
+    }
+
+    /// All elements of the group. Only use this when the order is small.
+    pub fn elements(&self) -> Vec<Matrix<Integer>> {
+        self.element_columns()
+            .iter()
+            .map(columns_to_matrix)
+            .collect()
+    }
+
+    /// The composition table of the group, with the elements indexed as in elements(). Only use this when the order is small.
+    pub fn composition_table(&self) -> Group {
+        let n = self.rank;
+        Group::from_raw_model(
+            self.element_columns(),
+            || identity_columns(n),
+            |g| {
+                let g = columns_to_matrix(&g).inv().unwrap();
+                (0..n)
+                    .map(|j| (0..n).map(|i| g.at(i, j).unwrap().clone()).collect())
+                    .collect()
+            },
+            |g, h| mul_columns(&g, &h),
+        )
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lattice(rows: Vec<Vec<i32>>) -> IntegralLattice {
+        IntegralLattice::new(Matrix::from_rows(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(Integer::from).collect())
+                .collect(),
+        ))
+        .unwrap()
+    }
+
+    fn naturals(ns: Vec<u32>) -> Vec<Natural> {
+        ns.into_iter().map(Natural::from).collect()
+    }
+
+    fn e8() -> IntegralLattice {
+        let mut rows = vec![vec![0; 8]; 8];
+        for i in 0..8 {
+            rows[i][i] = 2;
+        }
+        //the E8 Dynkin diagram as a path 0 - 1 - ... - 6 with 7 attached to 4
+        for (i, j) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (4, 7)] {
+            rows[i][j] = -1;
+            rows[j][i] = -1;
+        }
+        lattice(rows)
+    }
+
+    #[test]
+    fn test_new_lattice() {
+        assert!(IntegralLattice::new(Matrix::from_rows(vec![
+            vec![Integer::from(1), Integer::from(2)],
+            vec![Integer::from(2), Integer::from(1)],
+        ]))
+        .is_err());
+        assert!(IntegralLattice::new(Matrix::from_rows(vec![
+            vec![Integer::from(2), Integer::from(1)],
+            vec![Integer::from(0), Integer::from(2)],
+        ]))
+        .is_err());
+        let l = lattice(vec![vec![2, -1], vec![-1, 2]]);
+        assert_eq!(l.rank(), 2);
+        assert_eq!(
+            l.norm(&[Integer::from(1), Integer::from(1)]),
+            Integer::from(2)
+        );
+    }
+
+    #[test]
+    fn test_short_vectors() {
+        let z2 = lattice(vec![vec![1, 0], vec![0, 1]]);
+        assert_eq!(
+            z2.theta_series(10),
+            naturals(vec![1, 4, 4, 0, 4, 8, 0, 0, 4, 4, 8])
+        );
+        assert_eq!(z2.short_vectors(&Integer::from(2)).len(), 8);
+
+        //a skewed basis of the hexagonal lattice
+        let a2 = lattice(vec![vec![2, 5], vec![5, 14]]);
+        assert_eq!(a2.minimum(), Integer::from(2));
+        assert_eq!(a2.kissing_number(), 6);
+        assert!(a2
+            .minimal_vectors()
+            .iter()
+            .all(|x| a2.norm(x) == Integer::from(2)));
+        assert_eq!(a2.theta_series(6), naturals(vec![1, 0, 6, 0, 0, 0, 6]));
+
+        let e8 = e8();
+        assert_eq!(e8.minimum(), Integer::from(2));
+        assert_eq!(e8.kissing_number(), 240);
+        assert_eq!(e8.theta_series(4), naturals(vec![1, 0, 240, 0, 2160]));
+
+        let l = lattice(vec![vec![5, 2, 1], vec![2, 6, 3], vec![1, 3, 7]]);
+        for (x, norm) in l.short_vectors(&Integer::from(12)) {
+            assert_eq!(l.norm(&x), norm);
+            assert!(norm <= Integer::from(12));
+        }
+        let count = l.short_vectors(&Integer::from(12)).len();
+        let mut expected = 0;
+        for a in -5i32..=5 {
+            for b in -5i32..=5 {
+                for c in -5i32..=5 {
+                    let x = [Integer::from(a), Integer::from(b), Integer::from(c)];
+                    let norm = l.norm(&x);
+                    if Integer::ZERO < norm && norm <= Integer::from(12) {
+                        expected += 1;
+                    }
+                }
+            }
+        }
+        assert_eq!(count, expected);
+    }
+
+    #[test]
+    fn test_automorphism_group() {
+        for (l, order) in [
+            (
+                lattice(vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]]),
+                48u32,
+            ),
+            (lattice(vec![vec![2, -1], vec![-1, 2]]), 12),
+            (lattice(vec![vec![3, 1], vec![1, 4]]), 2),
+            (lattice(vec![vec![2, 1], vec![1, 3]]), 4),
+            (
+                lattice(vec![
+                    vec![2, -1, 0, 0],
+                    vec![-1, 2, -1, -1],
+                    vec![0, -1, 2, 0],
+                    vec![0, -1, 0, 2],
+                ]),
+                1152,
+            ),
+        ] {
+            let group = l.automorphism_group();
+            assert_eq!(group.order(), Natural::from(order));
+            for g in group.generators() {
+                assert!(group.contains(&g));
+            }
+        }
+
+        let group = lattice(vec![vec![2, -1], vec![-1, 2]]).automorphism_group();
+        let elements = group.elements();
+        assert_eq!(elements.len(), 12);
+        assert!(elements.iter().all(|g| group.contains(g)));
+        let table = group.composition_table();
+        assert_eq!(table.size(), 12);
+        assert!(!table.is_abelian());
+        assert!(!group.contains(&Matrix::from_rows(vec![
+            vec![Integer::from(1), Integer::from(1)],
+            vec![Integer::from(0), Integer::from(1)],
+        ])));
+
+        assert_eq!(
+            e8().automorphism_group().order(),
+            Natural::from(696729600u32)
+        );
+    }
+
+    #[test]
+    fn test_rank_zero() {
+        let l = IntegralLattice::new(Matrix::construct(0, 0, |_r, _c| Integer::ZERO)).unwrap();
+        assert_eq!(l.rank(), 0);
+        assert!(l.short_vectors(&Integer::from(5)).is_empty());
+        assert!(l.minimal_vectors().is_empty());
+        assert_eq!(l.kissing_number(), 0);
+        assert_eq!(l.theta_series(2), naturals(vec![1, 0, 0]));
+        let group = l.automorphism_group();
+        assert_eq!(group.order(), Natural::ONE);
+        assert_eq!(group.elements().len(), 1);
+        assert_eq!(group.composition_table().size(), 1);
+        assert!(l.is_isometric(&l));
+        assert!(!l.is_isometric(&lattice(vec![vec![1]])));
+        assert!(!lattice(vec![vec![1]]).is_isometric(&l));
+    }
+
+    #[test]
+    fn test_isometry() {
+        let check = |a: &IntegralLattice, b: &IntegralLattice| {
+            let u = a.isometry(b).unwrap();
+            let ut_a_u =
+                Matrix::mul(&Matrix::mul(&u.clone().transpose(), &a.gram()).unwrap(), &u).unwrap();
+            assert_eq!(ut_a_u, b.gram());
+        };
+        check(
+            &lattice(vec![vec![2, -1], vec![-1, 2]]),
+            &lattice(vec![vec![2, 5], vec![5, 14]]),
+        );
+        check(
+            &lattice(vec![vec![2, 1], vec![1, 3]]),
+            &lattice(vec![vec![3, 1], vec![1, 2]]),
+        );
+        check(&e8(), &e8());
+        //x^2 + 6y^2 and 2x^2 + 3y^2 have the same determinant but are not isometric
+        assert!(!lattice(vec![vec![1, 0], vec![0, 6]])
+            .is_isometric(&lattice(vec![vec![2, 0], vec![0, 3]])));
+        assert!(!lattice(vec![vec![1, 0], vec![0, 1]])
+            .is_isometric(&lattice(vec![vec![1, 0], vec![0, 2]])));
+        //the same determinant but different numbers of vectors of norm 1
+        assert!(!lattice(vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 4]])
+            .is_isometric(&lattice(vec![vec![1, 0, 0], vec![0, 2, 0], vec![0, 0, 2]])));
+        //a change of basis of D4 is isometric to it
+        let d4 = lattice(vec![
+            vec![2, -1, 0, 0],
+            vec![-1, 2, -1, -1],
+            vec![0, -1, 2, 0],
+            vec![0, -1, 0, 2],
+        ]);
+        let u = Matrix::from_rows(
+            vec![
+                vec![1, 1, 0, 0],
+                vec![0, 1, 0, 1],
+                vec![0, 0, 1, 1],
+                vec![0, 0, 0, 1],
+            ]
+            .into_iter()
+            .map(|row| row.into_iter().map(Integer::from).collect())
+            .collect(),
+        );
+        let gram = Matrix::mul(
+            &Matrix::mul(&u.clone().transpose(), &d4.gram()).unwrap(),
+            &u,
+        )
+        .unwrap();
+        check(&d4, &IntegralLattice::new(gram).unwrap());
+    }
+}
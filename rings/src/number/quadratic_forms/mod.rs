@@ -0,0 +1,415 @@
+use malachite_base::num::arithmetic::traits::{DivMod, Mod, Parity, UnsignedAbs};
+use malachite_base::num::basic::traits::{One, Two, Zero};
+use malachite_nz::{integer::Integer, natural::Natural};
+
+use crate::number::natural::functions::sqrt_floor;
+use crate::structure::structure::*;
+
+pub mod class_group;
+pub mod lattice;
+pub mod representation;
+
+/// A 2x2 integer matrix \[\[p, q\], \[r, s\]\] acting on (x, y) by (x, y) -> (px + qy, rx + sy).
+pub type Transformation = [[Integer; 2]; 2];
+
+fn identity_transformation() -> Transformation {
+    [[Integer::ONE, Integer::ZERO], [Integer::ZERO, Integer::ONE]]
+}
+
+fn mul_transformations(m: &Transformation, n: &Transformation) -> Transformation {
+    let entry = |i: usize, j: usize| &m[i][0] * &n[0][j] + &m[i][1] * &n[1][j];
+    [[entry(0, 0), entry(0, 1)], [entry(1, 0), entry(1, 1)]]
+}
+
+// the inverse of a transformation of determinant 1
+fn inv_transformation(m: &Transformation) -> Transformation {
+    debug_assert_eq!(&m[0][0] * &m[1][1] - &m[0][1] * &m[1][0], Integer::ONE);
+    [[m[1][1].clone(), -&m[0][1]], [-&m[1][0], m[0][0].clone()]]
+}
+
+/// The binary quadratic form ax^2 + bxy + cy^2 with integer coefficients.
+///
+/// Reduction, equivalence and composition are supported for positive definite forms, those with negative discriminant and a > 0,
+/// and for indefinite forms, those with positive non-square discriminant. Equivalence always means proper equivalence, under transformations of determinant 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BinaryQuadraticForm {
+    a: Integer,
+    b: Integer,
+    c: Integer,
+}
+
+impl BinaryQuadraticForm {
+    pub fn new(a: Integer, b: Integer, c: Integer) -> Self {
+        Self { a, b, c }
+    }
+
+    /// The form (a, b, c) of discriminant d = b^2 - 4ac. Returns an error if a is zero or 4a does not divide b^2 - d.
+    pub fn from_discriminant(a: Integer, b: Integer, d: &Integer) -> Result<Self, &'static str> {
+        if a == Integer::ZERO {
+            return Err("a must be non-zero");
+        }
+        let (c, r) = (&b * &b - d).div_mod(Integer::from(4) * &a);
+        if r != Integer::ZERO {
+            return Err("4a must divide b^2 - d");
+        }
+        Ok(Self::new(a, b, c))
+    }
+
+    /// The principal form x^2 + bxy + cy^2 of discriminant d, where b is 0 or 1. Returns an error if d is not 0 or 1 modulo 4.
+    pub fn principal(d: &Integer) -> Result<Self, &'static str> {
+        let b = d.mod_op(Integer::TWO);
+        Self::from_discriminant(Integer::ONE, b, d)
+            .map_err(|_| "the discriminant must be 0 or 1 modulo 4")
+    }
+
+    pub fn a(&self) -> &Integer {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Integer {
+        &self.b
+    }
+
+    pub fn c(&self) -> &Integer {
+        &self.c
+    }
+
+    pub fn discriminant(&self) -> Integer {
+        &self.b * &self.b - Integer::from(4) * &self.a * &self.c
+    }
+
+    pub fn evaluate(&self, x: &Integer, y: &Integer) -> Integer {
+        &self.a * x * x + &self.b * x * y + &self.c * y * y
+    }
+
+    /// The gcd of the coefficients.
+    pub fn content(&self) -> Natural {
+        Integer::gcd_list(vec![&self.a, &self.b, &self.c]).unsigned_abs()
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        self.content() == Natural::ONE
+    }
+
+    pub fn is_positive_definite(&self) -> bool {
+        self.discriminant() < Integer::ZERO && self.a > Integer::ZERO
+    }
+
+    /// Whether the discriminant is positive and not a square.
+    pub fn is_indefinite(&self) -> bool {
+        let d = self.discriminant();
+        if d <= Integer::ZERO {
+            return false;
+        }
+        let s = Integer::from(sqrt_floor(d.unsigned_abs_ref()));
+        &s * &s != d
+    }
+
+    /// The form g(x, y) = f(px + qy, rx + sy).
+    pub fn transform(&self, m: &Transformation) -> Self {
+        let [[p, q], [r, s]] = m;
+        Self::new(
+            self.evaluate(p, r),
+            Integer::TWO * &self.a * p * q
+                + &self.b * (p * s + q * r)
+                + Integer::TWO * &self.c * r * s,
+            self.evaluate(q, s),
+        )
+    }
+
+    /// The form (a, -b, c), whose class is the inverse of the class of (a, b, c) under composition.
+    pub fn inverse(&self) -> Self {
+        Self::new(self.a.clone(), -&self.b, self.c.clone())
+    }
+
+    fn sqrt_discriminant_floor(&self) -> Integer {
+        Integer::from(sqrt_floor(&self.discriminant().unsigned_abs()))
+    }
+
+    /// Whether the form is reduced.
+    /// A positive definite form is reduced when |b| <= a <= c, and b >= 0 if either |b| = a or a = c.
+    /// An indefinite form of discriminant D is reduced when |sqrt(D) - 2|a|| < b < sqrt(D).
+    pub fn is_reduced(&self) -> bool {
+        if self.is_positive_definite() {
+            self.b.unsigned_abs_ref() <= self.a.unsigned_abs_ref()
+                && self.a <= self.c
+                && (self.b >= Integer::ZERO
+                    || (self.b.unsigned_abs_ref() != self.a.unsigned_abs_ref() && self.a != self.c))
+        } else if self.is_indefinite() {
+            //sqrt(D) is irrational, so the strict inequalities can be checked against its floor s
+            let s = self.sqrt_discriminant_floor();
+            let two_a = Integer::TWO * Integer::from(self.a.unsigned_abs_ref());
+            self.b <= s && &self.b + &two_a > s && &two_a - &self.b <= s
+        } else {
+            false
+        }
+    }
+
+    /// The reduction operator on indefinite forms: rho(a, b, c) = (c, r, (r^2 - D)/4c) where r = -b modulo 2c
+    /// with -|c| < r <= |c| if |c| > sqrt(D) and sqrt(D) - 2|c| < r < sqrt(D) otherwise.
+    /// rho(f) is equivalent to f, and it maps reduced forms to reduced forms.
+    pub fn rho(&self) -> Self {
+        self.rho_with_transformation().0
+    }
+
+    fn rho_with_transformation(&self) -> (Self, Transformation) {
+        assert!(self.is_indefinite(), "the form must be indefinite");
+        let d = self.discriminant();
+        let s = self.sqrt_discriminant_floor();
+        let c_abs = Integer::from(self.c.unsigned_abs_ref());
+        let two_c_abs = Integer::TWO * &c_abs;
+        let r = if c_abs > s {
+            let r = (-&self.b).mod_op(&two_c_abs);
+            if r > c_abs {
+                r - two_c_abs
+            } else {
+                r
+            }
+        } else {
+            &s - (&s + &self.b).mod_op(&two_c_abs)
+        };
+        //(x, y) -> (y, -x + ty) takes b to -b - 2ct = r
+        let t = -(&r + &self.b) / (Integer::TWO * &self.c);
+        let rho = Self::new(
+            self.c.clone(),
+            r.clone(),
+            (&r * &r - d) / (Integer::from(4) * &self.c),
+        );
+        (rho, [[Integer::ZERO, Integer::ONE], [-Integer::ONE, t]])
+    }
+
+    /// The reduced form equivalent to f, together with a transformation m of determinant 1 such that the reduced form is f.transform(m).
+    /// For an indefinite form there are several equivalent reduced forms, see [`BinaryQuadraticForm::cycle`], and one of them is returned.
+    pub fn reduce_with_transformation(&self) -> (Self, Transformation) {
+        if self.is_positive_definite() {
+            self.reduce_positive_definite()
+        } else if self.is_indefinite() {
+            let mut f = self.clone();
+            let mut m = identity_transformation();
+            while !f.is_reduced() {
+                let (rho, n) = f.rho_with_transformation();
+                f = rho;
+                m = mul_transformations(&m, &n);
+            }
+            (f, m)
+        } else {
+            panic!("the form must be positive definite or indefinite")
+        }
+    }
+
+    pub fn reduce(&self) -> Self {
+        self.reduce_with_transformation().0
+    }
+
+    fn reduce_positive_definite(&self) -> (Self, Transformation) {
+        let (mut a, mut b, mut c) = (self.a.clone(), self.b.clone(), self.c.clone());
+        let mut m = identity_transformation();
+        loop {
+            //normalize so that -a < b <= a using (x, y) -> (x - qy, y)
+            if !(-&a < b && b <= a) {
+                let two_a = Integer::TWO * &a;
+                let (mut q, mut r) = (&b).div_mod(&two_a);
+                if r > a {
+                    r -= &two_a;
+                    q += Integer::ONE;
+                }
+                c += &a * &q * &q - &b * &q;
+                b = r;
+                m = mul_transformations(&m, &[[Integer::ONE, -&q], [Integer::ZERO, Integer::ONE]]);
+            }
+            //swap using (x, y) -> (-y, x)
+            let swap = [
+                [Integer::ZERO, -Integer::ONE],
+                [Integer::ONE, Integer::ZERO],
+            ];
+            if a > c {
+                (a, b, c) = (c, -b, a);
+                m = mul_transformations(&m, &swap);
+            } else {
+                if a == c && b < Integer::ZERO {
+                    b = -b;
+                    m = mul_transformations(&m, &swap);
+                }
+                break;
+            }
+        }
+        let f = Self::new(a, b, c);
+        debug_assert!(f.is_reduced());
+        debug_assert_eq!(self.transform(&m), f);
+        (f, m)
+    }
+
+    /// The cycle of reduced forms equivalent to an indefinite form f: the reduced forms g, rho(g), rho(rho(g)), ... until g repeats, for a reduced form g equivalent to f.
+    /// Two indefinite forms are equivalent exactly when their cycles are the same.
+    pub fn cycle(&self) -> Vec<Self> {
+        assert!(self.is_indefinite(), "the form must be indefinite");
+        let start = self.reduce();
+        let mut cycle = vec![start.clone()];
+        let mut f = start.rho();
+        while f != start {
+            let next = f.rho();
+            cycle.push(f);
+            f = next;
+        }
+        cycle
+    }
+
+    /// Whether f and g are properly equivalent.
+    /// Reduction is only defined for definite forms and indefinite forms of non-square discriminant, so forms of square discriminant are only compared for equality.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        if self.discriminant() != other.discriminant() {
+            return false;
+        }
+        //f is negative definite exactly when -f is positive definite, and -f transforms as f does
+        let negate = |f: &Self| Self::new(-&f.a, -&f.b, -&f.c);
+        if self.is_positive_definite() && other.is_positive_definite() {
+            self.reduce() == other.reduce()
+        } else if negate(self).is_positive_definite() && negate(other).is_positive_definite() {
+            negate(self).reduce() == negate(other).reduce()
+        } else if self.is_indefinite() && other.is_indefinite() {
+            self.cycle().contains(&other.reduce())
+        } else if self.discriminant() < Integer::ZERO || self.is_indefinite() {
+            //one is positive and the other negative definite
+            false
+        } else {
+            self == other
+        }
+    }
+
+    /// The reduced composition of two primitive forms of the same discriminant D, by the formula of Dirichlet:
+    /// for e = gcd(a1, a2, (b1 + b2)/2) = u a1 + v a2 + w (b1 + b2)/2 the composition is (a3, b3, c3)
+    /// with a3 = a1 a2 / e^2 and b3 = (u a1 b2 + v a2 b1 + w (b1 b2 + D)/2) / e.
+    pub fn compose(&self, other: &Self) -> Self {
+        let d = self.discriminant();
+        assert_eq!(d, other.discriminant(), "the discriminants must be equal");
+        let (a1, b1) = (&self.a, &self.b);
+        let (a2, b2) = (&other.a, &other.b);
+        let beta = (b1 + b2) / Integer::TWO;
+        let (g, x, y) = Integer::xgcd(a1, a2);
+        let (e, p, w) = Integer::xgcd(&g, &beta);
+        let (u, v) = (&p * x, &p * y);
+        debug_assert_eq!(&u * a1 + &v * a2 + &w * &beta, e);
+        let a3 = a1 * a2 / (&e * &e);
+        let b3_times_e = &u * a1 * b2 + &v * a2 * b1 + &w * ((b1 * b2 + &d) / Integer::TWO);
+        debug_assert_eq!(&b3_times_e % &e, Integer::ZERO);
+        let b3 = b3_times_e / &e;
+        Self::from_discriminant(a3, b3, &d).unwrap().reduce()
+    }
+
+    /// The reduced nth power of a primitive form under composition.
+    pub fn nat_pow(&self, n: &Natural) -> Self {
+        let mut power = Self::principal(&self.discriminant()).unwrap().reduce();
+        let mut square = self.reduce();
+        let mut n = n.clone();
+        while n > Natural::ZERO {
+            if n.odd() {
+                power = power.compose(&square);
+            }
+            square = square.compose(&square);
+            n >>= 1;
+        }
+        power
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(a: i64, b: i64, c: i64) -> BinaryQuadraticForm {
+        BinaryQuadraticForm::new(Integer::from(a), Integer::from(b), Integer::from(c))
+    }
+
+    #[test]
+    fn test_reduce_positive_definite() {
+        let f = form(33, 71, 39);
+        assert_eq!(f.discriminant(), Integer::from(-107));
+        let (g, m) = f.reduce_with_transformation();
+        assert!(g.is_reduced());
+        assert_eq!(f.transform(&m), g);
+        assert_eq!(g, form(1, 1, 27));
+
+        assert_eq!(form(2, -2, 3).reduce(), form(2, 2, 3));
+        assert_eq!(form(3, -2, 3).reduce(), form(3, 2, 3));
+        assert!(!form(2, -2, 3).is_reduced());
+        assert!(form(1, 1, 1).is_reduced());
+
+        assert!(form(1, 0, 5).is_equivalent(&form(6, 10, 5)));
+        let m = [
+            [Integer::from(3), Integer::from(2)],
+            [Integer::from(4), Integer::from(3)],
+        ];
+        assert!(form(2, 2, 3).is_equivalent(&form(2, 2, 3).transform(&m)));
+        assert!(!form(1, 0, 5).is_equivalent(&form(2, 2, 3)));
+        //(2, 1, 3) and (2, -1, 3) are improperly but not properly equivalent
+        assert!(!form(2, 1, 3).is_equivalent(&form(2, -1, 3)));
+
+        //negative definite forms
+        assert!(form(-1, 0, -5).is_equivalent(&form(-6, -10, -5)));
+        assert!(form(-2, -2, -3).is_equivalent(&form(-2, -2, -3).transform(&m)));
+        assert!(!form(-1, 0, -5).is_equivalent(&form(-2, -2, -3)));
+        assert!(!form(-1, 0, -5).is_equivalent(&form(1, 0, 5)));
+        //square discriminants
+        assert!(form(0, 1, 0).is_equivalent(&form(0, 1, 0)));
+        assert!(!form(0, 1, 0).is_equivalent(&form(1, 1, 0)));
+    }
+
+    #[test]
+    fn test_reduce_indefinite() {
+        //square discriminants are excluded
+        let f = form(5, 33, 52);
+        assert_eq!(f.discriminant(), Integer::from(49));
+        assert!(!f.is_indefinite());
+
+        let f = form(7, 37, 43);
+        let d = f.discriminant();
+        assert_eq!(d, Integer::from(165));
+        let (g, m) = f.reduce_with_transformation();
+        assert!(g.is_reduced());
+        assert_eq!(f.transform(&m), g);
+        let cycle = f.cycle();
+        assert!(cycle
+            .iter()
+            .all(|g| g.is_reduced() && g.discriminant() == d));
+        assert_eq!(cycle[0].rho(), cycle[1 % cycle.len()]);
+        assert!(f.is_equivalent(&cycle[cycle.len() - 1]));
+
+        //x^2 - 3y^2 and -x^2 + 3y^2 are not properly equivalent since the fundamental unit 2 + sqrt(3) has norm 1
+        assert!(!form(1, 0, -3).is_equivalent(&form(-1, 0, 3)));
+        //but x^2 - 2y^2 and -x^2 + 2y^2 are since 1 + sqrt(2) has norm -1
+        assert!(form(1, 0, -2).is_equivalent(&form(-1, 0, 2)));
+        let m = [
+            [Integer::from(5), Integer::from(7)],
+            [Integer::from(2), Integer::from(3)],
+        ];
+        assert!(form(1, 0, -2).is_equivalent(&form(1, 0, -2).transform(&m)));
+        assert!(!form(-1, 0, 3).is_equivalent(&form(1, 0, -3).transform(&m)));
+    }
+
+    #[test]
+    fn test_compose() {
+        //discriminant -23 has class group of order 3 generated by (2, 1, 3)
+        let f = form(2, 1, 3);
+        let f2 = f.compose(&f);
+        assert_eq!(f2, form(2, -1, 3));
+        assert_eq!(f2, f.inverse());
+        assert_eq!(f2.compose(&f), form(1, 1, 6));
+        assert_eq!(f.nat_pow(&Natural::from(3u8)), form(1, 1, 6));
+        assert_eq!(f.nat_pow(&Natural::from(5u8)), f2);
+        assert_eq!(f.nat_pow(&Natural::ZERO), form(1, 1, 6));
+
+        //composing non-reduced forms agrees with composing their reductions
+        let g = form(2, 1, 3).transform(&[
+            [Integer::from(3), Integer::from(2)],
+            [Integer::from(4), Integer::from(3)],
+        ]);
+        assert_eq!(g.compose(&f), f2);
+
+        //indefinite forms of discriminant 60
+        let f = form(-1, 6, 6);
+        assert!(f.is_indefinite());
+        let principal = BinaryQuadraticForm::principal(&Integer::from(60)).unwrap();
+        assert!(f.compose(&f.inverse()).is_equivalent(&principal));
+        assert!(f.compose(&principal).is_equivalent(&f));
+    }
+}
@@ -0,0 +1,209 @@
+use malachite_base::num::arithmetic::traits::{Mod, Parity, UnsignedAbs};
+use malachite_base::num::basic::traits::{One, Two, Zero};
+use malachite_nz::{integer::Integer, natural::Natural};
+
+use super::{inv_transformation, mul_transformations, BinaryQuadraticForm, Transformation};
+use crate::number::natural::{factor::factor, functions::all_sqrt_mod};
+use crate::structure::structure::*;
+
+impl BinaryQuadraticForm {
+    //the transformations m of determinant 1 with f.transform(m) = f for a reduced positive definite form f
+    //these depend only on the discriminant of the primitive form f / content
+    fn reduced_automorphs(&self) -> Vec<Transformation> {
+        debug_assert!(self.is_positive_definite() && self.is_reduced());
+        let i = |n: i32| Integer::from(n);
+        let content = Integer::from(self.content());
+        let primitive_discriminant = self.discriminant() / (&content * &content);
+        let generator = if primitive_discriminant == i(-4) {
+            [[i(0), i(-1)], [i(1), i(0)]]
+        } else if primitive_discriminant == i(-3) {
+            [[i(1), i(1)], [i(-1), i(0)]]
+        } else {
+            [[i(-1), i(0)], [i(0), i(-1)]]
+        };
+        let mut automorphs = vec![[[i(1), i(0)], [i(0), i(1)]]];
+        loop {
+            let next = mul_transformations(automorphs.last().unwrap(), &generator);
+            if next == automorphs[0] {
+                break;
+            }
+            automorphs.push(next);
+        }
+        automorphs
+    }
+
+    /// All (x, y) with gcd(x, y) = 1 and f(x, y) = m, for a positive definite form f and m >= 1.
+    ///
+    /// Each primitive representation of m extends to a transformation taking f to a form (m, b, c) with 0 <= b < 2m, where b^2 = D modulo 4m.
+    /// So for each such b the reductions of f and g = (m, b, (b^2 - D)/4m) are compared, and when they agree the representations are read off from the transformations to the common reduced form.
+    pub fn primitive_representations(&self, m: &Natural) -> Vec<(Integer, Integer)> {
+        assert!(
+            self.is_positive_definite(),
+            "the form must be positive definite"
+        );
+        assert!(m > &Natural::ZERO);
+        let d = self.discriminant();
+        let (f_reduced, f_transformation) = self.reduce_with_transformation();
+        let automorphs = f_reduced.reduced_automorphs();
+        let two_m = Natural::TWO * m;
+        let mut representations = vec![];
+        for b in all_sqrt_mod(&d, &(Natural::TWO * &two_m)) {
+            if b >= two_m {
+                continue;
+            }
+            let g = BinaryQuadraticForm::from_discriminant(Integer::from(m), Integer::from(b), &d)
+                .unwrap();
+            let (g_reduced, g_transformation) = g.reduce_with_transformation();
+            if g_reduced != f_reduced {
+                continue;
+            }
+            //g(v) = f(t v) for t = f_transformation automorph g_transformation^-1, and g(1, 0) = m
+            let g_transformation_inv = inv_transformation(&g_transformation);
+            for automorph in &automorphs {
+                let t = mul_transformations(
+                    &mul_transformations(&f_transformation, automorph),
+                    &g_transformation_inv,
+                );
+                let [[x, _], [y, _]] = t;
+                debug_assert_eq!(self.evaluate(&x, &y), Integer::from(m));
+                representations.push((x, y));
+            }
+        }
+        representations.sort();
+        representations.dedup();
+        representations
+    }
+}
+
+/// All (x, y) with x, y >= 0, gcd(x, y) = 1 and x^2 + dy^2 = m, for d >= 1 and m >= 1.
+///
+/// This is Cornacchia's algorithm: the solutions correspond to square roots r of -d modulo m, and each is found by reducing the form (m, 2r, (r^2 + d)/m) of discriminant -4d.
+pub fn cornacchia(d: &Natural, m: &Natural) -> Vec<(Natural, Natural)> {
+    assert!(d > &Natural::ZERO);
+    let f = BinaryQuadraticForm::new(Integer::ONE, Integer::ZERO, Integer::from(d));
+    let mut solutions = f
+        .primitive_representations(m)
+        .into_iter()
+        .filter(|(x, y)| x >= &Integer::ZERO && y >= &Integer::ZERO)
+        .map(|(x, y)| (x.unsigned_abs(), y.unsigned_abs()))
+        .collect::<Vec<_>>();
+    solutions.sort();
+    solutions.dedup();
+    solutions
+}
+
+/// Some (x, y) with x >= y >= 0 and x^2 + y^2 = n if there is one.
+///
+/// Such a pair exists if and only if every prime p = 3 modulo 4 divides n to an even power.
+/// Each prime p = 1 modulo 4 is written as p = a^2 + b^2 by Cornacchia's algorithm, and these are multiplied together as Gaussian integers a + bi.
+pub fn sum_of_two_squares(n: &Natural) -> Option<(Natural, Natural)> {
+    if n == &Natural::ZERO {
+        return Some((Natural::ZERO, Natural::ZERO));
+    }
+    let gaussian_mul = |(a, b): (Integer, Integer), (c, d): (&Integer, &Integer)| {
+        (&a * c - &b * d, &a * d + &b * c)
+    };
+    let mut z = (Integer::ONE, Integer::ZERO);
+    for (p, k) in factor(n.clone()).unwrap().into_powers() {
+        let p_mod_4 = (&p).mod_op(Natural::from(4u8));
+        let (a, b) = if p == Natural::TWO {
+            (Integer::ONE, Integer::ONE)
+        } else if p_mod_4 == Natural::ONE {
+            let (a, b) = cornacchia(&Natural::ONE, &p).into_iter().next().unwrap();
+            (Integer::from(a), Integer::from(b))
+        } else if k.even() {
+            //p^k = (p^(k/2))^2 + 0^2
+            let half = Integer::from(p.nat_pow(&(k / Natural::TWO)));
+            z = (&z.0 * &half, &z.1 * &half);
+            continue;
+        } else {
+            return None;
+        };
+        let mut i = Natural::ZERO;
+        while i < k {
+            z = gaussian_mul(z, (&a, &b));
+            i += Natural::ONE;
+        }
+    }
+    let (x, y) = (z.0.unsigned_abs(), z.1.unsigned_abs());
+    Some(if x >= y { (x, y) } else { (y, x) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitive_representations() {
+        let f = BinaryQuadraticForm::new(Integer::from(2), Integer::from(1), Integer::from(3));
+        for m in 1u32..100 {
+            let representations = f.primitive_representations(&Natural::from(m));
+            //check against a search over a box containing all representations
+            let mut expected = vec![];
+            for x in -20i32..=20 {
+                for y in -20i32..=20 {
+                    let (x, y) = (Integer::from(x), Integer::from(y));
+                    if Integer::gcd(&x, &y) == Integer::ONE
+                        && f.evaluate(&x, &y) == Integer::from(m)
+                    {
+                        expected.push((x, y));
+                    }
+                }
+            }
+            expected.sort();
+            assert_eq!(representations, expected);
+        }
+        let f = BinaryQuadraticForm::new(Integer::from(1), Integer::from(1), Integer::from(1));
+        assert_eq!(f.primitive_representations(&Natural::from(7u8)).len(), 12);
+        assert_eq!(f.primitive_representations(&Natural::from(1u8)).len(), 6);
+
+        //a non-primitive form with the automorphs of x^2 + y^2
+        let f = BinaryQuadraticForm::new(Integer::from(2), Integer::ZERO, Integer::from(2));
+        let i = |n: i32| Integer::from(n);
+        assert_eq!(
+            f.primitive_representations(&Natural::TWO),
+            vec![(i(-1), i(0)), (i(0), i(-1)), (i(0), i(1)), (i(1), i(0))]
+        );
+        assert_eq!(f.primitive_representations(&Natural::from(10u8)).len(), 8);
+        assert!(f.primitive_representations(&Natural::from(3u8)).is_empty());
+    }
+
+    #[test]
+    fn test_cornacchia() {
+        let n = |k: u32| Natural::from(k);
+        assert_eq!(cornacchia(&n(1), &n(13)), vec![(n(2), n(3)), (n(3), n(2))]);
+        assert_eq!(cornacchia(&n(1), &n(1)), vec![(n(0), n(1)), (n(1), n(0))]);
+        assert_eq!(cornacchia(&n(1), &n(3)), vec![]);
+        assert_eq!(cornacchia(&n(2), &n(11)), vec![(n(3), n(1))]);
+        assert_eq!(cornacchia(&n(5), &n(29)), vec![(n(3), n(2))]);
+        //65 = 1 + 64 = 49 + 16, and 4^2 + 7^2 is the other order
+        assert_eq!(
+            cornacchia(&n(1), &n(65)),
+            vec![(n(1), n(8)), (n(4), n(7)), (n(7), n(4)), (n(8), n(1))]
+        );
+        //25 = 0 + 25 = 9 + 16, but only 3^2 + 4^2 is primitive
+        assert_eq!(cornacchia(&n(1), &n(25)), vec![(n(3), n(4)), (n(4), n(3))]);
+    }
+
+    #[test]
+    fn test_sum_of_two_squares() {
+        for k in 0u32..500 {
+            let n = Natural::from(k);
+            let expected = (0u32..=k).any(|x| (0u32..=x).any(|y| x * x + y * y == k));
+            match sum_of_two_squares(&n) {
+                Some((x, y)) => {
+                    assert!(x >= y);
+                    assert_eq!(&x * &x + &y * &y, n);
+                }
+                None => assert!(!expected),
+            }
+        }
+        assert_eq!(
+            sum_of_two_squares(&Natural::from(9u8)),
+            Some((Natural::from(3u8), Natural::ZERO))
+        );
+        let n = Natural::from(1000000009u32) * Natural::from(998244353u32);
+        let (x, y) = sum_of_two_squares(&n).unwrap();
+        assert_eq!(&x * &x + &y * &y, n);
+    }
+}
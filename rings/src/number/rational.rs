@@ -6,6 +6,7 @@ use malachite_base::num::basic::traits::Zero;
 use malachite_nz::integer::Integer;
 use malachite_q::Rational;
 
+use super::super::polynomial::multiplication::rational_polynomial_mul;
 use super::super::polynomial::polynomial::*;
 use super::super::structure::factorization::*;
 use super::super::structure::structure::*;
@@ -27,6 +28,10 @@ impl SemiRingStructure for CannonicalStructure<Rational> {
     fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
         a * b
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        rational_polynomial_mul(a, b)
+    }
 }
 
 impl RingStructure for CannonicalStructure<Rational> {
@@ -0,0 +1,211 @@
+use malachite_nz::integer::Integer;
+
+use super::polynomial::*;
+use super::power_series::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+impl<FS: FieldStructure> PolynomialStructure<FS> {
+    /// For f of degree n and s dividing n, the polynomial h of degree s, monic with h(0) = 0, such that f = g(h) for some g, if there is one. Such h is unique.
+    /// By the algorithm of Kozen and Landau: the top s coefficients of f agree with those of lc(f) h^(n/s), so h is read off from an (n/s)th root of the reversal of f.
+    /// The characteristic of the field must not divide n/s.
+    pub fn right_composition_factor(
+        &self,
+        f: &Polynomial<FS::Set>,
+        s: usize,
+    ) -> Option<Polynomial<FS::Set>> {
+        let field = self.coeff_ring();
+        let n = self.degree(f)?;
+        if s == 0 || !n.is_multiple_of(s) {
+            return None;
+        }
+        let series_ring = PowerSeriesStructure::new(field.clone(), s);
+        let monic = self.mul_scalar(f, &field.inv(self.leading_coeff(f).unwrap()).unwrap());
+        let root =
+            series_ring.nth_root(&series_ring.from_polynomial(&self.reversed(&monic)), n / s)?;
+        let h = self.reduce_poly(Polynomial::from_coeffs(
+            std::iter::once(field.zero())
+                .chain((0..s).rev().map(|i| {
+                    series_ring
+                        .coeff(&root, i)
+                        .cloned()
+                        .unwrap_or_else(|| field.zero())
+                }))
+                .collect(),
+        ));
+        self.left_composition_factor(f, &h)?;
+        Some(h)
+    }
+
+    /// The polynomial g with f = g(h), if there is one, from the expansion of f in powers of h.
+    pub fn left_composition_factor(
+        &self,
+        f: &Polynomial<FS::Set>,
+        h: &Polynomial<FS::Set>,
+    ) -> Option<Polynomial<FS::Set>> {
+        if self.degree(h)? == 0 {
+            return None;
+        }
+        //the digits of f in base h must be constants
+        let mut g_coeffs = vec![];
+        let mut rest = f.clone();
+        while !self.is_zero(&rest) {
+            let (quo, rem) = self.quorem(&rest, h).unwrap();
+            g_coeffs.push(self.as_constant(&rem)?);
+            rest = quo;
+        }
+        Some(Polynomial::from_coeffs(g_coeffs))
+    }
+
+    fn is_indecomposable_tame(&self, f: &Polynomial<FS::Set>) -> bool {
+        let n = self.degree(f).unwrap();
+        (2..n)
+            .filter(|s| n.is_multiple_of(*s))
+            .all(|s| self.right_composition_factor(f, s).is_none())
+    }
+
+    fn decompose_tame(&self, f: &Polynomial<FS::Set>) -> Vec<Vec<Polynomial<FS::Set>>> {
+        let n = self.degree(f).unwrap();
+        let mut decompositions = vec![];
+        //each decomposition is found once from its last component, which is determined by its degree
+        for s in (2..n).filter(|s| n.is_multiple_of(*s)) {
+            if let Some(h) = self.right_composition_factor(f, s) {
+                if self.is_indecomposable_tame(&h) {
+                    let g = self.left_composition_factor(f, &h).unwrap();
+                    for mut decomposition in self.decompose_tame(&g) {
+                        decomposition.push(h.clone());
+                        decompositions.push(decomposition);
+                    }
+                }
+            }
+        }
+        if decompositions.is_empty() {
+            decompositions.push(vec![f.clone()]);
+        }
+        decompositions
+    }
+
+    /// All complete decompositions f = g_1(g_2(...(g_k))) into indecomposable polynomials of degree at least 2.
+    /// The components after the first are monic with zero constant term, which determines them.
+    /// A polynomial of degree at most 1 is its own only decomposition.
+    /// Returns an error if the characteristic of the field divides the degree of f, when the decompositions can not be found this way.
+    pub fn decompose(
+        &self,
+        f: &Polynomial<FS::Set>,
+    ) -> Result<Vec<Vec<Polynomial<FS::Set>>>, &'static str> {
+        let field = self.coeff_ring();
+        match self.degree(f) {
+            None | Some(0) | Some(1) => Ok(vec![vec![f.clone()]]),
+            Some(n) => {
+                if field.is_zero(&field.from_int(&Integer::from(n))) {
+                    Err("the characteristic divides the degree")
+                } else {
+                    Ok(self.decompose_tame(f))
+                }
+            }
+        }
+    }
+}
+
+impl<F: MetaType> Polynomial<F>
+where
+    F::Structure: FieldStructure,
+{
+    pub fn right_composition_factor(&self, s: usize) -> Option<Self> {
+        Self::structure().right_composition_factor(self, s)
+    }
+
+    pub fn left_composition_factor(&self, h: &Self) -> Option<Self> {
+        Self::structure().left_composition_factor(self, h)
+    }
+
+    pub fn decompose(&self) -> Result<Vec<Vec<Self>>, &'static str> {
+        Self::structure().decompose(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+    use crate::structure::elements::*;
+    use malachite_q::Rational;
+
+    fn check_decompositions<F: MetaType>(f: &Polynomial<F>, decompositions: &[Vec<Polynomial<F>>])
+    where
+        F::Structure: FieldStructure,
+    {
+        for decomposition in decompositions {
+            let mut g = decomposition[0].clone();
+            for h in &decomposition[1..] {
+                assert!(h.degree().unwrap() >= 2);
+                assert!(Polynomial::<F>::structure().is_monic(h));
+                assert!(F::structure().is_zero(&h.coeff(0)));
+                g = Polynomial::compose(&g, h);
+            }
+            assert_eq!(&g, f);
+        }
+    }
+
+    #[test]
+    fn test_decompose_over_rationals() {
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+
+        //(x^3 + x)^2 + 1 = (y^3 + 2y^2 + y + 1)(x^2)
+        let f = ((x.pow(3) + x).pow(2) + 1).into_verbose();
+        let decompositions = f.decompose().unwrap();
+        check_decompositions(&f, &decompositions);
+        assert_eq!(decompositions.len(), 2);
+        assert!(decompositions.contains(&vec![
+            (x.pow(2) + 1).into_verbose(),
+            (x.pow(3) + x).into_verbose()
+        ]));
+        assert!(decompositions.contains(&vec![
+            (x.pow(3) + 2 * x.pow(2) + x + 1).into_verbose(),
+            x.pow(2).into_verbose()
+        ]));
+
+        //the chebyshev polynomial T_6 = T_2(T_3) = T_3(T_2)
+        let f = (32 * x.pow(6) - 48 * x.pow(4) + 18 * x.pow(2) - 1).into_verbose();
+        let decompositions = f.decompose().unwrap();
+        check_decompositions(&f, &decompositions);
+        assert_eq!(decompositions.len(), 2);
+
+        //x^8 only decomposes as x^2(x^2(x^2)), and shifted by 1 it is 3 (x + 1)^8 - 2
+        let f = (3 * (x + 1).pow(8) - 2).into_verbose();
+        let decompositions = f.decompose().unwrap();
+        check_decompositions(&f, &decompositions);
+        assert_eq!(decompositions.len(), 1);
+        assert_eq!(decompositions[0].len(), 3);
+
+        //indecomposable
+        let f = (x.pow(4) + x + 1).into_verbose();
+        assert_eq!(f.decompose().unwrap(), vec![vec![f.clone()]]);
+        assert!(f.right_composition_factor(2).is_none());
+        let h = (x.pow(2) + x).into_verbose();
+        assert!(f.left_composition_factor(&h).is_none());
+        assert_eq!(
+            Polynomial::compose(&f, &h).left_composition_factor(&h),
+            Some(f.clone())
+        );
+        let f = (x.pow(5) + 2).into_verbose();
+        assert_eq!(f.decompose().unwrap(), vec![vec![f.clone()]]);
+    }
+
+    #[test]
+    fn test_decompose_over_finite_field() {
+        let x = &Polynomial::<Modulo<5>>::var().into_ergonomic();
+        let f = Polynomial::compose(
+            &(x.pow(2) + 2).into_verbose(),
+            &(x.pow(3) + x + 1).into_verbose(),
+        );
+        let decompositions = f.decompose().unwrap();
+        check_decompositions(&f, &decompositions);
+        assert!(decompositions
+            .iter()
+            .any(|d| d.len() == 2 && d[1] == (x.pow(3) + x).into_verbose()));
+
+        //the characteristic divides the degree
+        assert!((x.pow(5) + x).into_verbose().decompose().is_err());
+    }
+}
@@ -0,0 +1,341 @@
+use super::polynomial::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+//quorem uses power series inversion when both the divisor and the quotient have at least this many coefficients
+pub(crate) const FAST_DIVISION_THRESHOLD: usize = 128;
+//gcd and xgcd use the half gcd algorithm when both inputs have at least this many coefficients
+pub(crate) const HALF_GCD_THRESHOLD: usize = 128;
+
+/// A 2x2 matrix of polynomials acting on pairs of polynomials, as produced by the half gcd algorithm.
+pub type PolynomialMatrix<Set> = [[Polynomial<Set>; 2]; 2];
+
+/// A quotient and remainder (q, r).
+pub type PolynomialQuoRem<Set> = (Polynomial<Set>, Polynomial<Set>);
+
+/// A gcd g together with Bezout coefficients (g, s, t).
+pub type PolynomialXgcd<Set> = (Polynomial<Set>, Polynomial<Set>, Polynomial<Set>);
+
+impl<RS: SemiRingStructure> PolynomialStructure<RS> {
+    /// The remainder of p on division by x^n.
+    pub fn truncate(&self, p: &Polynomial<RS::Set>, n: usize) -> Polynomial<RS::Set> {
+        self.reduce_poly(Polynomial::from_coeffs(
+            p.coeffs().into_iter().take(n).cloned().collect(),
+        ))
+    }
+
+    /// The quotient of p on division by x^n.
+    pub fn shift_down(&self, p: &Polynomial<RS::Set>, n: usize) -> Polynomial<RS::Set> {
+        self.reduce_poly(Polynomial::from_coeffs(
+            p.coeffs().into_iter().skip(n).cloned().collect(),
+        ))
+    }
+}
+
+impl<FS: FieldStructure> PolynomialStructure<FS> {
+    /// The inverse of f modulo x^n by Newton iteration. None if the constant term of f is zero.
+    pub fn power_series_inverse(
+        &self,
+        f: &Polynomial<FS::Set>,
+        n: usize,
+    ) -> Option<Polynomial<FS::Set>> {
+        let mut g = Polynomial::constant(self.coeff_ring().inv(self.coeff(f, 0)).ok()?);
+        if n == 0 {
+            return Some(self.zero());
+        }
+        //each step doubles the number of correct coefficients of g
+        let mut precision = 1;
+        while precision < n {
+            precision = std::cmp::min(2 * precision, n);
+            //g <- g + g (1 - f g)
+            let error = self.add(
+                &self.one(),
+                &self.neg(&self.truncate(&self.mul(&self.truncate(f, precision), &g), precision)),
+            );
+            g = self.truncate(&self.add(&g, &self.mul(&g, &error)), precision);
+        }
+        Some(g)
+    }
+
+    /// Division with remainder computing the quotient from a power series inverse of the reversed divisor. None if b is zero.
+    pub fn quorem_by_newton(
+        &self,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+    ) -> Option<PolynomialQuoRem<FS::Set>> {
+        let a = self.reduce_poly(a.clone());
+        let b = self.reduce_poly(b.clone());
+        let m = self.degree(&b)?;
+        let n = match self.degree(&a) {
+            Some(n) if n >= m => n,
+            _ => {
+                return Some((self.zero(), a));
+            }
+        };
+        let b_rev_inv = self
+            .power_series_inverse(&self.reversed(&b), n - m + 1)
+            .unwrap();
+        Some(self.quorem_by_reversed_inverse(&a, &b, &b_rev_inv))
+    }
+
+    /// Division with remainder of a by a nonzero b given the inverse of the reversal of b modulo x^k for some k > deg(a) - deg(b).
+    /// Useful for many divisions by the same b.
+    pub fn quorem_by_reversed_inverse(
+        &self,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+        b_rev_inv: &Polynomial<FS::Set>,
+    ) -> (Polynomial<FS::Set>, Polynomial<FS::Set>) {
+        let m = self.degree(b).unwrap();
+        let n = match self.degree(a) {
+            Some(n) if n >= m => n,
+            _ => {
+                return (self.zero(), a.clone());
+            }
+        };
+        //the reversal of the quotient is the reversal of a divided by the reversal of b modulo x^(n-m+1)
+        let k = n - m + 1;
+        let mut q_coeffs = self
+            .truncate(
+                &self.mul(
+                    &self.truncate(&self.reversed(a), k),
+                    &self.truncate(b_rev_inv, k),
+                ),
+                k,
+            )
+            .into_coeffs();
+        q_coeffs.resize(k, self.coeff_ring().zero());
+        q_coeffs.reverse();
+        let q = self.reduce_poly(Polynomial::from_coeffs(q_coeffs));
+        let r = self.add(a, &self.neg(&self.mul(&q, b)));
+        debug_assert!(self.degree(&r).is_none_or(|d| d < m));
+        (q, r)
+    }
+
+    fn identity_matrix(&self) -> PolynomialMatrix<FS::Set> {
+        [[self.one(), self.zero()], [self.zero(), self.one()]]
+    }
+
+    //the matrix taking (a, b) to (b, a - q b)
+    fn quotient_matrix(&self, q: &Polynomial<FS::Set>) -> PolynomialMatrix<FS::Set> {
+        [[self.zero(), self.one()], [self.one(), self.neg(q)]]
+    }
+
+    fn matrix_mul(
+        &self,
+        m: &PolynomialMatrix<FS::Set>,
+        n: &PolynomialMatrix<FS::Set>,
+    ) -> PolynomialMatrix<FS::Set> {
+        [0, 1].map(|i| {
+            [0, 1].map(|j| self.add(&self.mul(&m[i][0], &n[0][j]), &self.mul(&m[i][1], &n[1][j])))
+        })
+    }
+
+    fn matrix_apply(
+        &self,
+        m: &PolynomialMatrix<FS::Set>,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+    ) -> (Polynomial<FS::Set>, Polynomial<FS::Set>) {
+        (
+            self.add(&self.mul(&m[0][0], a), &self.mul(&m[0][1], b)),
+            self.add(&self.mul(&m[1][0], a), &self.mul(&m[1][1], b)),
+        )
+    }
+
+    /// For deg(a) > deg(b) a matrix M, a product of Euclidean quotient steps, such that M(a, b) = (c, d) are consecutive remainders of a and b with deg(c) >= ceil(deg(a)/2) > deg(d).
+    pub fn half_gcd(
+        &self,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+    ) -> PolynomialMatrix<FS::Set> {
+        let n = self.degree(a).unwrap();
+        debug_assert!(self.degree(b).is_none_or(|d| d < n));
+        let m = n.div_ceil(2);
+        let at_least_m = |p: &Polynomial<FS::Set>| self.degree(p).is_some_and(|d| d >= m);
+        if !at_least_m(b) {
+            return self.identity_matrix();
+        }
+        if n < HALF_GCD_THRESHOLD {
+            let mut matrix = self.identity_matrix();
+            let (mut c, mut d) = (a.clone(), b.clone());
+            while at_least_m(&d) {
+                let (q, r) = self.try_quorem(&c, &d).unwrap();
+                matrix = self.matrix_mul(&self.quotient_matrix(&q), &matrix);
+                (c, d) = (d, r);
+            }
+            return matrix;
+        }
+
+        //the quotients of the top halves of a and b agree with the first quotients of a and b
+        let r = self.half_gcd(&self.shift_down(a, m), &self.shift_down(b, m));
+        let (c, d) = self.matrix_apply(&r, a, b);
+        let l = match self.degree(&d) {
+            Some(l) if l >= m => l,
+            _ => {
+                return r;
+            }
+        };
+        let (q, e) = self.quorem(&c, &d).unwrap();
+        let k = 2 * m - l;
+        let s = self.half_gcd(&self.shift_down(&d, k), &self.shift_down(&e, k));
+        self.matrix_mul(&s, &self.matrix_mul(&self.quotient_matrix(&q), &r))
+    }
+
+    //the last nonzero remainder g of a and b, up to a unit, and if cofactors is set also s, t such that g = s a + t b
+    fn half_gcd_remainder(
+        &self,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+        cofactors: bool,
+    ) -> (Polynomial<FS::Set>, PolynomialMatrix<FS::Set>) {
+        let mut matrix = self.identity_matrix();
+        let (mut c, mut d) = (a.clone(), b.clone());
+        while !self.is_zero(&d) {
+            //a division step makes deg(c) > deg(d) and then each half gcd step halves the degree
+            let (q, r) = self.quorem(&c, &d).unwrap();
+            if cofactors {
+                matrix = self.matrix_mul(&self.quotient_matrix(&q), &matrix);
+            }
+            (c, d) = (d, r);
+            if !self.is_zero(&d) && self.degree(&c).unwrap() >= HALF_GCD_THRESHOLD {
+                let h = self.half_gcd(&c, &d);
+                (c, d) = self.matrix_apply(&h, &c, &d);
+                if cofactors {
+                    matrix = self.matrix_mul(&h, &matrix);
+                }
+            }
+        }
+        (c, matrix)
+    }
+
+    /// The monic gcd of a and b computed by the half gcd algorithm.
+    pub fn gcd_by_half_gcd(
+        &self,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+    ) -> Polynomial<FS::Set> {
+        let (g, _matrix) = self.half_gcd_remainder(a, b, false);
+        let (_unit, g) = self.factor_fav_assoc(&g);
+        g
+    }
+
+    /// The monic gcd g of a and b together with s, t such that g = s a + t b, computed by the half gcd algorithm.
+    pub fn xgcd_by_half_gcd(
+        &self,
+        a: &Polynomial<FS::Set>,
+        b: &Polynomial<FS::Set>,
+    ) -> PolynomialXgcd<FS::Set> {
+        let (g, [[s, t], _]) = self.half_gcd_remainder(a, b, true);
+        if self.is_zero(&g) {
+            return (g, self.one(), self.zero());
+        }
+        let (unit, g) = self.factor_fav_assoc(&g);
+        (
+            g,
+            self.div(&s, &unit).unwrap(),
+            self.div(&t, &unit).unwrap(),
+        )
+    }
+}
+
+impl<F: MetaType> Polynomial<F>
+where
+    F::Structure: FieldStructure,
+{
+    pub fn power_series_inverse(&self, n: usize) -> Option<Self> {
+        Self::structure().power_series_inverse(self, n)
+    }
+
+    pub fn gcd_by_half_gcd(a: &Self, b: &Self) -> Self {
+        Self::structure().gcd_by_half_gcd(a, b)
+    }
+
+    pub fn xgcd_by_half_gcd(a: &Self, b: &Self) -> (Self, Self, Self) {
+        Self::structure().xgcd_by_half_gcd(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+    use malachite_q::Rational;
+
+    //a deterministic polynomial of degree n over Z/101
+    fn pseudorandom(n: usize, seed: usize) -> Polynomial<Modulo<101>> {
+        let mut state = seed;
+        let mut coeffs = (0..n)
+            .map(|_| {
+                state = (state * 1103515245 + 12345) % 2147483648;
+                Modulo::<101>::from(state >> 8)
+            })
+            .collect::<Vec<_>>();
+        coeffs.push(Modulo::from(1usize));
+        Polynomial::from_coeffs(coeffs)
+    }
+
+    #[test]
+    fn test_power_series_inverse() {
+        let f = Polynomial::<Rational>::from_coeffs(vec![1, -1]);
+        let g = f.power_series_inverse(10).unwrap();
+        assert_eq!(g, Polynomial::from_coeffs(vec![1; 10]));
+        assert!(Polynomial::<Rational>::var()
+            .power_series_inverse(3)
+            .is_none());
+
+        let f = pseudorandom(100, 1);
+        let g = f.power_series_inverse(150).unwrap();
+        let ring = Polynomial::<Modulo<101>>::structure();
+        assert_eq!(ring.truncate(&ring.mul(&f, &g), 150), ring.one());
+    }
+
+    #[test]
+    fn test_quorem_by_newton() {
+        let ring = Polynomial::<Modulo<101>>::structure();
+        for (n, m) in [(300, 100), (100, 100), (50, 120), (200, 1), (150, 80)] {
+            let a = pseudorandom(n, n);
+            let b = pseudorandom(m, m + 7);
+            assert_eq!(
+                ring.quorem_by_newton(&a, &b).unwrap(),
+                ring.try_quorem(&a, &b).unwrap()
+            );
+        }
+        let ring = Polynomial::<Rational>::structure();
+        let a = Polynomial::<Rational>::from_coeffs((0..90).map(|i| i * i - 7).collect());
+        let b = Polynomial::<Rational>::from_coeffs((0..70).map(|i| 3 - 2 * i).collect());
+        assert_eq!(
+            ring.quorem_by_newton(&a, &b).unwrap(),
+            ring.try_quorem(&a, &b).unwrap()
+        );
+        assert!(ring.quorem_by_newton(&a, &ring.zero()).is_none());
+    }
+
+    #[test]
+    fn test_half_gcd() {
+        let ring = Polynomial::<Modulo<101>>::structure();
+        let common = pseudorandom(60, 3);
+        for (n, m) in [(200, 150), (300, 299), (150, 250), (100, 0)] {
+            let a = ring.mul(&common, &pseudorandom(n, n + 1));
+            let b = ring.mul(&common, &pseudorandom(m, m + 2));
+            let g = ring.gcd_by_half_gcd(&a, &b);
+            assert_eq!(g, ring.euclidean_gcd(a.clone(), b.clone()));
+            assert_eq!(ring.degree(&ring.rem(&g, &common)), None);
+            let (g2, s, t) = ring.xgcd_by_half_gcd(&a, &b);
+            assert_eq!(g, g2);
+            assert_eq!(ring.add(&ring.mul(&s, &a), &ring.mul(&t, &b)), g);
+            assert_eq!((g, s, t), ring.euclidean_xgcd(a.clone(), b.clone()));
+        }
+        //coprime inputs
+        let a = pseudorandom(180, 5);
+        let b = pseudorandom(170, 6);
+        assert_eq!(ring.gcd_by_half_gcd(&a, &b), ring.one());
+
+        //half gcd reduces the degree by half
+        let a = pseudorandom(257, 8);
+        let b = pseudorandom(256, 9);
+        let (c, d) = ring.matrix_apply(&ring.half_gcd(&a, &b), &a, &b);
+        assert!(ring.degree(&c).unwrap() >= 129);
+        assert!(ring.degree(&d).unwrap() < 129);
+    }
+}
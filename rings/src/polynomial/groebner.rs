@@ -0,0 +1,424 @@
+use super::multipoly::*;
+use crate::structure::structure::*;
+use std::cmp::Ordering;
+
+/// A monomial ordering with respect to a list of variables, the first being the largest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonomialOrdering {
+    Lexicographic,
+    GradedReverseLexicographic,
+}
+
+impl MonomialOrdering {
+    fn cmp(&self, a: &[usize], b: &[usize]) -> Ordering {
+        match self {
+            MonomialOrdering::Lexicographic => a.cmp(b),
+            MonomialOrdering::GradedReverseLexicographic => {
+                let deg_a: usize = a.iter().sum();
+                let deg_b: usize = b.iter().sum();
+                deg_a
+                    .cmp(&deg_b)
+                    .then_with(|| b.iter().rev().cmp(a.iter().rev()))
+            }
+        }
+    }
+}
+
+fn divides(a: &[usize], b: &[usize]) -> bool {
+    a.iter().zip(b.iter()).all(|(i, j)| i <= j)
+}
+
+fn lcm(a: &[usize], b: &[usize]) -> Vec<usize> {
+    a.iter()
+        .zip(b.iter())
+        .map(|(i, j)| *std::cmp::max(i, j))
+        .collect()
+}
+
+fn coprime(a: &[usize], b: &[usize]) -> bool {
+    a.iter().zip(b.iter()).all(|(i, j)| *i == 0 || *j == 0)
+}
+
+//a polynomial as its nonzero terms in decreasing order
+type Terms<T> = Vec<(Vec<usize>, T)>;
+
+impl<FS: FieldStructure> MultiPolynomialStructure<FS> {
+    //the terms of p as exponent vectors with respect to vars
+    fn poly_to_terms(&self, p: &MultiPolynomial<FS::Set>, vars: &[Variable]) -> Terms<FS::Set> {
+        fn collect<FS: FieldStructure>(
+            ring: &MultiPolynomialStructure<FS>,
+            p: &MultiPolynomial<FS::Set>,
+            vars: &[Variable],
+            exponents: &mut Vec<usize>,
+            terms: &mut Terms<FS::Set>,
+        ) {
+            match vars.split_first() {
+                None => {
+                    let c = ring
+                        .as_constant(p)
+                        .expect("polynomial involves a variable not in vars");
+                    if !ring.coeff_ring().is_zero(&c) {
+                        terms.push((exponents.clone(), c));
+                    }
+                }
+                Some((v, rest)) => {
+                    for (k, c) in ring.expand(p, v).into_coeffs().into_iter().enumerate() {
+                        exponents.push(k);
+                        collect(ring, &c, rest, exponents, terms);
+                        exponents.pop();
+                    }
+                }
+            }
+        }
+        let mut terms = vec![];
+        collect(self, p, vars, &mut vec![], &mut terms);
+        terms
+    }
+
+    fn terms_to_poly(&self, terms: &Terms<FS::Set>, vars: &[Variable]) -> MultiPolynomial<FS::Set> {
+        self.sum(
+            terms
+                .iter()
+                .map(|(exponents, c)| {
+                    self.product(
+                        std::iter::once(MultiPolynomial::constant(c.clone()))
+                            .chain(
+                                vars.iter()
+                                    .zip(exponents.iter())
+                                    .map(|(v, k)| self.var_pow(v.clone(), *k)),
+                            )
+                            .collect::<Vec<_>>()
+                            .iter()
+                            .collect(),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .iter()
+                .collect(),
+        )
+    }
+
+    //f - c x^m g
+    fn sub_monomial_mul(
+        &self,
+        ordering: MonomialOrdering,
+        f: &Terms<FS::Set>,
+        c: &FS::Set,
+        m: &[usize],
+        g: &Terms<FS::Set>,
+    ) -> Terms<FS::Set> {
+        let field = self.coeff_ring();
+        let mut result = vec![];
+        let mut f = f.iter().peekable();
+        let mut g = g
+            .iter()
+            .map(|(e, d)| {
+                (
+                    e.iter()
+                        .zip(m.iter())
+                        .map(|(i, j)| i + j)
+                        .collect::<Vec<_>>(),
+                    field.neg(&field.mul(c, d)),
+                )
+            })
+            .peekable();
+        loop {
+            match (f.peek(), g.peek()) {
+                (None, None) => break,
+                (Some(_), None) => result.push(f.next().unwrap().clone()),
+                (None, Some(_)) => result.push(g.next().unwrap()),
+                (Some((fe, _)), Some((ge, _))) => match ordering.cmp(fe, ge) {
+                    Ordering::Greater => result.push(f.next().unwrap().clone()),
+                    Ordering::Less => result.push(g.next().unwrap()),
+                    Ordering::Equal => {
+                        let (e, a) = f.next().unwrap();
+                        let (_, b) = g.next().unwrap();
+                        let s = field.add(a, &b);
+                        if !field.is_zero(&s) {
+                            result.push((e.clone(), s));
+                        }
+                    }
+                },
+            }
+        }
+        result
+    }
+
+    //the remainder of f on division by the polynomials in basis
+    fn normal_form(
+        &self,
+        ordering: MonomialOrdering,
+        mut f: Terms<FS::Set>,
+        basis: &[Terms<FS::Set>],
+    ) -> Terms<FS::Set> {
+        let field = self.coeff_ring();
+        let mut remainder = vec![];
+        //terms of f before index i are irreducible
+        let mut i = 0;
+        while i < f.len() {
+            let (e, c) = &f[i];
+            match basis.iter().find(|g| divides(&g[0].0, e)) {
+                Some(g) => {
+                    let (ge, gc) = &g[0];
+                    let m = e
+                        .iter()
+                        .zip(ge.iter())
+                        .map(|(a, b)| a - b)
+                        .collect::<Vec<_>>();
+                    let q = field.div(c, gc).unwrap();
+                    let tail = f.split_off(i);
+                    remainder.extend(f);
+                    f = self.sub_monomial_mul(ordering, &tail, &q, &m, g);
+                    i = 0;
+                }
+                None => {
+                    i += 1;
+                }
+            }
+        }
+        remainder.extend(f);
+        remainder
+    }
+
+    fn make_monic(&self, f: Terms<FS::Set>) -> Terms<FS::Set> {
+        let field = self.coeff_ring();
+        let lc_inv = field.inv(&f[0].1).unwrap();
+        f.into_iter()
+            .map(|(e, c)| (e, field.mul(&c, &lc_inv)))
+            .collect()
+    }
+
+    fn groebner_basis_terms(
+        &self,
+        ordering: MonomialOrdering,
+        polys: Vec<Terms<FS::Set>>,
+    ) -> Vec<Terms<FS::Set>> {
+        let field = self.coeff_ring();
+        let mut basis: Vec<Terms<FS::Set>> = vec![];
+        for f in polys {
+            let f = self.normal_form(ordering, f, &basis);
+            if !f.is_empty() {
+                basis.push(self.make_monic(f));
+            }
+        }
+
+        //Buchberger's algorithm, skipping pairs with coprime leading monomials
+        let mut pairs = (0..basis.len())
+            .flat_map(|j| (0..j).map(move |i| (i, j)))
+            .collect::<Vec<_>>();
+        while let Some((i, j)) = pairs.pop() {
+            let (ei, ci) = &basis[i][0];
+            let (ej, cj) = &basis[j][0];
+            if coprime(ei, ej) {
+                continue;
+            }
+            let l = lcm(ei, ej);
+            let mi = l
+                .iter()
+                .zip(ei.iter())
+                .map(|(a, b)| a - b)
+                .collect::<Vec<_>>();
+            let mj = l
+                .iter()
+                .zip(ej.iter())
+                .map(|(a, b)| a - b)
+                .collect::<Vec<_>>();
+            let s = self.sub_monomial_mul(
+                ordering,
+                &self.sub_monomial_mul(
+                    ordering,
+                    &vec![],
+                    &field.neg(&field.inv(ci).unwrap()),
+                    &mi,
+                    &basis[i],
+                ),
+                &field.inv(cj).unwrap(),
+                &mj,
+                &basis[j],
+            );
+            let h = self.normal_form(ordering, s, &basis);
+            if !h.is_empty() {
+                let k = basis.len();
+                basis.push(self.make_monic(h));
+                pairs.extend((0..k).map(|i| (i, k)));
+            }
+        }
+
+        //keep only the polynomials whose leading monomial is minimal, then reduce each by the others
+        let mut minimal: Vec<Terms<FS::Set>> = vec![];
+        for (i, g) in basis.iter().enumerate() {
+            if !basis
+                .iter()
+                .enumerate()
+                .any(|(j, h)| j != i && divides(&h[0].0, &g[0].0) && (h[0].0 != g[0].0 || j < i))
+            {
+                minimal.push(g.clone());
+            }
+        }
+        let mut reduced = vec![];
+        for i in 0..minimal.len() {
+            let others = minimal
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, h)| h.clone())
+                .collect::<Vec<_>>();
+            let mut g = minimal[i].clone();
+            let tail = g.split_off(1);
+            g.extend(self.normal_form(ordering, tail, &others));
+            reduced.push(g);
+        }
+        reduced.sort_by(|a, b| ordering.cmp(&a[0].0, &b[0].0));
+        reduced
+    }
+
+    /// The reduced Groebner basis, sorted by increasing leading monomial, of the ideal generated by polys with respect to a monomial ordering on vars.
+    /// The polynomials may only involve the variables vars. The basis of the zero ideal is empty and the basis of the unit ideal is 1.
+    pub fn groebner_basis(
+        &self,
+        polys: &[MultiPolynomial<FS::Set>],
+        vars: &[Variable],
+        ordering: MonomialOrdering,
+    ) -> Vec<MultiPolynomial<FS::Set>> {
+        let polys = polys
+            .iter()
+            .map(|p| {
+                let mut terms = self.poly_to_terms(p, vars);
+                terms.sort_by(|(a, _), (b, _)| ordering.cmp(b, a));
+                terms
+            })
+            .collect();
+        self.groebner_basis_terms(ordering, polys)
+            .iter()
+            .map(|g| self.terms_to_poly(g, vars))
+            .collect()
+    }
+
+    /// The remainder of p on division by a Groebner basis with respect to a monomial ordering on vars. It is zero if and only if p is in the ideal.
+    pub fn groebner_normal_form(
+        &self,
+        p: &MultiPolynomial<FS::Set>,
+        basis: &[MultiPolynomial<FS::Set>],
+        vars: &[Variable],
+        ordering: MonomialOrdering,
+    ) -> MultiPolynomial<FS::Set> {
+        let to_terms = |p| {
+            let mut terms = self.poly_to_terms(p, vars);
+            terms.sort_by(|(a, _), (b, _)| ordering.cmp(b, a));
+            terms
+        };
+        let basis = basis.iter().map(to_terms).collect::<Vec<_>>();
+        self.terms_to_poly(&self.normal_form(ordering, to_terms(p), &basis), vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algebraeon_sets::structure::*;
+    use malachite_q::Rational;
+
+    #[test]
+    fn test_groebner_basis() {
+        let ring = MultiPolynomial::<Rational>::structure();
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let vars = vec![x.clone(), y.clone()];
+        let xp = ring.var(x.clone());
+        let yp = ring.var(y.clone());
+        let c = |n: i64| MultiPolynomial::constant(Rational::from(n));
+
+        //x^2 + y^2 - 1 and x - y
+        let f = ring.sum(vec![&ring.mul(&xp, &xp), &ring.mul(&yp, &yp), &c(-1)]);
+        let g = ring.add(&xp, &ring.neg(&yp));
+        let gb = ring.groebner_basis(
+            &[f.clone(), g.clone()],
+            &vars,
+            MonomialOrdering::Lexicographic,
+        );
+        //lexicographic with x > y eliminates x: {2y^2 - 1, x - y} up to scaling
+        assert_eq!(gb.len(), 2);
+        assert_eq!(
+            gb[0],
+            ring.add(
+                &ring.mul(&yp, &yp),
+                &MultiPolynomial::constant(Rational::from_signeds(-1, 2))
+            )
+        );
+        assert_eq!(gb[1], g);
+        for ordering in [
+            MonomialOrdering::Lexicographic,
+            MonomialOrdering::GradedReverseLexicographic,
+        ] {
+            let gb = ring.groebner_basis(&[f.clone(), g.clone()], &vars, ordering);
+            assert_eq!(
+                ring.groebner_normal_form(&f, &gb, &vars, ordering),
+                ring.zero()
+            );
+            assert_eq!(
+                ring.groebner_normal_form(&ring.mul(&f, &xp), &gb, &vars, ordering),
+                ring.zero()
+            );
+            assert_ne!(
+                ring.groebner_normal_form(&xp, &gb, &vars, ordering),
+                ring.zero()
+            );
+        }
+
+        //an inconsistent system generates the unit ideal
+        let gb = ring.groebner_basis(
+            &[ring.add(&xp, &c(-1)), ring.add(&xp, &c(-2))],
+            &vars,
+            MonomialOrdering::Lexicographic,
+        );
+        assert_eq!(gb, vec![c(1)]);
+
+        //the zero ideal
+        assert_eq!(
+            ring.groebner_basis(&[c(0)], &vars, MonomialOrdering::Lexicographic),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_groebner_basis_cyclic() {
+        //the cyclic 3 system
+        let ring = MultiPolynomial::<Rational>::structure();
+        let x = Variable::new("x");
+        let y = Variable::new("y");
+        let z = Variable::new("z");
+        let vars = vec![x.clone(), y.clone(), z.clone()];
+        let xp = ring.var(x.clone());
+        let yp = ring.var(y.clone());
+        let zp = ring.var(z.clone());
+        let polys = vec![
+            ring.sum(vec![&xp, &yp, &zp]),
+            ring.sum(vec![
+                &ring.mul(&xp, &yp),
+                &ring.mul(&yp, &zp),
+                &ring.mul(&zp, &xp),
+            ]),
+            ring.add(
+                &ring.product(vec![&xp, &yp, &zp]),
+                &MultiPolynomial::constant(Rational::from(-1)),
+            ),
+        ];
+        let gb = ring.groebner_basis(&polys, &vars, MonomialOrdering::Lexicographic);
+        //z^3 - 1, y^2 + yz + z^2, x + y + z
+        assert_eq!(gb.len(), 3);
+        assert_eq!(
+            gb[0],
+            ring.add(
+                &ring.var_pow(z.clone(), 3),
+                &MultiPolynomial::constant(Rational::from(-1))
+            )
+        );
+        assert_eq!(gb[2], polys[0]);
+        for p in &polys {
+            assert_eq!(
+                ring.groebner_normal_form(p, &gb, &vars, MonomialOrdering::Lexicographic),
+                ring.zero()
+            );
+        }
+    }
+}
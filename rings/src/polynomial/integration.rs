@@ -0,0 +1,335 @@
+use std::rc::Rc;
+
+use malachite_nz::integer::Integer;
+use malachite_q::Rational;
+
+use super::polynomial::*;
+use super::rational_function::*;
+use crate::number::algebraic::complex::ComplexAlgebraic;
+use crate::number::anf::number_field::{new_anf, ANFStructure};
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+/// The sum of c log(v(c, x)) over the roots c of an irreducible polynomial, where v has coefficients in the field Q\[c\].
+/// The field is given with c as its generator and the argument is v as a polynomial in x over it.
+#[derive(Debug, Clone)]
+pub struct LogarithmicTerm {
+    pub field: Rc<ANFStructure>,
+    pub argument: Polynomial<Polynomial<Rational>>,
+}
+
+impl LogarithmicTerm {
+    /// The monic irreducible polynomial over Q whose roots are the coefficients c of the logarithms.
+    pub fn residue_polynomial(&self) -> Polynomial<Rational> {
+        self.field.modulus().clone()
+    }
+
+    /// The pairs (c, v(c, x)) for each complex root c of the residue polynomial.
+    pub fn complex_terms(&self) -> Vec<(ComplexAlgebraic, Polynomial<ComplexAlgebraic>)> {
+        self.field
+            .all_complex_embeddings()
+            .into_iter()
+            .map(|embedding| {
+                (
+                    embedding.generator().clone(),
+                    self.argument.apply_map(|a| embedding.embed(a)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// An antiderivative of a rational function as a rational function plus a sum of logarithmic terms.
+#[derive(Debug, Clone)]
+pub struct RationalIntegral {
+    pub rational_part: RationalFunction<Rational>,
+    pub logarithmic_part: Vec<LogarithmicTerm>,
+}
+
+impl<FS: FieldStructure + CharZeroStructure> RationalFunctionStructure<FS> {
+    /// Hermite reduction of f: rational functions g and h with f = g' + h, such that the denominator of h is squarefree and of greater degree than its numerator.
+    pub fn hermite_reduce(
+        &self,
+        f: &RationalFunction<FS::Set>,
+    ) -> (RationalFunction<FS::Set>, RationalFunction<FS::Set>) {
+        let poly_ring = self.poly_ring();
+        let field = self.coeff_field();
+        let (s, mut a) = poly_ring
+            .quorem(&self.numerator(f), &self.denominator(f))
+            .unwrap();
+        //the polynomial part is integrated termwise
+        let mut g = self.from_base_ring(poly_ring.mul_var_pow(
+            &s.apply_map_with_powers(|(k, c)| {
+                field
+                    .div(c, &field.from_int(&Integer::from(k + 1)))
+                    .unwrap()
+            }),
+            1,
+        ));
+
+        //the squarefree decomposition d = d_1 d_2^2 ... d_m^m of the denominator by Yun's algorithm
+        let mut d = self.denominator(f);
+        let mut squarefree_factors = vec![];
+        let d_prime = poly_ring.derivative(d.clone());
+        let d_gcd = poly_ring.gcd(&d, &d_prime);
+        let mut b = poly_ring.div(&d, &d_gcd).unwrap();
+        let mut c = poly_ring.div(&d_prime, &d_gcd).unwrap();
+        while poly_ring.degree(&b).unwrap() != 0 {
+            //d_i = gcd(b, c - b') where b = d_i d_(i+1) ... d_m
+            let e = poly_ring.add(&c, &poly_ring.neg(&poly_ring.derivative(b.clone())));
+            let d_i = poly_ring.gcd(&b, &e);
+            b = poly_ring.div(&b, &d_i).unwrap();
+            c = poly_ring.div(&e, &d_i).unwrap();
+            squarefree_factors.push(d_i);
+        }
+
+        //reduce the power of each d_i one step at a time
+        for (i, v) in squarefree_factors.iter().enumerate().skip(1) {
+            if poly_ring.degree(v) == Some(0) {
+                continue;
+            }
+            let i = i + 1;
+            let u = poly_ring.div(&d, &poly_ring.nat_pow(v, &i.into())).unwrap();
+            let uv = poly_ring.mul(&u, &poly_ring.derivative(v.clone()));
+            //u v' is coprime to v since v is squarefree and coprime to u
+            let (unit, s, _t) = poly_ring.xgcd(&uv, v);
+            for j in (1..i).rev() {
+                //solve b u v' + c v = -a / j with deg(b) < deg(v), then a / (u v^(j+1)) = (b / v^j)' + (-j c - u b') / (u v^j)
+                let j_inv = field.inv(&field.from_int(&Integer::from(j))).unwrap();
+                let rhs = poly_ring.mul_scalar(&poly_ring.neg(&a), &j_inv);
+                let b = poly_ring.rem(&poly_ring.div(&poly_ring.mul(&rhs, &s), &unit).unwrap(), v);
+                let c = poly_ring
+                    .div(
+                        &poly_ring.add(&rhs, &poly_ring.neg(&poly_ring.mul(&b, &uv))),
+                        v,
+                    )
+                    .unwrap();
+                g = self.add(
+                    &g,
+                    &self
+                        .from_fraction(b.clone(), poly_ring.nat_pow(v, &j.into()))
+                        .unwrap(),
+                );
+                a = poly_ring.add(
+                    &poly_ring.neg(&poly_ring.mul_scalar(&c, &field.from_int(&Integer::from(j)))),
+                    &poly_ring.neg(&poly_ring.mul(&u, &poly_ring.derivative(b))),
+                );
+            }
+            d = poly_ring.mul(&u, v);
+        }
+        (g, self.from_fraction(a, d).unwrap())
+    }
+}
+
+impl RationalFunctionStructure<CannonicalStructure<Rational>> {
+    /// An antiderivative of f.
+    /// Hermite reduction gives the rational part and leaves a / d with d squarefree. The coefficients of the logarithms are the roots c of
+    /// the Rothstein–Trager resultant res_x(d, a - z d'), and for each irreducible factor of it the argument is gcd(d, a - c d') over Q\[c\],
+    /// which is the Lazard–Rioboo–Trager subresultant reduced modulo that factor. The coefficients therefore lie in the smallest possible fields.
+    pub fn integrate(&self, f: &RationalFunction<Rational>) -> RationalIntegral {
+        let (rational_part, h) = self.hermite_reduce(f);
+        let a = self.numerator(&h);
+        let d = self.denominator(&h);
+        let mut logarithmic_part = vec![];
+        if !a.is_zero() {
+            let constant =
+                |p: &Polynomial<Rational>| p.apply_map(|c| Polynomial::constant(c.clone()));
+            let d_prime = d.clone().derivative();
+            let resultant = Polynomial::resultant(
+                &constant(&d),
+                &Polynomial::add(
+                    &constant(&a),
+                    &d_prime.apply_map(|c| Polynomial::from_coeffs(vec![Rational::from(0), -c])),
+                ),
+            );
+            for (r, _k) in resultant.factor().unwrap().into_factors() {
+                if r.degree() == Some(0) {
+                    continue;
+                }
+                let r_lc = r.leading_coeff().unwrap();
+                let field = Rc::new(new_anf(r.apply_map(|c| c / &r_lc)));
+                let field_poly_ring = PolynomialStructure::new(field.clone());
+                let embed = |p: &Polynomial<Rational>| {
+                    p.apply_map(|c| field.reduce(&Polynomial::constant(c.clone())))
+                };
+                let c = field.reduce(&Polynomial::var());
+                let argument = field_poly_ring.gcd_by_modular_algorithm(
+                    &embed(&d),
+                    &field_poly_ring.add(
+                        &embed(&a),
+                        &field_poly_ring.neg(&field_poly_ring.mul_scalar(&embed(&d_prime), &c)),
+                    ),
+                );
+                logarithmic_part.push(LogarithmicTerm { field, argument });
+            }
+        }
+        RationalIntegral {
+            rational_part,
+            logarithmic_part,
+        }
+    }
+}
+
+impl<F: MetaType> RationalFunction<F>
+where
+    F::Structure: FieldStructure + CharZeroStructure,
+{
+    pub fn hermite_reduce(&self) -> (Self, Self) {
+        Self::structure().hermite_reduce(self)
+    }
+}
+
+impl RationalFunction<Rational> {
+    pub fn integrate(&self) -> RationalIntegral {
+        Self::structure().integrate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::elements::*;
+
+    fn rat(n: i64, d: i64) -> Rational {
+        Rational::from_integers(Integer::from(n), Integer::from(d))
+    }
+
+    //check that f - (rational part)' = a / d is accounted for by the logarithmic terms:
+    //the norms of their arguments multiply to d, and at each root of an argument the residue is its coefficient
+    fn check_integral(f: &RationalFunction<Rational>) -> RationalIntegral {
+        let integral = f.integrate();
+        let h = RationalFunction::add(
+            f,
+            &RationalFunction::neg(&integral.rational_part.derivative()),
+        );
+        let (a, d) = (h.numerator(), h.denominator());
+        assert!(d.is_squarefree());
+        if a.is_zero() {
+            assert!(integral.logarithmic_part.is_empty());
+            return integral;
+        }
+        assert!(a.degree().unwrap() < d.degree().unwrap());
+        let mut norm_product = Polynomial::<Rational>::one();
+        for term in &integral.logarithmic_part {
+            let field = &term.field;
+            let field_poly_ring = PolynomialStructure::new(field.clone());
+            let embed = |p: &Polynomial<Rational>| {
+                p.apply_map(|c| field.reduce(&Polynomial::constant(c.clone())))
+            };
+            let c = field.reduce(&Polynomial::var());
+            let a_minus_cd = field_poly_ring.add(
+                &embed(&a),
+                &field_poly_ring
+                    .neg(&field_poly_ring.mul_scalar(&embed(&d.clone().derivative()), &c)),
+            );
+            assert!(field_poly_ring.div(&a_minus_cd, &term.argument).is_ok());
+            norm_product = Polynomial::mul(
+                &norm_product,
+                &field_poly_ring.polynomial_norm(&term.argument),
+            );
+        }
+        assert_eq!(norm_product, d);
+        integral
+    }
+
+    fn frac(n: Vec<i64>, d: Vec<i64>) -> RationalFunction<Rational> {
+        RationalFunction::from_fraction(
+            Polynomial::from_coeffs(n.into_iter().map(Rational::from).collect()),
+            Polynomial::from_coeffs(d.into_iter().map(Rational::from).collect()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hermite_reduce() {
+        //2x / (x^2 + 1)^2 = (-1 / (x^2 + 1))'
+        let f = frac(vec![0, 2], vec![1, 0, 2, 0, 1]);
+        let integral = check_integral(&f);
+        assert_eq!(integral.rational_part, frac(vec![-1], vec![1, 0, 1]));
+        assert!(integral.logarithmic_part.is_empty());
+
+        //3x^2 + 1 / x^2 has antiderivative x^3 - 1/x
+        let f = frac(vec![1, 0, 0, 0, 3], vec![0, 0, 1]);
+        let integral = check_integral(&f);
+        assert_eq!(
+            integral.rational_part,
+            frac(vec![-1, 0, 0, 0, 1], vec![0, 1])
+        );
+
+        //1 / (x^3 (x - 1)^2) leaves logarithms
+        let f = frac(vec![1], vec![0, 0, 0, 1, -2, 1]);
+        let (g, h) = f.hermite_reduce();
+        assert_eq!(RationalFunction::add(&g.derivative(), &h), f);
+        assert!(h.denominator().is_squarefree());
+        check_integral(&f);
+    }
+
+    #[test]
+    fn test_integrate_logarithms() {
+        //1 / (x^3 - x) = -1/x + (1/2) / (x - 1) + (1/2) / (x + 1), so the logarithms of x^2 - 1 are grouped
+        let f = frac(vec![1], vec![0, -1, 0, 1]);
+        let integral = check_integral(&f);
+        assert_eq!(integral.logarithmic_part.len(), 2);
+        for term in &integral.logarithmic_part {
+            assert_eq!(term.field.degree(), 1);
+            let c = -term.residue_polynomial().coeff(0);
+            let v = term.argument.apply_map(|a| a.coeff(0));
+            if c < 0 {
+                assert_eq!(c, rat(-1, 1));
+                assert_eq!(v, Polynomial::from_coeffs(vec![rat(0, 1), rat(1, 1)]));
+            } else {
+                assert_eq!(c, rat(1, 2));
+                assert_eq!(
+                    v,
+                    Polynomial::from_coeffs(vec![rat(-1, 1), rat(0, 1), rat(1, 1)])
+                );
+            }
+        }
+
+        //1 / (x^2 - 2) = sum over c^2 = 1/8 of c log(x - 4c)
+        let f = frac(vec![1], vec![-2, 0, 1]);
+        let integral = check_integral(&f);
+        assert_eq!(integral.logarithmic_part.len(), 1);
+        let term = &integral.logarithmic_part[0];
+        assert_eq!(
+            term.residue_polynomial(),
+            Polynomial::from_coeffs(vec![rat(-1, 8), rat(0, 1), rat(1, 1)])
+        );
+        let complex_terms = term.complex_terms();
+        assert_eq!(complex_terms.len(), 2);
+        for (c, v) in complex_terms {
+            assert_eq!(
+                v,
+                Polynomial::from_coeffs(vec![
+                    ComplexAlgebraic::neg(&ComplexAlgebraic::mul(
+                        &c,
+                        &ComplexAlgebraic::from_int(&Integer::from(4))
+                    )),
+                    ComplexAlgebraic::one()
+                ])
+            );
+        }
+
+        //the arctangent 1 / (x^2 + 1) = sum over c^2 = -1/4 of c log(x + 2c)
+        let f = frac(vec![1], vec![1, 0, 1]);
+        let integral = check_integral(&f);
+        let term = &integral.logarithmic_part[0];
+        assert_eq!(
+            term.residue_polynomial(),
+            Polynomial::from_coeffs(vec![rat(1, 4), rat(0, 1), rat(1, 1)])
+        );
+
+        //a mix of rational and logarithmic parts with a residue field of degree 3
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+        let f = RationalFunction::from_fraction(
+            (x.pow(4) + 1).into_verbose(),
+            ((x.pow(3) - x - 1) * x.pow(2)).into_verbose(),
+        )
+        .unwrap();
+        let integral = check_integral(&f);
+        assert!(integral
+            .logarithmic_part
+            .iter()
+            .any(|term| term.field.degree() == 3));
+    }
+}
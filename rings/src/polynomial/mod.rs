@@ -1,6 +1,17 @@
 pub mod polynomial;
+pub mod multiplication;
+pub mod fast_division;
+pub mod multipoint;
+pub mod decomposition;
+pub mod power_series;
+pub mod newton_polygon;
 pub mod multipoly;
 pub mod symmetric;
 pub mod factoring;
 pub mod hensel_lifting;
-pub mod quotient;
\ No newline at end of file
+pub mod quotient;
+pub mod rational_function;
+pub mod integration;
+pub mod recurrence;
+pub mod groebner;
+pub mod resultants;
\ No newline at end of file
@@ -0,0 +1,508 @@
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+use malachite_base::num::arithmetic::traits::Lcm;
+use malachite_base::num::basic::traits::One;
+use malachite_base::num::logic::traits::SignificantBits;
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+
+//below these lengths of the shorter factor the simpler algorithm is used
+const KARATSUBA_THRESHOLD: usize = 16;
+const TOOM3_THRESHOLD: usize = 48;
+pub(crate) const NTT_THRESHOLD: usize = 24;
+
+//primes p = c * 2^40 + 1 with 2^61 < p < 2^62, each with a generator of the multiplicative group mod p
+const NTT_PRIMES: [(u64, u64); 64] = [
+    (4611615649683210241, 11),
+    (4611613450659954689, 3),
+    (4611549678985543681, 19),
+    (4611546380450660353, 5),
+    (4611524390218104833, 3),
+    (4611496902427410433, 5),
+    (4611480409752993793, 10),
+    (4611468315125088257, 3),
+    (4611467215613460481, 13),
+    (4611458419520438273, 3),
+    (4611454021473927169, 7),
+    (4611368259566960641, 11),
+    (4611359463473938433, 3),
+    (4611355065427427329, 21),
+    (4611277000101855233, 3),
+    (4611266004985577473, 5),
+    (4611253910357671937, 3),
+    (4611239616706510849, 7),
+    (4611200034287910913, 13),
+    (4611170347473960961, 13),
+    (4611154954311172097, 3),
+    (4611127466520477697, 5),
+    (4611115371892572161, 7),
+    (4611105476287922177, 3),
+    (4611084585566994433, 7),
+    (4611041704613511169, 11),
+    (4610999923171655681, 3),
+    (4610990027567005697, 3),
+    (4610988928055377921, 19),
+    (4610975733915844609, 22),
+    (4610962539776311297, 5),
+    (4610953743683289089, 3),
+    (4610939450032128001, 7),
+    (4610929554427478017, 5),
+    (4610874578846089217, 3),
+    (4610860285194928129, 11),
+    (4610815205218189313, 3),
+    (4610775622799589377, 3),
+    (4610758030613544961, 11),
+    (4610703055032156161, 3),
+    (4610695358450761729, 11),
+    (4610656875543789569, 3),
+    (4610620591660072961, 3),
+    (4610606298008911873, 10),
+    (4610577710706589697, 3),
+    (4610557919497289729, 3),
+    (4610541426822873089, 3),
+    (4610538128287989761, 3),
+    (4610534829753106433, 3),
+    (4610528232683339777, 3),
+    (4610510640497295361, 31),
+    (4610490849287995393, 10),
+    (4610472157590323201, 3),
+    (4610461162474045441, 7),
+    (4610439172241489921, 3),
+    (4610414982985678849, 13),
+    (4610389694218240001, 3),
+    (4610358907892662273, 10),
+    (4610342415218245633, 10),
+    (4610336917660106753, 3),
+    (4610290738171740161, 12),
+    (4610274245497323521, 3),
+    (4610261051357790209, 3),
+    (4610254454288023553, 5),
+];
+
+/// The coefficients of the product of the polynomials with coefficients a and b by the quadratic time algorithm.
+pub fn schoolbook_mul<RS: SemiRingStructure>(
+    ring: &RS,
+    a: &[RS::Set],
+    b: &[RS::Set],
+) -> Vec<RS::Set> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut coeffs = (0..a.len() + b.len() - 1)
+        .map(|_| ring.zero())
+        .collect::<Vec<_>>();
+    for i in 0..a.len() {
+        for j in 0..b.len() {
+            ring.add_mut(&mut coeffs[i + j], &ring.mul(&a[i], &b[j]));
+        }
+    }
+    coeffs
+}
+
+//add x * t^offset to acc, extending acc if necessary
+fn add_shifted<RS: SemiRingStructure>(
+    ring: &RS,
+    acc: &mut Vec<RS::Set>,
+    x: &[RS::Set],
+    offset: usize,
+) {
+    while acc.len() < offset + x.len() {
+        acc.push(ring.zero());
+    }
+    for (i, c) in x.iter().enumerate() {
+        ring.add_mut(&mut acc[offset + i], c);
+    }
+}
+
+fn add_vec<RS: SemiRingStructure>(ring: &RS, a: &[RS::Set], b: &[RS::Set]) -> Vec<RS::Set> {
+    let mut sum = a.to_vec();
+    add_shifted(ring, &mut sum, b, 0);
+    sum
+}
+
+fn sub_vec<RS: RingStructure>(ring: &RS, a: &[RS::Set], b: &[RS::Set]) -> Vec<RS::Set> {
+    let neg_b = b.iter().map(|c| ring.neg(c)).collect::<Vec<_>>();
+    add_vec(ring, a, &neg_b)
+}
+
+fn scale_vec<RS: SemiRingStructure>(ring: &RS, a: &[RS::Set], s: &RS::Set) -> Vec<RS::Set> {
+    a.iter().map(|c| ring.mul(c, s)).collect()
+}
+
+//the product when a is at least twice as long as b, by cutting a into pieces the length of b
+fn unbalanced_mul<RS: SemiRingStructure>(
+    ring: &RS,
+    a: &[RS::Set],
+    b: &[RS::Set],
+    mul: impl Fn(&RS, &[RS::Set], &[RS::Set]) -> Vec<RS::Set>,
+) -> Vec<RS::Set> {
+    let mut coeffs = vec![];
+    for (i, chunk) in a.chunks(b.len()).enumerate() {
+        add_shifted(ring, &mut coeffs, &mul(ring, chunk, b), i * b.len());
+    }
+    coeffs
+}
+
+/// The coefficients of the product of the polynomials with coefficients a and b by Karatsuba's algorithm.
+pub fn karatsuba_mul<RS: RingStructure>(ring: &RS, a: &[RS::Set], b: &[RS::Set]) -> Vec<RS::Set> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.len() < KARATSUBA_THRESHOLD {
+        return schoolbook_mul(ring, a, b);
+    }
+    if a.len() >= 2 * b.len() {
+        return unbalanced_mul(ring, a, b, karatsuba_mul);
+    }
+    //a = a0 + a1 t^h and b = b0 + b1 t^h
+    let h = a.len().div_ceil(2);
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(h.min(b.len()));
+    let z0 = karatsuba_mul(ring, a0, b0);
+    let z2 = karatsuba_mul(ring, a1, b1);
+    let z1 = karatsuba_mul(ring, &add_vec(ring, a0, a1), &add_vec(ring, b0, b1));
+    let z1 = sub_vec(ring, &sub_vec(ring, &z1, &z0), &z2);
+    let mut coeffs = z0;
+    add_shifted(ring, &mut coeffs, &z1, h);
+    add_shifted(ring, &mut coeffs, &z2, 2 * h);
+    coeffs.truncate(a.len() + b.len() - 1);
+    coeffs
+}
+
+/// The coefficients of the product of the polynomials with coefficients a and b by the Toom-Cook 3-way algorithm.
+pub fn toom3_mul<RS: IntegralDomainStructure + CharZeroStructure>(
+    ring: &RS,
+    a: &[RS::Set],
+    b: &[RS::Set],
+) -> Vec<RS::Set> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.len() < TOOM3_THRESHOLD {
+        return karatsuba_mul(ring, a, b);
+    }
+    if a.len() >= 2 * b.len() {
+        return unbalanced_mul(ring, a, b, toom3_mul);
+    }
+    let two = ring.from_nat(&Natural::from(2u8));
+    let three = ring.from_nat(&Natural::from(3u8));
+    let div_exact = |x: &[RS::Set], d: &RS::Set| -> Vec<RS::Set> {
+        x.iter().map(|c| ring.div(c, d).unwrap()).collect()
+    };
+
+    //a = a0 + a1 t^k + a2 t^2k and likewise for b, where the parts of b may be short or empty
+    let k = a.len().div_ceil(3);
+    let split = |x: &'_ [RS::Set]| -> [Vec<RS::Set>; 3] {
+        [0, 1, 2].map(|i| x[(i * k).min(x.len())..((i + 1) * k).min(x.len())].to_vec())
+    };
+    let evaluations = |[x0, x1, x2]: &[Vec<RS::Set>; 3]| -> [Vec<RS::Set>; 5] {
+        let x02 = add_vec(ring, x0, x2);
+        let xm1 = sub_vec(ring, &x02, x1);
+        //x0 - 2 x1 + 4 x2 = 2 (x(-1) + x2) - x0
+        let xm2 = sub_vec(ring, &scale_vec(ring, &add_vec(ring, &xm1, x2), &two), x0);
+        [x0.clone(), add_vec(ring, &x02, x1), xm1, xm2, x2.clone()]
+    };
+    let ea = evaluations(&split(a));
+    let eb = evaluations(&split(b));
+    let [r0, r1, rm1, rm2, rinf] = [0, 1, 2, 3, 4].map(|i| toom3_mul(ring, &ea[i], &eb[i]));
+
+    //interpolation sequence due to Bodrato
+    let r3 = div_exact(&sub_vec(ring, &rm2, &r1), &three);
+    let r1 = div_exact(&sub_vec(ring, &r1, &rm1), &two);
+    let r2 = sub_vec(ring, &rm1, &r0);
+    let r3 = add_vec(
+        ring,
+        &div_exact(&sub_vec(ring, &r2, &r3), &two),
+        &scale_vec(ring, &rinf, &two),
+    );
+    let r2 = sub_vec(ring, &add_vec(ring, &r2, &r1), &rinf);
+    let r1 = sub_vec(ring, &r1, &r3);
+
+    let mut coeffs = r0;
+    add_shifted(ring, &mut coeffs, &r1, k);
+    add_shifted(ring, &mut coeffs, &r2, 2 * k);
+    add_shifted(ring, &mut coeffs, &r3, 3 * k);
+    add_shifted(ring, &mut coeffs, &rinf, 4 * k);
+    debug_assert!(coeffs[a.len() + b.len() - 1..]
+        .iter()
+        .all(|c| ring.is_zero(c)));
+    coeffs.truncate(a.len() + b.len() - 1);
+    coeffs
+}
+
+fn mul_mod(a: u64, b: u64, p: u64) -> u64 {
+    ((a as u128 * b as u128) % p as u128) as u64
+}
+
+fn pow_mod(mut a: u64, mut e: u64, p: u64) -> u64 {
+    let mut r = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            r = mul_mod(r, a, p);
+        }
+        a = mul_mod(a, a, p);
+        e >>= 1;
+    }
+    r
+}
+
+fn residue(x: &Integer, p: u64) -> u64 {
+    let mut r = 0u64;
+    for limb in x.unsigned_abs_ref().limbs().rev() {
+        r = ((((r as u128) << 64) | limb as u128) % p as u128) as u64;
+    }
+    if *x < 0 && r != 0 {
+        p - r
+    } else {
+        r
+    }
+}
+
+//in place transform of values, whose length is a power of two, with respect to a root of unity of that order
+fn ntt(values: &mut [u64], root: u64, p: u64) {
+    let n = values.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let w = pow_mod(root, (n / len) as u64, p);
+        for start in (0..n).step_by(len) {
+            let mut wk = 1;
+            for k in start..start + len / 2 {
+                let u = values[k];
+                let v = mul_mod(values[k + len / 2], wk, p);
+                values[k] = if u + v >= p { u + v - p } else { u + v };
+                values[k + len / 2] = if u >= v { u - v } else { u + p - v };
+                wk = mul_mod(wk, w, p);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// The coefficients of the product of the integer polynomials with coefficients a and b by number theoretic transforms modulo several primes and the chinese remainder theorem.
+/// None if the coefficients of the product are too large for the available primes.
+pub fn ntt_mul(a: &[Integer], b: &[Integer]) -> Option<Vec<Integer>> {
+    if a.is_empty() || b.is_empty() {
+        return Some(vec![]);
+    }
+    let bits = |x: &[Integer]| {
+        x.iter()
+            .map(|c| c.unsigned_abs_ref().significant_bits())
+            .max()
+            .unwrap()
+    };
+    //the product coefficients are less than 2^bound / 2 in absolute value
+    let bound = bits(a) + bits(b) + (a.len().min(b.len()) as u64).significant_bits() + 1;
+    let num_primes = bound.div_ceil(61) as usize;
+    if num_primes > NTT_PRIMES.len() {
+        return None;
+    }
+    let primes = &NTT_PRIMES[..num_primes];
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+
+    let residues = primes
+        .iter()
+        .map(|&(p, g)| {
+            let root = pow_mod(g, (p - 1) / n as u64, p);
+            let transform = |x: &[Integer]| {
+                let mut values = x.iter().map(|c| residue(c, p)).collect::<Vec<_>>();
+                values.resize(n, 0);
+                ntt(&mut values, root, p);
+                values
+            };
+            let mut values = transform(a)
+                .into_iter()
+                .zip(transform(b))
+                .map(|(x, y)| mul_mod(x, y, p))
+                .collect::<Vec<_>>();
+            ntt(&mut values, pow_mod(root, p - 2, p), p);
+            let n_inv = pow_mod(n as u64, p - 2, p);
+            values.truncate(len);
+            values.into_iter().map(|x| mul_mod(x, n_inv, p)).collect()
+        })
+        .collect::<Vec<Vec<u64>>>();
+
+    //Garner's algorithm: find the mixed radix digits v_i with c = v_0 + p_0 (v_1 + p_1 (v_2 + ...))
+    let inverses = (0..num_primes)
+        .map(|i| {
+            let p = primes[i].0;
+            (0..i)
+                .map(|j| pow_mod(primes[j].0 % p, p - 2, p))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let modulus = primes
+        .iter()
+        .fold(Integer::ONE, |m, &(p, _)| m * Integer::from(p));
+    let half_modulus = &modulus >> 1;
+    Some(
+        (0..len)
+            .map(|k| {
+                let mut digits = Vec::with_capacity(num_primes);
+                for i in 0..num_primes {
+                    let p = primes[i].0;
+                    let mut x = residues[i][k];
+                    for (j, digit) in digits.iter().enumerate() {
+                        let d = digit % p;
+                        x = mul_mod(if x >= d { x - d } else { x + p - d }, inverses[i][j], p);
+                    }
+                    digits.push(x);
+                }
+                let mut c = Integer::from(digits[num_primes - 1]);
+                for i in (0..num_primes - 1).rev() {
+                    c = c * Integer::from(primes[i].0) + Integer::from(digits[i]);
+                }
+                if c > half_modulus {
+                    c - &modulus
+                } else {
+                    c
+                }
+            })
+            .collect(),
+    )
+}
+
+/// The coefficients of the product of integer polynomials, choosing an algorithm by their size.
+/// Toom-3 is only used when the coefficients are too large for the transforms.
+pub fn integer_polynomial_mul(a: &[Integer], b: &[Integer]) -> Vec<Integer> {
+    let ring = CannonicalStructure::<Integer>::new();
+    if a.len().min(b.len()) < NTT_THRESHOLD {
+        schoolbook_mul(&ring, a, b)
+    } else {
+        ntt_mul(a, b).unwrap_or_else(|| toom3_mul(&ring, a, b))
+    }
+}
+
+/// The coefficients of the product of rational polynomials, by clearing denominators and multiplying integer polynomials.
+/// Each operand is scaled by the lcm of its own denominators, so the integer coefficients are no larger than they need to be.
+pub fn rational_polynomial_mul(a: &[Rational], b: &[Rational]) -> Vec<Rational> {
+    if a.len().min(b.len()) < NTT_THRESHOLD {
+        return schoolbook_mul(&CannonicalStructure::<Rational>::new(), a, b);
+    }
+    //x = x_num / x_den for an integer polynomial x_num
+    let clear_denominators = |x: &[Rational]| {
+        let x_den = x
+            .iter()
+            .fold(Natural::ONE, |d, c| d.lcm(c.denominator_ref()));
+        let x_num = x
+            .iter()
+            .map(|c| {
+                Integer::from_sign_and_abs(
+                    *c >= 0,
+                    c.numerator_ref() * (&x_den / c.denominator_ref()),
+                )
+            })
+            .collect::<Vec<_>>();
+        (x_num, x_den)
+    };
+    let (a_num, a_den) = clear_denominators(a);
+    let (b_num, b_den) = clear_denominators(b);
+    let d = Integer::from(a_den * b_den);
+    integer_polynomial_mul(&a_num, &b_num)
+        .into_iter()
+        .map(|c| Rational::from_integers(c, d.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+
+    //a deterministic sequence of integers with the given number of bits
+    fn pseudorandom(len: usize, bits: u64, seed: u64) -> Vec<Integer> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                let mut x = Integer::from(0);
+                for _ in 0..bits.div_ceil(32) {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    x = (x << 32) + Integer::from(state >> 32);
+                }
+                x >>= bits.div_ceil(32) * 32 - bits;
+                if state & 1 == 0 {
+                    -x
+                } else {
+                    x
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_integer_algorithms_agree() {
+        let ring = CannonicalStructure::<Integer>::new();
+        for (la, lb, bits) in [
+            (1, 1, 10),
+            (17, 16, 5),
+            (50, 60, 100),
+            (100, 20, 64),
+            (130, 97, 3),
+            (200, 200, 300),
+            (40, 300, 1),
+        ] {
+            let a = pseudorandom(la, bits, la as u64);
+            let b = pseudorandom(lb, bits, lb as u64 + 1000);
+            let expected = schoolbook_mul(&ring, &a, &b);
+            assert_eq!(karatsuba_mul(&ring, &a, &b), expected);
+            assert_eq!(toom3_mul(&ring, &a, &b), expected);
+            assert_eq!(ntt_mul(&a, &b).unwrap(), expected);
+            assert_eq!(integer_polynomial_mul(&a, &b), expected);
+        }
+        assert_eq!(
+            ntt_mul(&[], &pseudorandom(3, 3, 0)).unwrap(),
+            Vec::<Integer>::new()
+        );
+        //too large for the available primes
+        let a = pseudorandom(2, 2500, 0);
+        assert!(ntt_mul(&a, &a).is_none());
+    }
+
+    #[test]
+    fn test_rational_and_modular() {
+        let a = pseudorandom(100, 20, 1)
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| Rational::from_integers(c, Integer::from(i as u64 + 1)))
+            .collect::<Vec<_>>();
+        let b = pseudorandom(120, 20, 2)
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| Rational::from_integers(c, Integer::from(3 * i as u64 + 2)))
+            .collect::<Vec<_>>();
+        let ring = CannonicalStructure::<Rational>::new();
+        assert_eq!(
+            rational_polynomial_mul(&a, &b),
+            schoolbook_mul(&ring, &a, &b)
+        );
+        //an integer operand is not scaled by the denominators of the other
+        let c = pseudorandom(100, 20, 3)
+            .into_iter()
+            .map(Rational::from)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rational_polynomial_mul(&c, &b),
+            schoolbook_mul(&ring, &c, &b)
+        );
+
+        let ring = CannonicalStructure::<Modulo<7>>::new();
+        let a = (0..150usize)
+            .map(|i| Modulo::<7>::from(i * i + 3))
+            .collect::<Vec<_>>();
+        let b = (0..130usize)
+            .map(|i| Modulo::<7>::from(2 * i + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(ring.polynomial_mul(&a, &b), schoolbook_mul(&ring, &a, &b));
+        assert_eq!(karatsuba_mul(&ring, &a, &b), schoolbook_mul(&ring, &a, &b));
+    }
+}
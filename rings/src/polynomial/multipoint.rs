@@ -0,0 +1,184 @@
+use super::polynomial::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+//below this many points evaluation and interpolation are done directly
+const SUBPRODUCT_TREE_THRESHOLD: usize = 32;
+
+impl<FS: FieldStructure> PolynomialStructure<FS> {
+    /// The subproduct tree of the points. The first level is the linear factors x - x_i, each following level
+    /// is the products of adjacent pairs in the level before, and the last level is the product of all of them.
+    pub fn subproduct_tree(&self, points: &[FS::Set]) -> Vec<Vec<Polynomial<FS::Set>>> {
+        let mut levels = vec![points
+            .iter()
+            .map(|x| {
+                Polynomial::from_coeffs(vec![self.coeff_ring().neg(x), self.coeff_ring().one()])
+            })
+            .collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [f, g] => self.mul(f, g),
+                    [f] => f.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+
+    //the values of p at the roots of the first level of a nonempty subproduct tree, by remainders down the tree
+    fn evaluate_by_subproduct_tree(
+        &self,
+        p: &Polynomial<FS::Set>,
+        tree: &[Vec<Polynomial<FS::Set>>],
+    ) -> Vec<FS::Set> {
+        let mut remainders = vec![p.clone()];
+        for level in tree.iter().rev() {
+            remainders = level
+                .iter()
+                .enumerate()
+                .map(|(i, m)| self.rem(&remainders[i / 2], m))
+                .collect();
+        }
+        remainders
+            .iter()
+            .map(|r| self.coeff(r, 0).clone())
+            .collect()
+    }
+
+    /// The values of p at each of the points.
+    pub fn multipoint_evaluate(&self, p: &Polynomial<FS::Set>, points: &[FS::Set]) -> Vec<FS::Set> {
+        if points.len() < SUBPRODUCT_TREE_THRESHOLD {
+            points.iter().map(|x| self.evaluate(p, x)).collect()
+        } else {
+            self.evaluate_by_subproduct_tree(p, &self.subproduct_tree(points))
+        }
+    }
+
+    /// The polynomial of least degree through the points (x_i, y_i), computed from the subproduct tree of the x_i.
+    /// None if the x_i are not distinct.
+    pub fn interpolate_by_subproduct_tree(
+        &self,
+        points: &[(FS::Set, FS::Set)],
+    ) -> Option<Polynomial<FS::Set>> {
+        if points.is_empty() {
+            return Some(self.zero());
+        }
+        let xs = points.iter().map(|(x, _y)| x.clone()).collect::<Vec<_>>();
+        let tree = self.subproduct_tree(&xs);
+        //the lagrange basis polynomial at x_i is m(x) / ((x - x_i) m'(x_i)) where m is the product of all x - x_j
+        let m = tree.last().unwrap()[0].clone();
+        let weights = self.evaluate_by_subproduct_tree(&self.derivative(m), &tree);
+        let mut combined = points
+            .iter()
+            .zip(weights)
+            .map(|((_x, y), w)| Some(Polynomial::constant(self.coeff_ring().div(y, &w).ok()?)))
+            .collect::<Option<Vec<_>>>()?;
+        for level in &tree[..tree.len() - 1] {
+            combined = combined
+                .chunks(2)
+                .zip(level.chunks(2))
+                .map(|(c, m)| match (c, m) {
+                    ([c0, c1], [m0, m1]) => self.add(&self.mul(c0, m1), &self.mul(c1, m0)),
+                    ([c0], [_m0]) => c0.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        Some(combined.pop().unwrap())
+    }
+
+    /// The polynomial of least degree through the points (x_i, y_i), where the x_i are distinct.
+    pub fn interpolate(&self, points: &[(FS::Set, FS::Set)]) -> Option<Polynomial<FS::Set>> {
+        if points.len() < SUBPRODUCT_TREE_THRESHOLD {
+            self.interpolate_by_lagrange_basis(&points.to_vec())
+        } else {
+            self.interpolate_by_subproduct_tree(points)
+        }
+    }
+}
+
+impl<F: MetaType> Polynomial<F>
+where
+    F::Structure: FieldStructure,
+{
+    pub fn multipoint_evaluate(&self, points: &[F]) -> Vec<F> {
+        Self::structure().multipoint_evaluate(self, points)
+    }
+
+    pub fn interpolate_by_subproduct_tree(points: &[(F, F)]) -> Option<Self> {
+        Self::structure().interpolate_by_subproduct_tree(points)
+    }
+
+    pub fn interpolate(points: &[(F, F)]) -> Option<Self> {
+        Self::structure().interpolate(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+    use malachite_q::Rational;
+
+    #[test]
+    fn test_multipoint_evaluate() {
+        let f = Polynomial::<Modulo<997>>::from_coeffs(
+            (0..200usize).map(|i| Modulo::from(i * i + 5)).collect(),
+        );
+        let points = (0..300usize).map(Modulo::from).collect::<Vec<_>>();
+        let values = f.multipoint_evaluate(&points);
+        for (x, y) in points.iter().zip(values) {
+            assert_eq!(f.evaluate(x), y);
+        }
+
+        let f = Polynomial::<Rational>::from_coeffs(vec![3, 0, -1, 2]);
+        let points = (-40..40).map(Rational::from).collect::<Vec<_>>();
+        let values = f.multipoint_evaluate(&points);
+        for (x, y) in points.iter().zip(values) {
+            assert_eq!(f.evaluate(x), y);
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let points = (0..100usize)
+            .map(|i| {
+                (
+                    Modulo::<997>::from(3 * i + 1),
+                    Modulo::<997>::from(i * i * i + 7),
+                )
+            })
+            .collect::<Vec<_>>();
+        let f = Polynomial::interpolate(&points).unwrap();
+        assert!(f.degree().unwrap() < 100);
+        for (x, y) in &points {
+            assert_eq!(&f.evaluate(x), y);
+        }
+
+        let points = (0..50)
+            .map(|i| (Rational::from(i), Rational::from(i * i - 2 * i + 5)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Polynomial::interpolate(&points).unwrap(),
+            Polynomial::<Rational>::from_coeffs(vec![5, -2, 1])
+        );
+        assert_eq!(
+            Polynomial::interpolate_by_subproduct_tree(&points[..5]).unwrap(),
+            Polynomial::<Rational>::from_coeffs(vec![5, -2, 1])
+        );
+
+        let mut points = points;
+        points.push((Rational::from(3), Rational::from(0)));
+        assert!(Polynomial::interpolate(&points).is_none());
+        assert_eq!(
+            Polynomial::<Rational>::interpolate_by_subproduct_tree(&[]),
+            Some(Polynomial::zero())
+        );
+    }
+}
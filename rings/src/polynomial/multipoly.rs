@@ -9,6 +9,7 @@ use std::sync::atomic::AtomicUsize;
 use malachite_nz::natural::Natural;
 
 use super::super::structure::structure::*;
+use super::multiplication::karatsuba_mul;
 use super::polynomial::*;
 use algebraeon_sets::structure::*;
 
@@ -490,6 +491,10 @@ impl<RS: RingStructure> SemiRingStructure for MultiPolynomialStructure<RS> {
                 .collect(),
         ))
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        karatsuba_mul(self, a, b)
+    }
 }
 
 impl<RS: RingStructure> RingStructure for MultiPolynomialStructure<RS> {
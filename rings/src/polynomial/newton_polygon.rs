@@ -0,0 +1,528 @@
+use std::rc::Rc;
+
+use malachite_base::num::arithmetic::traits::Lcm;
+use malachite_base::num::basic::traits::One;
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+
+use super::multipoly::*;
+use super::polynomial::*;
+use crate::number::algebraic::padic::valuation::{padic_rat_valuation, Valuation};
+use crate::number::anf::number_field::{new_anf, ANFStructure};
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+/// An edge of a lower Newton polygon from the point start to the point end, where start.0 < end.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewtonPolygonEdge {
+    pub start: (usize, Integer),
+    pub end: (usize, Integer),
+}
+
+impl NewtonPolygonEdge {
+    pub fn slope(&self) -> Rational {
+        Rational::from_integers(
+            &self.end.1 - &self.start.1,
+            Integer::from(self.end.0 - self.start.0),
+        )
+    }
+
+    pub fn length(&self) -> usize {
+        self.end.0 - self.start.0
+    }
+}
+
+/// The edges from left to right of the lower convex hull of the points (i, v_i) for which v_i is finite.
+/// If v_i is the valuation of the coefficient of x^i of a polynomial, an edge of slope -s and length l accounts for l of its roots of valuation s.
+pub fn newton_polygon(valuations: &[Valuation]) -> Vec<NewtonPolygonEdge> {
+    let mut hull: Vec<(usize, Integer)> = vec![];
+    for (i, v) in valuations.iter().enumerate() {
+        if let Valuation::Finite(v) = v {
+            while hull.len() >= 2 {
+                let (i0, v0) = &hull[hull.len() - 2];
+                let (i1, v1) = &hull[hull.len() - 1];
+                //drop the last point if it lies on or above the line from the point before it to (i, v)
+                if (v1 - v0) * Integer::from(i - i0) >= (v - v0) * Integer::from(i1 - i0) {
+                    hull.pop();
+                } else {
+                    break;
+                }
+            }
+            hull.push((i, v.clone()));
+        }
+    }
+    hull.windows(2)
+        .map(|w| NewtonPolygonEdge {
+            start: w[0].clone(),
+            end: w[1].clone(),
+        })
+        .collect()
+}
+
+//the power of the variable dividing a, which is infinite for a = 0
+fn order<RS: SemiRingStructure>(ring: &RS, a: &Polynomial<RS::Set>) -> Valuation {
+    match a.coeffs().into_iter().position(|c| !ring.is_zero(c)) {
+        Some(k) => Valuation::Finite(Integer::from(k)),
+        None => Valuation::Infinity,
+    }
+}
+
+impl Polynomial<Rational> {
+    /// The Newton polygon of the p-adic valuations of the coefficients.
+    pub fn padic_newton_polygon(&self, p: &Natural) -> Vec<NewtonPolygonEdge> {
+        newton_polygon(
+            &self
+                .coeffs()
+                .into_iter()
+                .map(|c| padic_rat_valuation(p, c.clone()))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// A root y of a bivariate polynomial as a Puiseux series c_1 x^e_1 + c_2 x^e_2 + ... with increasing rational exponents,
+/// truncated at some exponent, and with coefficients in a number field.
+/// It stands for itself and its conjugates, one for each embedding of the number field.
+#[derive(Debug, Clone)]
+pub struct PuiseuxExpansion {
+    pub field: Rc<ANFStructure>,
+    pub terms: Vec<(Rational, Polynomial<Rational>)>,
+    //the number of roots, counted with multiplicity, whose expansions begin with the terms
+    pub multiplicity: usize,
+    //whether the terms are the whole of the series
+    pub exact: bool,
+}
+
+impl PuiseuxExpansion {
+    /// The least common multiple of the denominators of the exponents.
+    pub fn ramification_index(&self) -> Natural {
+        self.terms
+            .iter()
+            .fold(Natural::ONE, |e, (k, _c)| e.lcm(k.denominator_ref()))
+    }
+}
+
+//the expansion so far is y = terms + x^offset Y where x = t^ramification
+#[derive(Debug, Clone)]
+struct PartialExpansion {
+    field: Rc<ANFStructure>,
+    terms: Vec<(Rational, Polynomial<Rational>)>,
+    offset: Rational,
+    ramification: usize,
+}
+
+impl PartialExpansion {
+    fn finish(&self, multiplicity: usize, exact: bool) -> PuiseuxExpansion {
+        PuiseuxExpansion {
+            field: self.field.clone(),
+            terms: self.terms.clone(),
+            multiplicity,
+            exact,
+        }
+    }
+}
+
+//g(t, y) as its list of coefficients in y, each a polynomial in t over a number field
+type Bivariate = Vec<Polynomial<Polynomial<Rational>>>;
+
+//g(t^q, t^p (c + y)) / t^shift
+fn substitute(
+    field: &Rc<ANFStructure>,
+    g: &Bivariate,
+    p: &Integer,
+    q: usize,
+    shift: &Integer,
+    c: &Polynomial<Rational>,
+) -> Bivariate {
+    let t_ring = PolynomialStructure::new(field.clone());
+    let n = g.len();
+    let mut coeffs = vec![vec![]; n];
+    //the coefficients of (c + y)^i
+    let mut power = vec![field.one()];
+    for (i, a) in g.iter().enumerate() {
+        for (j, a_j) in a.coeffs().into_iter().enumerate() {
+            if field.is_zero(a_j) {
+                continue;
+            }
+            let e =
+                usize::try_from(&(Integer::from(q * j) + p * Integer::from(i) - shift)).unwrap();
+            for (m, b) in power.iter().enumerate() {
+                let row: &mut Vec<Polynomial<Rational>> = &mut coeffs[m];
+                if row.len() <= e {
+                    row.resize(e + 1, field.zero());
+                }
+                row[e] = field.add(&row[e], &field.mul(a_j, b));
+            }
+        }
+        let mut next = vec![field.zero(); power.len() + 1];
+        for (m, b) in power.iter().enumerate() {
+            next[m] = field.add(&next[m], &field.mul(c, b));
+            next[m + 1] = field.add(&next[m + 1], b);
+        }
+        power = next;
+    }
+    coeffs
+        .into_iter()
+        .map(|row| t_ring.reduce_poly(Polynomial::from_coeffs(row)))
+        .collect()
+}
+
+//find the expansions of the r roots of g(t, Y) of positive valuation (or of any valuation if not positive_only)
+fn puiseux_step(
+    g: &Bivariate,
+    r: usize,
+    positive_only: bool,
+    partial: &PartialExpansion,
+    bound: &Rational,
+    expansions: &mut Vec<PuiseuxExpansion>,
+) {
+    let field = &partial.field;
+    let t_ring = PolynomialStructure::new(field.clone());
+    let valuations = g[..=r]
+        .iter()
+        .map(|a| order(field.as_ref(), a))
+        .collect::<Vec<_>>();
+    //Y = 0 is a root of g of multiplicity the order of g in Y
+    let zero_roots = valuations
+        .iter()
+        .position(|v| v != &Valuation::Infinity)
+        .unwrap();
+    if zero_roots > 0 {
+        expansions.push(partial.finish(zero_roots, true));
+    }
+    for edge in newton_polygon(&valuations) {
+        let gamma = -edge.slope();
+        if positive_only && gamma <= 0 {
+            continue;
+        }
+        let exponent = &partial.offset + &gamma / Rational::from(partial.ramification);
+        if &exponent >= bound {
+            expansions.push(partial.finish(edge.length(), false));
+            continue;
+        }
+        //the leading coefficients c of the roots Y = c t^gamma + ... are the nonzero roots of the edge polynomial
+        let p = if gamma < 0 {
+            -Integer::from(gamma.numerator_ref())
+        } else {
+            Integer::from(gamma.numerator_ref())
+        };
+        let q = usize::try_from(gamma.denominator_ref()).unwrap();
+        let (i0, v0) = &edge.start;
+        let shift = v0 * Integer::from(q) + &p * Integer::from(*i0);
+        let psi = Polynomial::from_coeffs(
+            (*i0..=edge.end.0)
+                .map(|i| match &valuations[i] {
+                    Valuation::Finite(v)
+                        if v * Integer::from(q) + &p * Integer::from(i) == shift =>
+                    {
+                        t_ring.coeff(&g[i], usize::try_from(v).unwrap()).clone()
+                    }
+                    _ => field.zero(),
+                })
+                .collect(),
+        );
+        for (h, mult) in t_ring.factor(&psi).unwrap().into_factors() {
+            let mult = usize::try_from(&mult).unwrap();
+            let (next_field, g, terms, c) = if t_ring.degree(&h) == Some(1) {
+                (
+                    field.clone(),
+                    g.clone(),
+                    partial.terms.clone(),
+                    field.neg(t_ring.coeff(&h, 0)),
+                )
+            } else {
+                let (l, alpha, c) = field.adjoin_root(&h);
+                let embed = |a: &Polynomial<Rational>| l.reduce(&Polynomial::compose(a, &alpha));
+                let g = g.iter().map(|a| a.apply_map(embed)).collect::<Vec<_>>();
+                let terms = partial
+                    .terms
+                    .iter()
+                    .map(|(k, a)| (k.clone(), embed(a)))
+                    .collect();
+                (Rc::new(l), g, terms, c)
+            };
+            let g = substitute(&next_field, &g, &p, q, &shift, &c);
+            let mut terms = terms;
+            terms.push((exponent.clone(), c));
+            let partial = PartialExpansion {
+                field: next_field,
+                terms,
+                offset: exponent.clone(),
+                ramification: partial.ramification * q,
+            };
+            puiseux_step(&g, mult, true, &partial, bound, expansions);
+        }
+    }
+}
+
+fn puiseux(
+    f: &Polynomial<Polynomial<Rational>>,
+    x0: &Rational,
+    y0: &Rational,
+    positive_only: bool,
+    bound: &Rational,
+) -> Vec<PuiseuxExpansion> {
+    assert!(!f.is_zero());
+    let x_shift = Polynomial::from_coeffs(vec![x0.clone(), Rational::ONE]);
+    let y_shift =
+        Polynomial::from_coeffs(vec![Polynomial::constant(y0.clone()), Polynomial::one()]);
+    let f = Polynomial::compose(f, &y_shift).apply_map(|a| Polynomial::compose(a, &x_shift));
+    let field = Rc::new(new_anf(Polynomial::var()));
+    let g = f
+        .coeffs()
+        .into_iter()
+        .map(|a| a.apply_map(|c| field.reduce(&Polynomial::constant(c.clone()))))
+        .collect::<Vec<_>>();
+    let partial = PartialExpansion {
+        field,
+        terms: vec![],
+        offset: Rational::from(0),
+        ramification: 1,
+    };
+    let mut expansions = vec![];
+    if let Some(n) = f.degree() {
+        if n > 0 {
+            puiseux_step(&g, n, positive_only, &partial, bound, &mut expansions);
+        }
+    }
+    expansions
+}
+
+impl Polynomial<Polynomial<Rational>> {
+    /// The Newton polygon of f = sum a_i(x) y^i, viewed as a polynomial in y over Q\[x\], for the x-adic valuations of the a_i.
+    pub fn newton_polygon(&self) -> Vec<NewtonPolygonEdge> {
+        let ring = Rational::structure();
+        newton_polygon(
+            &self
+                .coeffs()
+                .into_iter()
+                .map(|a| order(ring.as_ref(), a))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// The roots y of f(x, y) = sum a_i(x) y^i as Puiseux series in x - x0, keeping the terms with exponent less than bound.
+    pub fn puiseux_expansions(&self, x0: &Rational, bound: &Rational) -> Vec<PuiseuxExpansion> {
+        puiseux(self, x0, &Rational::from(0), false, bound)
+    }
+
+    /// The branches of the curve f(x, y) = 0 through (x0, y0) as Puiseux series for y - y0 in x - x0, keeping the terms with exponent less than bound.
+    pub fn puiseux_branches_at(
+        &self,
+        x0: &Rational,
+        y0: &Rational,
+        bound: &Rational,
+    ) -> Vec<PuiseuxExpansion> {
+        puiseux(self, x0, y0, true, bound)
+    }
+}
+
+impl MultiPolynomial<Rational> {
+    /// f as a polynomial in y with coefficients in Q\[x\], or None if f involves other variables.
+    pub fn as_bivariate(
+        &self,
+        x: &Variable,
+        y: &Variable,
+    ) -> Option<Polynomial<Polynomial<Rational>>> {
+        Some(Polynomial::from_coeffs(
+            self.expand(y)
+                .into_coeffs()
+                .into_iter()
+                .map(|a| {
+                    Some(Polynomial::from_coeffs(
+                        a.expand(x)
+                            .into_coeffs()
+                            .into_iter()
+                            .map(|c| c.as_constant())
+                            .collect::<Option<Vec<_>>>()?,
+                    ))
+                })
+                .collect::<Option<Vec<_>>>()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::elements::*;
+
+    fn fin(v: i64) -> Valuation {
+        Valuation::Finite(Integer::from(v))
+    }
+
+    fn edge(i0: usize, v0: i64, i1: usize, v1: i64) -> NewtonPolygonEdge {
+        NewtonPolygonEdge {
+            start: (i0, Integer::from(v0)),
+            end: (i1, Integer::from(v1)),
+        }
+    }
+
+    fn rat(n: i64, d: i64) -> Rational {
+        Rational::from_integers(Integer::from(n), Integer::from(d))
+    }
+
+    #[test]
+    fn test_newton_polygon() {
+        assert_eq!(
+            newton_polygon(&[fin(3), fin(1), fin(2), Valuation::Infinity, fin(0), fin(0)]),
+            vec![edge(0, 3, 1, 1), edge(1, 1, 4, 0), edge(4, 0, 5, 0)]
+        );
+        //collinear points give a single edge
+        assert_eq!(
+            newton_polygon(&[fin(2), fin(1), fin(0)]),
+            vec![edge(0, 2, 2, 0)]
+        );
+        assert_eq!(newton_polygon(&[fin(5)]), vec![]);
+
+        //8 + 2x + x^2/3 + 4x^3 over Q_2
+        let f = Polynomial::from_coeffs(vec![rat(8, 1), rat(2, 1), rat(1, 3), rat(4, 1)]);
+        let np = f.padic_newton_polygon(&Natural::from(2u32));
+        assert_eq!(
+            np,
+            vec![edge(0, 3, 1, 1), edge(1, 1, 2, 0), edge(2, 0, 3, 2)]
+        );
+        assert_eq!(np[0].slope(), rat(-2, 1));
+        assert_eq!(np[2].length(), 1);
+    }
+
+    fn bivariate(coeffs: Vec<Vec<i64>>) -> Polynomial<Polynomial<Rational>> {
+        Polynomial::from_coeffs(
+            coeffs
+                .into_iter()
+                .map(|a| Polynomial::from_coeffs(a.into_iter().map(Rational::from).collect()))
+                .collect(),
+        )
+    }
+
+    //each expansion stands for as many roots as the degree of its field times its multiplicity
+    fn count_roots(expansions: &[PuiseuxExpansion]) -> usize {
+        expansions
+            .iter()
+            .map(|e| e.field.degree() * e.multiplicity)
+            .sum()
+    }
+
+    #[test]
+    fn test_puiseux_cusp() {
+        //y^2 = x^3 has the roots y = ±x^(3/2)
+        let f = bivariate(vec![vec![0, 0, 0, -1], vec![], vec![1]]);
+        assert_eq!(f.newton_polygon(), vec![edge(0, 3, 2, 0)]);
+        let expansions = f.puiseux_expansions(&Rational::from(0), &Rational::from(10));
+        assert_eq!(count_roots(&expansions), 2);
+        let mut leading = vec![];
+        for e in &expansions {
+            assert!(e.exact);
+            assert_eq!(e.terms.len(), 1);
+            assert_eq!(e.terms[0].0, rat(3, 2));
+            assert_eq!(e.ramification_index(), Natural::from(2u32));
+            leading.push(e.terms[0].1.coeff(0));
+        }
+        leading.sort();
+        assert_eq!(leading, vec![Rational::from(-1), Rational::from(1)]);
+
+        //x y = 1 has the root y = x^(-1)
+        let f = bivariate(vec![vec![-1], vec![0, 1]]);
+        let expansions = f.puiseux_expansions(&Rational::from(0), &Rational::from(10));
+        assert_eq!(expansions.len(), 1);
+        assert!(expansions[0].exact);
+        assert_eq!(
+            expansions[0].terms,
+            vec![(Rational::from(-1), Polynomial::one())]
+        );
+    }
+
+    #[test]
+    fn test_puiseux_algebraic_coefficients() {
+        //y^2 = 2x^2 + x^3 has the roots y = ±sqrt(2) x sqrt(1 + x/2) = ±sqrt(2) (x + x^2/4 - x^3/32 + ...)
+        let f = bivariate(vec![vec![0, 0, -2, -1], vec![], vec![1]]);
+        let expansions = f.puiseux_expansions(&Rational::from(0), &Rational::from(4));
+        assert_eq!(expansions.len(), 1);
+        let e = &expansions[0];
+        assert_eq!(e.field.degree(), 2);
+        assert!(!e.exact);
+        let exponents = e.terms.iter().map(|(k, _c)| k.clone()).collect::<Vec<_>>();
+        assert_eq!(exponents, vec![rat(1, 1), rat(2, 1), rat(3, 1)]);
+        let anf = &e.field;
+        let c1 = &e.terms[0].1;
+        assert!(anf.equal(&anf.mul(c1, c1), &Polynomial::constant(Rational::from(2))));
+        assert!(anf.equal(
+            &e.terms[1].1,
+            &anf.mul(c1, &Polynomial::constant(rat(1, 4)))
+        ));
+        assert!(anf.equal(
+            &e.terms[2].1,
+            &anf.mul(c1, &Polynomial::constant(rat(-1, 32)))
+        ));
+        assert_eq!(count_roots(&expansions), 2);
+
+        //y^3 - x y + x^3 has a branch y = x^2 + ... and two branches y = ±x^(1/2) + ...
+        let f = bivariate(vec![vec![0, 0, 0, 1], vec![0, -1], vec![], vec![1]]);
+        let expansions = f.puiseux_expansions(&Rational::from(0), &Rational::from(3));
+        assert_eq!(count_roots(&expansions), 3);
+        assert!(expansions
+            .iter()
+            .any(|e| e.terms.first().map(|(k, _c)| k.clone()) == Some(Rational::from(2))));
+
+        //y^4 = 2 x^2 has roots of valuation 1/2 with leading coefficients the fourth roots of 2
+        let f = bivariate(vec![vec![0, 0, -2], vec![], vec![], vec![], vec![1]]);
+        let expansions = f.puiseux_expansions(&Rational::from(0), &Rational::from(5));
+        assert_eq!(count_roots(&expansions), 4);
+        for e in &expansions {
+            let anf = &e.field;
+            let c = &e.terms[0].1;
+            assert_eq!(e.terms[0].0, rat(1, 2));
+            assert!(anf.equal(
+                &anf.nat_pow(c, &4u32.into()),
+                &Polynomial::constant(Rational::from(2))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_puiseux_branches_at() {
+        //the nodal cubic y^2 = x^2 (x + 1) has the branches y = ±x sqrt(1 + x) at the origin
+        let x = &Variable::new("x");
+        let y = &Variable::new("y");
+        let xp = &MultiPolynomial::<Rational>::var(x.clone()).into_ergonomic();
+        let yp = &MultiPolynomial::<Rational>::var(y.clone()).into_ergonomic();
+        let f = (yp.pow(2) - xp.pow(2) * (xp + 1))
+            .into_verbose()
+            .as_bivariate(x, y)
+            .unwrap();
+        let branches =
+            f.puiseux_branches_at(&Rational::from(0), &Rational::from(0), &Rational::from(3));
+        assert_eq!(branches.len(), 2);
+        for b in &branches {
+            assert_eq!(b.field.degree(), 1);
+            let c = b.terms[0].1.coeff(0);
+            assert_eq!(b.terms[0].0, Rational::from(1));
+            assert_eq!(
+                b.terms[1],
+                (
+                    Rational::from(2),
+                    Polynomial::constant(c / Rational::from(2))
+                )
+            );
+        }
+
+        //at (-1, 0) there is the single branch y^2 = x (x - 1)^2, that is y = ±(x^(1/2) - x^(3/2))
+        let branches =
+            f.puiseux_branches_at(&Rational::from(-1), &Rational::from(0), &Rational::from(2));
+        assert_eq!(count_roots(&branches), 2);
+        for b in &branches {
+            assert_eq!(b.terms.len(), 2);
+            assert_eq!(b.terms[0].0, rat(1, 2));
+            assert_eq!(b.terms[1].0, rat(3, 2));
+            assert_eq!(b.terms[1].1.coeff(0), -b.terms[0].1.coeff(0));
+        }
+
+        //(0, 1) is not on the curve
+        assert!(f
+            .puiseux_branches_at(&Rational::from(0), &Rational::from(1), &Rational::from(3))
+            .is_empty());
+        assert!(MultiPolynomial::<Rational>::var(Variable::new("z"))
+            .as_bivariate(x, y)
+            .is_none());
+    }
+}
@@ -6,6 +6,8 @@ use malachite_nz::natural::Natural;
 
 use crate::linear::matrix::*;
 
+use super::fast_division::{FAST_DIVISION_THRESHOLD, HALF_GCD_THRESHOLD};
+
 use super::super::structure::structure::*;
 use algebraeon_sets::structure::*;
 
@@ -147,19 +149,9 @@ impl<RS: SemiRingStructure> SemiRingStructure for PolynomialStructure<RS> {
     }
 
     fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
-        let mut coeffs = Vec::with_capacity(a.coeffs.len() + b.coeffs.len());
-        for _k in 0..a.coeffs.len() + b.coeffs.len() {
-            coeffs.push(self.coeff_ring.zero());
-        }
-        for i in 0..a.coeffs.len() {
-            for j in 0..b.coeffs.len() {
-                self.coeff_ring.add_mut(
-                    &mut coeffs[i + j],
-                    &self.coeff_ring.mul(&a.coeffs[i], &b.coeffs[j]),
-                );
-            }
-        }
-        self.reduce_poly(Polynomial::from_coeffs(coeffs))
+        self.reduce_poly(Polynomial::from_coeffs(
+            self.coeff_ring.polynomial_mul(&a.coeffs, &b.coeffs),
+        ))
     }
 }
 
@@ -583,6 +575,14 @@ impl<RS: IntegralDomainStructure> PolynomialStructure<RS> {
     pub fn resultant(&self, a: Polynomial<RS::Set>, b: Polynomial<RS::Set>) -> RS::Set {
         if self.is_zero(&a) || self.is_zero(&b) {
             self.coeff_ring().zero()
+        } else if self.degree(&a).unwrap() < self.degree(&b).unwrap() {
+            // the subresultant sequence puts the larger degree first, and res(a, b) = (-1)^(deg(a)deg(b)) res(b, a)
+            let res = self.resultant(b.clone(), a.clone());
+            if self.degree(&a).unwrap() % 2 == 1 && self.degree(&b).unwrap() % 2 == 1 {
+                self.coeff_ring().neg(&res)
+            } else {
+                res
+            }
         } else {
             let (mut prs, mut ssres) = self.pseudo_remainder_subresultant_sequence(a, b);
             if self.degree(&prs.pop().unwrap()).unwrap() > 0 {
@@ -683,13 +683,21 @@ impl<RS: GreatestCommonDivisorStructure> PolynomialStructure<RS> {
 
 impl<FS: FieldStructure> GreatestCommonDivisorStructure for PolynomialStructure<FS> {
     fn gcd(&self, x: &Self::Set, y: &Self::Set) -> Self::Set {
-        self.euclidean_gcd(x.clone(), y.clone())
+        if std::cmp::min(self.num_coeffs(x), self.num_coeffs(y)) >= HALF_GCD_THRESHOLD {
+            self.gcd_by_half_gcd(x, y)
+        } else {
+            self.euclidean_gcd(x.clone(), y.clone())
+        }
     }
 }
 
 impl<FS: FieldStructure> BezoutDomainStructure for PolynomialStructure<FS> {
     fn xgcd(&self, x: &Self::Set, y: &Self::Set) -> (Self::Set, Self::Set, Self::Set) {
-        self.euclidean_xgcd(x.clone(), y.clone())
+        if std::cmp::min(self.num_coeffs(x), self.num_coeffs(y)) >= HALF_GCD_THRESHOLD {
+            self.xgcd_by_half_gcd(x, y)
+        } else {
+            self.euclidean_xgcd(x.clone(), y.clone())
+        }
     }
 }
 
@@ -753,6 +761,10 @@ impl<FS: FieldStructure> EuclideanDivisionStructure for PolynomialStructure<FS>
     }
 
     fn quorem(&self, a: &Self::Set, b: &Self::Set) -> Option<(Self::Set, Self::Set)> {
+        let (n, m) = (self.num_coeffs(a), self.num_coeffs(b));
+        if m >= FAST_DIVISION_THRESHOLD && n >= m + FAST_DIVISION_THRESHOLD {
+            return self.quorem_by_newton(a, b);
+        }
         match self.try_quorem(a, b) {
             Ok((q, r)) => Some((q, r)),
             Err(RingDivisionError::NotDivisible) => panic!(),
@@ -1528,6 +1540,23 @@ mod tests {
         println!("{:#?}", Polynomial::resultant(&f, &g));
     }
 
+    #[test]
+    fn test_resultant() {
+        let x = &Polynomial::<Integer>::var().into_ergonomic();
+        //res(a, b) = (-1)^(deg(a)deg(b)) res(b, a)
+        let a = (x + 2).into_verbose();
+        let b = (x.pow(3) - 1).into_verbose();
+        assert_eq!(Polynomial::resultant(&a, &b), Integer::from(-9));
+        assert_eq!(Polynomial::resultant(&b, &a), Integer::from(9));
+        let a = (2 * x - 1).into_verbose();
+        let b = (x.pow(2) + 1).into_verbose();
+        assert_eq!(Polynomial::resultant(&a, &b), Integer::from(5));
+        assert_eq!(Polynomial::resultant(&b, &a), Integer::from(5));
+        let a = (x.pow(2) - 1).into_verbose();
+        let b = (x.pow(2) + x).into_verbose();
+        assert_eq!(Polynomial::resultant(&a, &b), Integer::from(0));
+    }
+
     // #[test]
     // fn test_squarefree_part_by_yuns() {
     //     let x = &Ergonomic::new(Polynomial::<Integer>::var());
@@ -0,0 +1,546 @@
+use std::rc::Rc;
+
+use malachite_nz::integer::Integer;
+
+use super::polynomial::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+/// A power series known modulo x^precision.
+#[derive(Debug, Clone)]
+pub struct PowerSeries<Set> {
+    //the coefficients of x^0, ..., x^(precision - 1)
+    truncation: Polynomial<Set>,
+    precision: usize,
+}
+
+impl<Set> PowerSeries<Set> {
+    /// The known coefficients as a polynomial of degree less than the precision.
+    pub fn truncation(&self) -> &Polynomial<Set> {
+        &self.truncation
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+}
+
+/// The ring R\[\[x\]\] of power series over R truncated at x^precision.
+/// Each element carries its own precision which is at most that of the structure, and arithmetic
+/// returns the precision to which the result is determined by the known coefficients of the inputs.
+#[derive(Debug, Clone)]
+pub struct PowerSeriesStructure<RS: RingStructure> {
+    poly_ring: Rc<PolynomialStructure<RS>>,
+    precision: usize,
+}
+
+impl<RS: RingStructure> PowerSeriesStructure<RS> {
+    pub fn new(coeff_ring: Rc<RS>, precision: usize) -> Self {
+        Self {
+            poly_ring: Rc::new(PolynomialStructure::new(coeff_ring)),
+            precision,
+        }
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn poly_ring(&self) -> Rc<PolynomialStructure<RS>> {
+        self.poly_ring.clone()
+    }
+
+    pub fn coeff_ring(&self) -> Rc<RS> {
+        self.poly_ring.coeff_ring()
+    }
+
+    /// The power series p + O(x^precision), with the precision capped at that of the structure.
+    pub fn from_polynomial_with_precision(
+        &self,
+        p: &Polynomial<RS::Set>,
+        precision: usize,
+    ) -> PowerSeries<RS::Set> {
+        let precision = std::cmp::min(precision, self.precision);
+        PowerSeries {
+            truncation: self.poly_ring.truncate(p, precision),
+            precision,
+        }
+    }
+
+    pub fn from_polynomial(&self, p: &Polynomial<RS::Set>) -> PowerSeries<RS::Set> {
+        self.from_polynomial_with_precision(p, self.precision)
+    }
+
+    pub fn var(&self) -> PowerSeries<RS::Set> {
+        self.from_polynomial(&self.poly_ring.var())
+    }
+
+    /// The coefficient of x^k, or None if it is not known.
+    pub fn coeff<'a>(&'a self, f: &'a PowerSeries<RS::Set>, k: usize) -> Option<&'a RS::Set> {
+        if k < f.precision {
+            Some(self.poly_ring.coeff(&f.truncation, k))
+        } else {
+            None
+        }
+    }
+
+    /// f with its precision lowered to at most the given precision.
+    pub fn truncate(&self, f: &PowerSeries<RS::Set>, precision: usize) -> PowerSeries<RS::Set> {
+        self.from_polynomial_with_precision(&f.truncation, std::cmp::min(f.precision, precision))
+    }
+
+    /// Whether a and b agree up to the smaller of their precisions.
+    /// Unlike equality this is not transitive: O(x) agrees with both 1 + O(x^2) and 2 + O(x^2).
+    pub fn agree_to_precision(&self, a: &PowerSeries<RS::Set>, b: &PowerSeries<RS::Set>) -> bool {
+        let precision = std::cmp::min(a.precision, b.precision);
+        self.poly_ring.equal(
+            &self.poly_ring.truncate(&a.truncation, precision),
+            &self.poly_ring.truncate(&b.truncation, precision),
+        )
+    }
+
+    //the index of the first nonzero known coefficient, or the precision if there is none
+    fn order(&self, f: &PowerSeries<RS::Set>) -> usize {
+        let coeff_ring = self.coeff_ring();
+        (0..f.precision)
+            .find(|k| !coeff_ring.is_zero(self.poly_ring.coeff(&f.truncation, *k)))
+            .unwrap_or(f.precision)
+    }
+
+    /// The index of the first nonzero coefficient, or None if every known coefficient is zero.
+    pub fn valuation(&self, f: &PowerSeries<RS::Set>) -> Option<usize> {
+        let v = self.order(f);
+        if v < f.precision {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn derivative(&self, f: &PowerSeries<RS::Set>) -> PowerSeries<RS::Set> {
+        PowerSeries {
+            truncation: self.poly_ring.derivative(f.truncation.clone()),
+            precision: f.precision.saturating_sub(1),
+        }
+    }
+
+    /// The composition f(g), where g has no constant term. None if g has a nonzero constant term.
+    pub fn compose(
+        &self,
+        f: &PowerSeries<RS::Set>,
+        g: &PowerSeries<RS::Set>,
+    ) -> Option<PowerSeries<RS::Set>> {
+        let v = self.order(g);
+        if v == 0 {
+            return None;
+        }
+        //the unknown terms of f contribute O(x^(precision(f) v))
+        let precision = std::cmp::min(f.precision.saturating_mul(v), g.precision);
+        let g = self.truncate(g, precision);
+        let mut h = self.from_polynomial_with_precision(&self.poly_ring.zero(), precision);
+        for c in f.truncation.coeffs().into_iter().rev() {
+            h = self.add(
+                &self.mul(&h, &g),
+                &self.from_polynomial_with_precision(&Polynomial::constant(c.clone()), precision),
+            );
+        }
+        Some(self.truncate(&h, precision))
+    }
+}
+
+impl<FS: FieldStructure> PowerSeriesStructure<FS> {
+    /// The inverse of f. None if f has zero constant term.
+    pub fn inv(&self, f: &PowerSeries<FS::Set>) -> Option<PowerSeries<FS::Set>> {
+        IntegralDomainStructure::inv(self, f).ok()
+    }
+
+    /// The nth root of f with leading coefficient 1. None unless f has leading term x^(nk) for some k and n is invertible.
+    pub fn nth_root(&self, f: &PowerSeries<FS::Set>, n: usize) -> Option<PowerSeries<FS::Set>> {
+        let field = self.coeff_ring();
+        let v = self.valuation(f)?;
+        if n == 0
+            || v % n != 0
+            || !field.equal(self.poly_ring.coeff(&f.truncation, v), &field.one())
+        {
+            return None;
+        }
+        let n_inv = field.inv(&field.from_int(&Integer::from(n))).ok()?;
+        //h = f / x^v has constant term 1 and its root is found by Newton iteration g <- ((n - 1) g + h / g^(n-1)) / n
+        let precision = f.precision - v;
+        let h = PowerSeries {
+            truncation: self.poly_ring.shift_down(&f.truncation, v),
+            precision,
+        };
+        let mut g = self.from_polynomial_with_precision(&self.poly_ring.one(), precision);
+        let mut k = 1;
+        while k < precision {
+            k = std::cmp::min(2 * k, precision);
+            let g_k = PowerSeries {
+                truncation: g.truncation.clone(),
+                precision: k,
+            };
+            let g_pow = self.nat_pow(&g_k, &(n - 1).into());
+            let step = self.add(
+                &self.mul(
+                    &g_k,
+                    &self.from_polynomial(&Polynomial::constant(
+                        field.from_int(&Integer::from(n - 1)),
+                    )),
+                ),
+                &self.div(&self.truncate(&h, k), &g_pow).unwrap(),
+            );
+            g = self.truncate(
+                &self.mul(
+                    &step,
+                    &self.from_polynomial(&Polynomial::constant(n_inv.clone())),
+                ),
+                k,
+            );
+        }
+        Some(PowerSeries {
+            truncation: self.poly_ring.mul_var_pow(&g.truncation, v / n),
+            precision: precision + v / n,
+        })
+    }
+
+    pub fn sqrt(&self, f: &PowerSeries<FS::Set>) -> Option<PowerSeries<FS::Set>> {
+        self.nth_root(f, 2)
+    }
+
+    /// The compositional inverse of f, that is g with f(g(x)) = g(f(x)) = x. None unless f has zero constant term and nonzero linear term.
+    pub fn reverse(&self, f: &PowerSeries<FS::Set>) -> Option<PowerSeries<FS::Set>> {
+        if self.order(f) != 1 {
+            return None;
+        }
+        let x = self.var();
+        let df = self.derivative(f);
+        //Newton iteration g <- g - (f(g) - x) / f'(g)
+        let mut g = self.truncate(
+            &self.mul(
+                &x,
+                &self.from_polynomial(&Polynomial::constant(
+                    self.coeff_ring()
+                        .inv(self.poly_ring.coeff(&f.truncation, 1))
+                        .unwrap(),
+                )),
+            ),
+            std::cmp::min(f.precision, 2),
+        );
+        let mut k = g.precision;
+        while k < f.precision {
+            k = std::cmp::min(2 * k, f.precision);
+            let g_k = PowerSeries {
+                truncation: g.truncation.clone(),
+                precision: k,
+            };
+            let error = self.add(&self.compose(f, &g_k).unwrap(), &self.neg(&x));
+            let slope = self.compose(&df, &g_k).unwrap();
+            g = self.truncate(
+                &self.add(&g_k, &self.neg(&self.div(&error, &slope).unwrap())),
+                k,
+            );
+        }
+        Some(g)
+    }
+}
+
+impl<FS: FieldStructure + CharZeroStructure> PowerSeriesStructure<FS> {
+    /// The antiderivative of f with zero constant term.
+    pub fn integral(&self, f: &PowerSeries<FS::Set>) -> PowerSeries<FS::Set> {
+        let field = self.coeff_ring();
+        self.from_polynomial_with_precision(
+            &self.poly_ring.mul_var_pow(
+                &f.truncation.apply_map_with_powers(|(k, c)| {
+                    field
+                        .div(c, &field.from_int(&Integer::from(k + 1)))
+                        .unwrap()
+                }),
+                1,
+            ),
+            f.precision + 1,
+        )
+    }
+
+    /// The logarithm of f. None unless f has constant term 1.
+    pub fn log(&self, f: &PowerSeries<FS::Set>) -> Option<PowerSeries<FS::Set>> {
+        let field = self.coeff_ring();
+        if f.precision == 0 || !field.equal(self.poly_ring.coeff(&f.truncation, 0), &field.one()) {
+            return None;
+        }
+        //log(f) is the integral of f' / f
+        Some(self.integral(&self.div(&self.derivative(f), f).unwrap()))
+    }
+
+    /// The exponential of f. None unless f has zero constant term.
+    pub fn exp(&self, f: &PowerSeries<FS::Set>) -> Option<PowerSeries<FS::Set>> {
+        if self.order(f) == 0 {
+            return None;
+        }
+        //Newton iteration g <- g (1 - log(g) + f)
+        let mut g = self.from_polynomial_with_precision(&self.poly_ring.one(), f.precision);
+        let mut k = 1;
+        while k < f.precision {
+            k = std::cmp::min(2 * k, f.precision);
+            let g_k = PowerSeries {
+                truncation: g.truncation.clone(),
+                precision: k,
+            };
+            let step = self.add(
+                &self.add(&self.one(), &self.neg(&self.log(&g_k).unwrap())),
+                &self.truncate(f, k),
+            );
+            g = self.truncate(&self.mul(&g_k, &step), k);
+        }
+        Some(g)
+    }
+}
+
+impl<RS: RingStructure> PartialEq for PowerSeriesStructure<RS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.poly_ring == other.poly_ring && self.precision == other.precision
+    }
+}
+
+impl<RS: RingStructure> Eq for PowerSeriesStructure<RS> {}
+
+impl<RS: RingStructure> Structure for PowerSeriesStructure<RS> {
+    type Set = PowerSeries<RS::Set>;
+}
+
+impl<RS: RingStructure + ToStringStructure> ToStringStructure for PowerSeriesStructure<RS> {
+    fn to_string(&self, elem: &Self::Set) -> String {
+        format!(
+            "{} + O(λ^{})",
+            self.poly_ring.to_string(&elem.truncation),
+            elem.precision
+        )
+    }
+}
+
+impl<RS: RingStructure> PartialEqStructure for PowerSeriesStructure<RS> {
+    /// Whether a and b have the same precision and the same known coefficients.
+    fn equal(&self, a: &Self::Set, b: &Self::Set) -> bool {
+        a.precision == b.precision && self.poly_ring.equal(&a.truncation, &b.truncation)
+    }
+}
+
+impl<RS: RingStructure> EqStructure for PowerSeriesStructure<RS> {}
+
+impl<RS: RingStructure> SemiRingStructure for PowerSeriesStructure<RS> {
+    fn zero(&self) -> Self::Set {
+        self.from_polynomial(&self.poly_ring.zero())
+    }
+
+    fn one(&self) -> Self::Set {
+        self.from_polynomial(&self.poly_ring.one())
+    }
+
+    fn add(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
+        self.from_polynomial_with_precision(
+            &self.poly_ring.add(&a.truncation, &b.truncation),
+            std::cmp::min(a.precision, b.precision),
+        )
+    }
+
+    fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
+        //(a + O(x^pa))(b + O(x^pb)) = ab + O(x^min(pa + v(b), pb + v(a)))
+        let precision = std::cmp::min(a.precision + self.order(b), b.precision + self.order(a));
+        let precision = std::cmp::min(precision, self.precision);
+        self.from_polynomial_with_precision(
+            &self.poly_ring.mul(
+                &self.poly_ring.truncate(&a.truncation, precision),
+                &self.poly_ring.truncate(&b.truncation, precision),
+            ),
+            precision,
+        )
+    }
+}
+
+impl<RS: RingStructure> RingStructure for PowerSeriesStructure<RS> {
+    fn neg(&self, a: &Self::Set) -> Self::Set {
+        PowerSeries {
+            truncation: self.poly_ring.neg(&a.truncation),
+            precision: a.precision,
+        }
+    }
+}
+
+impl<FS: FieldStructure> IntegralDomainStructure for PowerSeriesStructure<FS> {
+    fn div(&self, a: &Self::Set, b: &Self::Set) -> Result<Self::Set, RingDivisionError> {
+        let vb = match self.valuation(b) {
+            Some(vb) => vb,
+            None => {
+                return Err(RingDivisionError::DivideByZero);
+            }
+        };
+        let va = self.order(a);
+        if va < vb || a.precision < vb {
+            return Err(RingDivisionError::NotDivisible);
+        }
+        //a / b = (a / x^vb) / (b / x^vb) where b / x^vb is a unit
+        let precision = std::cmp::min(a.precision - vb, b.precision + va - 2 * vb);
+        let b_inv = self
+            .poly_ring
+            .power_series_inverse(&self.poly_ring.shift_down(&b.truncation, vb), precision)
+            .unwrap();
+        Ok(self.from_polynomial_with_precision(
+            &self
+                .poly_ring
+                .mul(&self.poly_ring.shift_down(&a.truncation, vb), &b_inv),
+            precision,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+    use malachite_q::Rational;
+
+    fn series(
+        ring: &PowerSeriesStructure<CannonicalStructure<Rational>>,
+        coeffs: Vec<(i64, i64)>,
+    ) -> PowerSeries<Rational> {
+        ring.from_polynomial(&Polynomial::from_coeffs(
+            coeffs
+                .into_iter()
+                .map(|(n, d)| Rational::from_integers(n.into(), d.into()))
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn test_power_series_precision() {
+        let ring = PowerSeriesStructure::new(Rational::structure(), 10);
+        let x = ring.var();
+        let a = ring.truncate(&ring.nat_pow(&x, &2u32.into()), 5);
+        let b = ring.truncate(&x, 4);
+        assert_eq!(ring.mul(&a, &b).precision(), 6);
+        assert_eq!(ring.add(&a, &b).precision(), 4);
+        assert_eq!(ring.nat_pow(&x, &12u32.into()).precision(), 10);
+        assert_eq!(ring.valuation(&ring.nat_pow(&x, &12u32.into())), None);
+
+        //1 / (1 - x) = 1 + x + x^2 + ...
+        let geometric = ring.inv(&ring.add(&ring.one(), &ring.neg(&x))).unwrap();
+        assert!(ring.equal(&geometric, &series(&ring, vec![(1, 1); 10])));
+        assert_eq!(geometric.precision(), 10);
+        //(x^2 + x^3) / (x + O(x^4)) = x + x^2 + O(x^4)
+        let c = ring
+            .div(&series(&ring, vec![(0, 1), (0, 1), (1, 1), (1, 1)]), &b)
+            .unwrap();
+        assert_eq!(c.precision(), 4);
+        assert!(ring.agree_to_precision(&c, &series(&ring, vec![(0, 1), (1, 1), (1, 1)])));
+        assert!(!ring.equal(&c, &series(&ring, vec![(0, 1), (1, 1), (1, 1)])));
+        assert!(ring.equal(
+            &c,
+            &ring.truncate(&series(&ring, vec![(0, 1), (1, 1), (1, 1)]), 4)
+        ));
+
+        //agreement to precision is not transitive, so it is not used for equality
+        let one = ring.truncate(&ring.one(), 2);
+        let two = ring.truncate(&ring.add(&ring.one(), &ring.one()), 2);
+        let unknown = ring.truncate(&ring.one(), 0);
+        assert!(ring.agree_to_precision(&unknown, &one));
+        assert!(ring.agree_to_precision(&unknown, &two));
+        assert!(!ring.agree_to_precision(&one, &two));
+        assert!(!ring.equal(&unknown, &one));
+        assert!(matches!(
+            ring.div(&x, &a),
+            Err(RingDivisionError::NotDivisible)
+        ));
+        assert!(matches!(
+            ring.div(&x, &ring.zero()),
+            Err(RingDivisionError::DivideByZero)
+        ));
+        assert!(ring.inv(&x).is_none());
+
+        let ring = PowerSeriesStructure::new(Modulo::<7>::structure(), 20);
+        let f = ring.from_polynomial(&Polynomial::from_coeffs(
+            vec![3usize, 1, 4, 1, 5]
+                .into_iter()
+                .map(Modulo::from)
+                .collect(),
+        ));
+        assert!(ring.equal(&ring.mul(&f, &ring.inv(&f).unwrap()), &ring.one()));
+    }
+
+    #[test]
+    fn test_power_series_exp_log() {
+        let ring = PowerSeriesStructure::new(Rational::structure(), 12);
+        let x = ring.var();
+        let factorials = [
+            1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880, 3628800, 39916800,
+        ];
+        let exp_x = ring.exp(&x).unwrap();
+        assert!(ring.equal(
+            &exp_x,
+            &series(&ring, factorials.into_iter().map(|n| (1, n)).collect())
+        ));
+        assert_eq!(exp_x.precision(), 12);
+        assert!(ring.equal(&ring.log(&exp_x).unwrap(), &x));
+
+        let f = series(&ring, vec![(1, 1), (-2, 3), (0, 1), (5, 1)]);
+        assert!(ring.equal(&ring.exp(&ring.log(&f).unwrap()).unwrap(), &f));
+        assert!(ring.log(&ring.var()).is_none());
+        assert!(ring.exp(&ring.one()).is_none());
+    }
+
+    #[test]
+    fn test_power_series_roots() {
+        let ring = PowerSeriesStructure::new(Rational::structure(), 15);
+        let f = series(&ring, vec![(1, 1), (1, 1)]);
+        let g = ring.sqrt(&f).unwrap();
+        assert!(ring.equal(&ring.mul(&g, &g), &f));
+        //the coefficients of sqrt(1 + x) begin 1, 1/2, -1/8, 1/16
+        assert!(ring.agree_to_precision(
+            &ring.truncate(&g, 4),
+            &series(&ring, vec![(1, 1), (1, 2), (-1, 8), (1, 16)])
+        ));
+
+        let f = series(&ring, vec![(0, 1), (0, 1), (0, 1), (1, 1), (2, 1), (-1, 3)]);
+        let g = ring.nth_root(&f, 3).unwrap();
+        assert_eq!(ring.valuation(&g), Some(1));
+        assert_eq!(g.precision(), 13);
+        assert!(ring.equal(&ring.nat_pow(&g, &3u32.into()), &f));
+        assert!(ring.nth_root(&f, 2).is_none());
+        assert!(ring.sqrt(&series(&ring, vec![(4, 1), (1, 1)])).is_none());
+    }
+
+    #[test]
+    fn test_power_series_composition() {
+        let ring = PowerSeriesStructure::new(Rational::structure(), 10);
+        let x = ring.var();
+        //g + g^2 = x has solution g = x - x^2 + 2x^3 - 5x^4 + 14x^5 - ...
+        let f = ring.add(&x, &ring.mul(&x, &x));
+        let g = ring.reverse(&f).unwrap();
+        assert!(ring.equal(
+            &g,
+            &series(
+                &ring,
+                vec![
+                    (0, 1),
+                    (1, 1),
+                    (-1, 1),
+                    (2, 1),
+                    (-5, 1),
+                    (14, 1),
+                    (-42, 1),
+                    (132, 1),
+                    (-429, 1),
+                    (1430, 1)
+                ]
+            )
+        ));
+        assert!(ring.equal(&ring.compose(&f, &g).unwrap(), &x));
+        assert!(ring.equal(&ring.compose(&g, &f).unwrap(), &x));
+        assert!(ring.compose(&f, &ring.one()).is_none());
+        assert!(ring.reverse(&ring.mul(&x, &x)).is_none());
+
+        //exp(x) composed with x^2 is exp(x^2) and is known to the same precision
+        let exp_x = ring.truncate(&ring.exp(&x).unwrap(), 5);
+        let h = ring.compose(&exp_x, &ring.mul(&x, &x)).unwrap();
+        assert_eq!(h.precision(), 10);
+        assert!(ring.equal(&h, &ring.exp(&ring.mul(&x, &x)).unwrap()));
+    }
+}
@@ -65,6 +65,15 @@ where
         self.from_col_vector(v.transpose())
     }
 
+    /// The composition g(h) modulo the modulus. See [`ModularComposition`].
+    pub fn modular_composition(
+        &self,
+        g: &Polynomial<FS::Set>,
+        h: &Polynomial<FS::Set>,
+    ) -> Polynomial<FS::Set> {
+        ModularComposition::new(self, h).compose(g)
+    }
+
     pub fn min_poly(&self, a: &Polynomial<FS::Set>) -> Polynomial<FS::Set> {
         MatrixStructure::new(self.ring().coeff_ring())
             .minimal_polynomial(self.col_multiplication_matrix(a))
@@ -83,3 +92,140 @@ where
             .unwrap()
     }
 }
+
+/// Composition g(h) modulo a fixed monic modulus f of degree n for many g and a fixed h, by the baby-step giant-step method of Brent and Kung.
+/// The powers h^0, ..., h^m are precomputed for a block length m. Then the coefficients of g are split into blocks of length m,
+/// each block is evaluated from the powers, and the blocks are combined by Horner's rule in h^m.
+/// Products modulo f use a precomputed inverse of the reversal of f, so each costs about three polynomial multiplications.
+#[derive(Debug, Clone)]
+pub struct ModularComposition<FS: FieldStructure> {
+    poly_ring: PolynomialStructure<FS>,
+    modulus: Polynomial<FS::Set>,
+    modulus_rev_inv: Polynomial<FS::Set>,
+    powers: Vec<Polynomial<FS::Set>>,
+    top_power: Polynomial<FS::Set>,
+}
+
+impl<FS: FieldStructure> ModularComposition<FS>
+where
+    PolynomialStructure<FS>: Structure<Set = Polynomial<FS::Set>>,
+{
+    /// Use a block length of about the square root of n, which is best for composing a single g of degree less than n.
+    pub fn new<const IS_FIELD: bool>(
+        quotient: &QuotientStructure<PolynomialStructure<FS>, IS_FIELD>,
+        h: &Polynomial<FS::Set>,
+    ) -> Self {
+        let n = quotient.degree();
+        let mut m = n.isqrt();
+        if m * m < n {
+            m += 1;
+        }
+        Self::with_block_length(quotient, h, m)
+    }
+
+    /// Use the block length m. Composing k polynomials of degree less than n costs about m + kn/m multiplications modulo f,
+    /// so a block length of about the square root of kn is best.
+    pub fn with_block_length<const IS_FIELD: bool>(
+        quotient: &QuotientStructure<PolynomialStructure<FS>, IS_FIELD>,
+        h: &Polynomial<FS::Set>,
+        m: usize,
+    ) -> Self {
+        let poly_ring = quotient.ring().as_ref().clone();
+        let modulus = poly_ring.factor_fav_assoc(quotient.modulus()).1;
+        let n = quotient.degree();
+        let modulus_rev_inv = poly_ring
+            .power_series_inverse(&poly_ring.reversed(&modulus), n.max(1))
+            .unwrap();
+        let mut composition = Self {
+            poly_ring,
+            modulus,
+            modulus_rev_inv,
+            powers: vec![],
+            top_power: quotient.one(),
+        };
+        let h = quotient.reduce(h);
+        //1 is reduced too, since it is 0 when the modulus is a constant
+        let mut powers = vec![quotient.reduce(&quotient.one())];
+        for i in 0..m.max(1) {
+            powers.push(composition.mul(&powers[i], &h));
+        }
+        composition.top_power = powers.pop().unwrap();
+        composition.powers = powers;
+        composition
+    }
+
+    //the remainder of a of degree less than 2n modulo the modulus
+    fn reduce(&self, a: &Polynomial<FS::Set>) -> Polynomial<FS::Set> {
+        self.poly_ring
+            .quorem_by_reversed_inverse(a, &self.modulus, &self.modulus_rev_inv)
+            .1
+    }
+
+    /// The product of a and b modulo the modulus, for a and b of degree less than that of the modulus.
+    pub fn mul(&self, a: &Polynomial<FS::Set>, b: &Polynomial<FS::Set>) -> Polynomial<FS::Set> {
+        self.reduce(&self.poly_ring.mul(a, b))
+    }
+
+    /// The composition g(h) modulo the modulus.
+    pub fn compose(&self, g: &Polynomial<FS::Set>) -> Polynomial<FS::Set> {
+        let field = self.poly_ring.coeff_ring();
+        let n = self.poly_ring.degree(&self.modulus).unwrap();
+        let blocks = g
+            .coeffs()
+            .chunks(self.powers.len())
+            .map(|block| {
+                let mut value = vec![field.zero(); n];
+                for (c, power) in block.iter().zip(&self.powers) {
+                    for (k, power_coeff) in power.coeffs().into_iter().enumerate() {
+                        field.add_mut(&mut value[k], &field.mul(c, power_coeff));
+                    }
+                }
+                self.poly_ring.reduce_poly(Polynomial::from_coeffs(value))
+            })
+            .collect::<Vec<_>>();
+        blocks
+            .into_iter()
+            .rev()
+            .fold(self.poly_ring.zero(), |value, block| {
+                self.poly_ring
+                    .add(&self.mul(&value, &self.top_power), &block)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+
+    #[test]
+    fn test_modular_composition() {
+        let field = Modulo::<7>::structure();
+        let ring = Polynomial::<Modulo<7>>::structure();
+        let mut random_elements = field.generate_random_elements(0);
+        let mut random_polynomial = |n: usize| {
+            Polynomial::from_coeffs((0..n).map(|_| random_elements.next().unwrap()).collect())
+        };
+        let f = ring.mul_scalar(&random_polynomial(21), &Modulo::from(3usize));
+        let g = random_polynomial(50);
+        let h = random_polynomial(30);
+        let mod_f = QuotientStructure::new_ring(ring.clone(), f.clone());
+
+        let expected = ring.rem(&Polynomial::compose(&g, &h), &f);
+        assert_eq!(mod_f.modular_composition(&g, &h), expected);
+        for m in [1, 3, 5, 50] {
+            let composition = ModularComposition::with_block_length(&mod_f, &h, m);
+            assert_eq!(composition.compose(&g), expected);
+            assert_eq!(composition.compose(&ring.zero()), ring.zero());
+            let a = random_polynomial(20);
+            let b = random_polynomial(20);
+            assert_eq!(composition.mul(&a, &b), ring.rem(&ring.mul(&a, &b), &f));
+        }
+
+        //everything is zero modulo a constant
+        let mod_3 =
+            QuotientStructure::new_ring(ring.clone(), Polynomial::constant(Modulo::from(3usize)));
+        assert_eq!(ModularComposition::new(&mod_3, &h).compose(&g), ring.zero());
+        assert_eq!(mod_3.modular_composition(&g, &h), ring.zero());
+    }
+}
@@ -0,0 +1,576 @@
+use std::fmt::Display;
+use std::rc::Rc;
+
+use malachite_q::Rational;
+
+use super::polynomial::*;
+use crate::number::anf::number_field::{new_anf, splitting_field_anf, ANFStructure};
+use crate::number::natural::nat_to_usize;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+/// A quotient of polynomials over a field, kept in reduced form: the numerator and denominator are coprime and the denominator is monic.
+#[derive(Debug, Clone)]
+pub struct RationalFunction<Set> {
+    numerator: Polynomial<Set>,
+    denominator: Polynomial<Set>,
+}
+
+/// A rational function written as a polynomial part plus a sum of terms numerator / factor^power,
+/// where each factor is monic and each numerator has smaller degree than its factor.
+#[derive(Debug, Clone)]
+pub struct PartialFractions<Set> {
+    pub polynomial_part: Polynomial<Set>,
+    //(numerator, factor, power)
+    pub terms: Vec<(Polynomial<Set>, Polynomial<Set>, usize)>,
+}
+
+/// The field K(x) of rational functions over a field K.
+#[derive(Debug, Clone)]
+pub struct RationalFunctionStructure<FS: FieldStructure> {
+    poly_ring: Rc<PolynomialStructure<FS>>,
+}
+
+impl<FS: FieldStructure> RationalFunctionStructure<FS> {
+    pub fn new(field: Rc<FS>) -> Self {
+        Self {
+            poly_ring: Rc::new(PolynomialStructure::new(field)),
+        }
+    }
+
+    pub fn poly_ring(&self) -> Rc<PolynomialStructure<FS>> {
+        self.poly_ring.clone()
+    }
+
+    pub fn coeff_field(&self) -> Rc<FS> {
+        self.poly_ring.coeff_ring()
+    }
+
+    //n/d in reduced form for nonzero d
+    fn reduce(&self, n: Polynomial<FS::Set>, d: Polynomial<FS::Set>) -> RationalFunction<FS::Set> {
+        let poly_ring = &self.poly_ring;
+        debug_assert!(!poly_ring.is_zero(&d));
+        if poly_ring.is_zero(&n) {
+            return RationalFunction {
+                numerator: poly_ring.zero(),
+                denominator: poly_ring.one(),
+            };
+        }
+        let g = poly_ring.gcd(&n, &d);
+        let n = poly_ring.div(&n, &g).unwrap();
+        let d = poly_ring.div(&d, &g).unwrap();
+        let lc_inv = self
+            .coeff_field()
+            .inv(poly_ring.leading_coeff(&d).unwrap())
+            .unwrap();
+        RationalFunction {
+            numerator: poly_ring.mul_scalar(&n, &lc_inv),
+            denominator: poly_ring.mul_scalar(&d, &lc_inv),
+        }
+    }
+
+    /// The rational function n/d, or None if d is zero.
+    pub fn from_fraction(
+        &self,
+        n: Polynomial<FS::Set>,
+        d: Polynomial<FS::Set>,
+    ) -> Option<RationalFunction<FS::Set>> {
+        if self.poly_ring.is_zero(&d) {
+            None
+        } else {
+            Some(self.reduce(n, d))
+        }
+    }
+
+    pub fn var(&self) -> RationalFunction<FS::Set> {
+        self.from_base_ring(self.poly_ring.var())
+    }
+
+    /// The value of f at x, or None if x is a pole of f.
+    pub fn evaluate(&self, f: &RationalFunction<FS::Set>, x: &FS::Set) -> Option<FS::Set> {
+        let field = self.coeff_field();
+        field
+            .div(
+                &self.poly_ring.evaluate(&f.numerator, x),
+                &self.poly_ring.evaluate(&f.denominator, x),
+            )
+            .ok()
+    }
+
+    pub fn derivative(&self, f: &RationalFunction<FS::Set>) -> RationalFunction<FS::Set> {
+        let poly_ring = &self.poly_ring;
+        let n = poly_ring.add(
+            &poly_ring.mul(&poly_ring.derivative(f.numerator.clone()), &f.denominator),
+            &poly_ring
+                .neg(&poly_ring.mul(&f.numerator, &poly_ring.derivative(f.denominator.clone()))),
+        );
+        self.reduce(n, poly_ring.mul(&f.denominator, &f.denominator))
+    }
+
+    /// The partial fraction decomposition of f, given the factorization of its denominator as pairwise coprime monic factors with multiplicities.
+    pub fn partial_fractions_by_factors(
+        &self,
+        f: &RationalFunction<FS::Set>,
+        factors: &[(Polynomial<FS::Set>, usize)],
+    ) -> PartialFractions<FS::Set> {
+        let poly_ring = &self.poly_ring;
+        let (polynomial_part, r) = poly_ring.quorem(&f.numerator, &f.denominator).unwrap();
+        let mut terms = vec![];
+        if !poly_ring.is_zero(&r) {
+            for (q, e) in factors {
+                //the part of r/d with denominator q^e is s/q^e where s = r (d/q^e)^{-1} mod q^e
+                let qe = poly_ring.nat_pow(q, &(*e).into());
+                let cofactor = poly_ring.div(&f.denominator, &qe).unwrap();
+                let (g, u, _v) = poly_ring.xgcd(&cofactor, &qe);
+                let mut s = poly_ring.rem(&poly_ring.div(&poly_ring.mul(&r, &u), &g).unwrap(), &qe);
+                //expand s in powers of q
+                for k in (1..=*e).rev() {
+                    let (quo, rem) = poly_ring.quorem(&s, q).unwrap();
+                    if !poly_ring.is_zero(&rem) {
+                        terms.push((rem, q.clone(), k));
+                    }
+                    s = quo;
+                }
+            }
+        }
+        PartialFractions {
+            polynomial_part,
+            terms,
+        }
+    }
+
+    /// The rational function represented by a partial fraction decomposition.
+    pub fn from_partial_fractions(
+        &self,
+        pf: &PartialFractions<FS::Set>,
+    ) -> RationalFunction<FS::Set> {
+        let mut f = self.from_base_ring(pf.polynomial_part.clone());
+        for (n, q, k) in &pf.terms {
+            let term = self
+                .from_fraction(n.clone(), self.poly_ring.nat_pow(q, &(*k).into()))
+                .unwrap();
+            f = self.add(&f, &term);
+        }
+        f
+    }
+
+    /// The Padé approximant of type \[m/n\] to the power series f, that is p/q with deg(p) <= m, deg(q) <= n and q(0) != 0 such that
+    /// p/q agrees with f modulo x^(m+n+1). Found from the remainder sequence of x^(m+n+1) and f. None if no such approximant exists.
+    pub fn pade_approximant(
+        &self,
+        f: &Polynomial<FS::Set>,
+        m: usize,
+        n: usize,
+    ) -> Option<RationalFunction<FS::Set>> {
+        let poly_ring = &self.poly_ring;
+        let (mut r0, mut r1) = (
+            poly_ring.var_pow(m + n + 1),
+            poly_ring.truncate(f, m + n + 1),
+        );
+        let (mut t0, mut t1) = (poly_ring.zero(), poly_ring.one());
+        while poly_ring.degree(&r1).is_some_and(|d| d > m) {
+            let (q, r) = poly_ring.quorem(&r0, &r1).unwrap();
+            let t = poly_ring.add(&t0, &poly_ring.neg(&poly_ring.mul(&q, &t1)));
+            (r0, r1) = (r1, r);
+            (t0, t1) = (t1, t);
+        }
+        if self.coeff_field().is_zero(poly_ring.coeff(&t1, 0)) {
+            return None;
+        }
+        Some(self.reduce(r1, t1))
+    }
+}
+
+impl<FS: FieldStructure> RationalFunctionStructure<FS>
+where
+    PolynomialStructure<FS>: UniqueFactorizationStructure<Set = Polynomial<FS::Set>>,
+{
+    /// The partial fraction decomposition of f over the coefficient field, with a term for each power of each irreducible factor of the denominator.
+    pub fn partial_fractions(&self, f: &RationalFunction<FS::Set>) -> PartialFractions<FS::Set> {
+        let factors = self
+            .poly_ring
+            .factor(&f.denominator)
+            .unwrap()
+            .into_factors()
+            .into_iter()
+            .map(|(q, e)| (q, nat_to_usize(&e).unwrap()))
+            .collect::<Vec<_>>();
+        self.partial_fractions_by_factors(f, &factors)
+    }
+}
+
+impl RationalFunctionStructure<CannonicalStructure<Rational>> {
+    /// The partial fraction decomposition of f into terms c / (x - a)^k over the splitting field of its denominator.
+    /// The splitting field is returned alongside the decomposition, whose coefficients lie in it.
+    pub fn partial_fractions_over_splitting_field(
+        &self,
+        f: &RationalFunction<Rational>,
+    ) -> (Rc<ANFStructure>, PartialFractions<Polynomial<Rational>>) {
+        let poly_ring = &self.poly_ring;
+        let (anf, roots) = if poly_ring.degree(&f.denominator) == Some(0) {
+            (new_anf(poly_ring.var()), vec![])
+        } else {
+            splitting_field_anf(&f.denominator)
+        };
+        let anf = Rc::new(anf);
+        let field = RationalFunctionStructure::new(anf.clone());
+        let anf_poly_ring = field.poly_ring();
+        let embed = |p: &Polynomial<Rational>| {
+            p.apply_map(|c| anf.reduce(&Polynomial::constant(c.clone())))
+        };
+        let f = RationalFunction {
+            numerator: embed(&f.numerator),
+            denominator: embed(&f.denominator),
+        };
+
+        //the denominator is the product of (x - a)^k over the distinct roots a with multiplicity k
+        let mut factors: Vec<(Polynomial<Polynomial<Rational>>, usize)> = vec![];
+        let mut d = f.denominator.clone();
+        for root in roots {
+            let linear = Polynomial::from_coeffs(vec![anf.neg(&root), anf.one()]);
+            let mut k = 0;
+            while let Ok(quo) = anf_poly_ring.div(&d, &linear) {
+                d = quo;
+                k += 1;
+            }
+            if k > 0 {
+                factors.push((linear, k));
+            }
+        }
+        debug_assert_eq!(anf_poly_ring.degree(&d), Some(0));
+        let pf = field.partial_fractions_by_factors(&f, &factors);
+        (anf, pf)
+    }
+}
+
+impl<FS: FieldStructure> PartialEq for RationalFunctionStructure<FS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.poly_ring == other.poly_ring
+    }
+}
+
+impl<FS: FieldStructure> Eq for RationalFunctionStructure<FS> {}
+
+impl<FS: FieldStructure> Structure for RationalFunctionStructure<FS> {
+    type Set = RationalFunction<FS::Set>;
+}
+
+impl<FS: FieldStructure + ToStringStructure> ToStringStructure for RationalFunctionStructure<FS> {
+    fn to_string(&self, elem: &Self::Set) -> String {
+        if self
+            .poly_ring
+            .equal(&elem.denominator, &self.poly_ring.one())
+        {
+            self.poly_ring.to_string(&elem.numerator)
+        } else {
+            format!(
+                "({})/({})",
+                self.poly_ring.to_string(&elem.numerator),
+                self.poly_ring.to_string(&elem.denominator)
+            )
+        }
+    }
+}
+
+impl<FS: FieldStructure> PartialEqStructure for RationalFunctionStructure<FS> {
+    fn equal(&self, a: &Self::Set, b: &Self::Set) -> bool {
+        //reduced forms are unique
+        self.poly_ring.equal(&a.numerator, &b.numerator)
+            && self.poly_ring.equal(&a.denominator, &b.denominator)
+    }
+}
+
+impl<FS: FieldStructure> EqStructure for RationalFunctionStructure<FS> {}
+
+impl<FS: FieldStructure> SemiRingStructure for RationalFunctionStructure<FS> {
+    fn zero(&self) -> Self::Set {
+        self.from_base_ring(self.poly_ring.zero())
+    }
+
+    fn one(&self) -> Self::Set {
+        self.from_base_ring(self.poly_ring.one())
+    }
+
+    fn add(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
+        let poly_ring = &self.poly_ring;
+        self.reduce(
+            poly_ring.add(
+                &poly_ring.mul(&a.numerator, &b.denominator),
+                &poly_ring.mul(&b.numerator, &a.denominator),
+            ),
+            poly_ring.mul(&a.denominator, &b.denominator),
+        )
+    }
+
+    fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
+        let poly_ring = &self.poly_ring;
+        self.reduce(
+            poly_ring.mul(&a.numerator, &b.numerator),
+            poly_ring.mul(&a.denominator, &b.denominator),
+        )
+    }
+}
+
+impl<FS: FieldStructure> RingStructure for RationalFunctionStructure<FS> {
+    fn neg(&self, a: &Self::Set) -> Self::Set {
+        RationalFunction {
+            numerator: self.poly_ring.neg(&a.numerator),
+            denominator: a.denominator.clone(),
+        }
+    }
+}
+
+impl<FS: FieldStructure> IntegralDomainStructure for RationalFunctionStructure<FS> {
+    fn div(&self, a: &Self::Set, b: &Self::Set) -> Result<Self::Set, RingDivisionError> {
+        if self.is_zero(b) {
+            Err(RingDivisionError::DivideByZero)
+        } else {
+            let poly_ring = &self.poly_ring;
+            Ok(self.reduce(
+                poly_ring.mul(&a.numerator, &b.denominator),
+                poly_ring.mul(&a.denominator, &b.numerator),
+            ))
+        }
+    }
+}
+
+impl<FS: FieldStructure> FieldStructure for RationalFunctionStructure<FS> {}
+
+impl<FS: FieldStructure> FieldOfFractionsStructure for RationalFunctionStructure<FS> {
+    type RS = PolynomialStructure<FS>;
+
+    fn base_ring_structure(&self) -> Rc<Self::RS> {
+        self.poly_ring.clone()
+    }
+
+    fn from_base_ring(&self, elem: <Self::RS as Structure>::Set) -> Self::Set {
+        RationalFunction {
+            numerator: elem,
+            denominator: self.poly_ring.one(),
+        }
+    }
+
+    fn numerator(&self, elem: &Self::Set) -> <Self::RS as Structure>::Set {
+        elem.numerator.clone()
+    }
+
+    fn denominator(&self, elem: &Self::Set) -> <Self::RS as Structure>::Set {
+        elem.denominator.clone()
+    }
+}
+
+impl<F: MetaType> MetaType for RationalFunction<F>
+where
+    F::Structure: FieldStructure,
+{
+    type Structure = RationalFunctionStructure<F::Structure>;
+
+    fn structure() -> Rc<Self::Structure> {
+        RationalFunctionStructure::new(F::structure()).into()
+    }
+}
+
+impl<F: MetaType> Display for RationalFunction<F>
+where
+    F::Structure: FieldStructure + ToStringStructure,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Self::structure().to_string(self))
+    }
+}
+
+impl<F: MetaType> PartialEq for RationalFunction<F>
+where
+    F::Structure: FieldStructure,
+{
+    fn eq(&self, other: &Self) -> bool {
+        Self::structure().equal(self, other)
+    }
+}
+
+impl<F: MetaType> Eq for RationalFunction<F> where F::Structure: FieldStructure {}
+
+impl<F: MetaType> RationalFunction<F>
+where
+    F::Structure: FieldStructure,
+{
+    pub fn from_fraction(n: Polynomial<F>, d: Polynomial<F>) -> Option<Self> {
+        Self::structure().from_fraction(n, d)
+    }
+
+    pub fn var() -> Self {
+        Self::structure().var()
+    }
+
+    pub fn evaluate(&self, x: &F) -> Option<F> {
+        Self::structure().evaluate(self, x)
+    }
+
+    pub fn derivative(&self) -> Self {
+        Self::structure().derivative(self)
+    }
+
+    pub fn from_partial_fractions(pf: &PartialFractions<F>) -> Self {
+        Self::structure().from_partial_fractions(pf)
+    }
+
+    pub fn pade_approximant(f: &Polynomial<F>, m: usize, n: usize) -> Option<Self> {
+        Self::structure().pade_approximant(f, m, n)
+    }
+}
+
+impl<F: MetaType> RationalFunction<F>
+where
+    F::Structure: FieldStructure,
+    PolynomialStructure<F::Structure>: UniqueFactorizationStructure<Set = Polynomial<F>>,
+{
+    pub fn partial_fractions(&self) -> PartialFractions<F> {
+        Self::structure().partial_fractions(self)
+    }
+}
+
+impl RationalFunction<Rational> {
+    pub fn partial_fractions_over_splitting_field(
+        &self,
+    ) -> (Rc<ANFStructure>, PartialFractions<Polynomial<Rational>>) {
+        Self::structure().partial_fractions_over_splitting_field(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+    use crate::structure::elements::*;
+
+    #[test]
+    fn test_rational_function_arithmetic() {
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+        let f = RationalFunction::from_fraction(
+            (x.pow(2) - 1).into_verbose(),
+            (2 * x - 2).into_verbose(),
+        )
+        .unwrap();
+        assert_eq!(
+            RationalFunction::numerator(&f),
+            Polynomial::from_coeffs(vec![
+                Rational::from_integers(1.into(), 2.into()),
+                Rational::from_integers(1.into(), 2.into())
+            ])
+        );
+        assert_eq!(RationalFunction::denominator(&f), Polynomial::one());
+        let g = RationalFunction::from_fraction(Polynomial::one(), (x.pow(2) + 1).into_verbose())
+            .unwrap();
+        let h = RationalFunction::add(&f, &g);
+        assert_eq!(RationalFunction::add(&h, &g.clone().neg()), f);
+        assert_eq!(
+            RationalFunction::mul(&g, &g.inv().unwrap()),
+            RationalFunction::one()
+        );
+        assert!(RationalFunction::<Rational>::zero().inv().is_err());
+        assert!(
+            RationalFunction::<Rational>::from_fraction(Polynomial::one(), Polynomial::zero())
+                .is_none()
+        );
+
+        assert_eq!(
+            g.evaluate(&Rational::from(2)),
+            Some(Rational::from_integers(1.into(), 5.into()))
+        );
+        let k = RationalFunction::from_fraction(Polynomial::one(), (x - 3).into_verbose()).unwrap();
+        assert_eq!(k.evaluate(&Rational::from(3)), None);
+        //d/dx 1/(x - 3) = -1/(x - 3)^2
+        assert_eq!(
+            k.derivative(),
+            RationalFunction::from_fraction(
+                Polynomial::from_coeffs(vec![-1]),
+                ((x - 3) * (x - 3)).into_verbose()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_partial_fractions() {
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+        let f = RationalFunction::from_fraction(
+            (x.pow(6) + 3 * x.pow(3) - x + 2).into_verbose(),
+            (3 * (x - 1).pow(2) * (x.pow(2) + 1) * (x + 2)).into_verbose(),
+        )
+        .unwrap();
+        let pf = f.partial_fractions();
+        assert_eq!(pf.polynomial_part.degree(), Some(1));
+        assert_eq!(pf.terms.len(), 4);
+        for (n, q, _k) in &pf.terms {
+            assert!(n.degree() < q.degree());
+        }
+        assert_eq!(RationalFunction::from_partial_fractions(&pf), f);
+
+        let y = &Polynomial::<Modulo<5>>::var().into_ergonomic();
+        let f = RationalFunction::from_fraction(
+            (y.pow(3) + 2).into_verbose(),
+            (y.pow(2) * (y.pow(2) + 2) * (y + 1).pow(3)).into_verbose(),
+        )
+        .unwrap();
+        let pf = f.partial_fractions();
+        assert_eq!(pf.polynomial_part, Polynomial::zero());
+        assert_eq!(RationalFunction::from_partial_fractions(&pf), f);
+    }
+
+    #[test]
+    fn test_partial_fractions_over_splitting_field() {
+        let x = &Polynomial::<Rational>::var().into_ergonomic();
+        let f = RationalFunction::from_fraction(
+            (x.pow(3) + 1).into_verbose(),
+            ((x.pow(2) - 2) * (x - 1).pow(2)).into_verbose(),
+        )
+        .unwrap();
+        let (anf, pf) = f.partial_fractions_over_splitting_field();
+        assert_eq!(pf.terms.len(), 4);
+        for (n, q, _k) in &pf.terms {
+            assert_eq!(q.degree(), Some(1));
+            assert_eq!(n.degree(), Some(0));
+        }
+        let field = RationalFunctionStructure::new(anf.clone());
+        let embedded = field
+            .from_fraction(
+                f.numerator().apply_map(|c| Polynomial::constant(c.clone())),
+                f.denominator()
+                    .apply_map(|c| Polynomial::constant(c.clone())),
+            )
+            .unwrap();
+        assert!(field.equal(&field.from_partial_fractions(&pf), &embedded));
+    }
+
+    #[test]
+    fn test_pade_approximant() {
+        //the [2/2] approximant to exp is (1 + x/2 + x^2/12) / (1 - x/2 + x^2/12)
+        let exp = Polynomial::<Rational>::from_coeffs(
+            [1, 1, 2, 6, 24, 120]
+                .into_iter()
+                .map(|n| Rational::from_integers(1.into(), n.into()))
+                .collect(),
+        );
+        let r = |n: i64, d: i64| Rational::from_integers(n.into(), d.into());
+        assert_eq!(
+            RationalFunction::pade_approximant(&exp, 2, 2).unwrap(),
+            RationalFunction::from_fraction(
+                Polynomial::from_coeffs(vec![r(1, 1), r(1, 2), r(1, 12)]),
+                Polynomial::from_coeffs(vec![r(1, 1), r(-1, 2), r(1, 12)])
+            )
+            .unwrap()
+        );
+        //the series of a rational function is recovered exactly
+        let geometric = Polynomial::<Rational>::from_coeffs(vec![1, 1, 1, 1, 1]);
+        assert_eq!(
+            RationalFunction::pade_approximant(&geometric, 1, 2).unwrap(),
+            RationalFunction::from_fraction(
+                Polynomial::one(),
+                Polynomial::from_coeffs(vec![1, -1])
+            )
+            .unwrap()
+        );
+        //there is no [0/1] approximant to x
+        assert!(RationalFunction::pade_approximant(&Polynomial::<Rational>::var(), 0, 1).is_none());
+    }
+}
@@ -0,0 +1,441 @@
+use std::rc::Rc;
+
+use malachite_nz::{integer::Integer, natural::Natural};
+use malachite_q::Rational;
+
+use super::polynomial::*;
+use super::rational_function::*;
+use crate::linear::matrix::*;
+use crate::linear::subspace::*;
+use crate::number::algebraic::complex::ComplexAlgebraic;
+use crate::structure::quotient::*;
+use crate::structure::structure::*;
+use algebraeon_sets::structure::*;
+
+/// A sequence over a field satisfying a linear recurrence a_(n+d) = c_1 a_(n+d-1) + c_2 a_(n+d-2) + ... + c_d a_n of order d,
+/// given by the coefficients c_1, ..., c_d and the initial terms a_0, ..., a_(d-1).
+#[derive(Debug, Clone)]
+pub struct LinearRecurrence<FS: FieldStructure> {
+    field: Rc<FS>,
+    coeffs: Vec<FS::Set>,
+    initial: Vec<FS::Set>,
+}
+
+impl<FS: FieldStructure> LinearRecurrence<FS> {
+    pub fn new(
+        field: Rc<FS>,
+        coeffs: Vec<FS::Set>,
+        initial: Vec<FS::Set>,
+    ) -> Result<Self, &'static str> {
+        if coeffs.len() != initial.len() {
+            return Err("the number of initial terms must equal the order of the recurrence");
+        }
+        Ok(Self {
+            field,
+            coeffs,
+            initial,
+        })
+    }
+
+    /// The shortest linear recurrence generating the given terms, by the Berlekamp–Massey algorithm.
+    /// It is the minimal recurrence of any sequence beginning with these terms whose minimal recurrence has order at most half the number of terms.
+    pub fn berlekamp_massey(field: Rc<FS>, terms: &[FS::Set]) -> Self {
+        let poly_ring = PolynomialStructure::new(field.clone());
+        //the connection polynomial 1 - c_1 x - ... - c_l x^l of the current recurrence, and the one before the last change of length
+        let mut c = poly_ring.one();
+        let mut b = poly_ring.one();
+        let mut l = 0;
+        let mut m = 1;
+        let mut b_discrepancy = field.one();
+        for n in 0..terms.len() {
+            let discrepancy = field.sum(
+                (0..=l)
+                    .map(|i| field.mul(poly_ring.coeff(&c, i), &terms[n - i]))
+                    .collect(),
+            );
+            if field.is_zero(&discrepancy) {
+                m += 1;
+                continue;
+            }
+            let scale = field.div(&discrepancy, &b_discrepancy).unwrap();
+            let next = poly_ring.add(
+                &c,
+                &poly_ring.neg(&poly_ring.mul_var_pow(&poly_ring.mul_scalar(&b, &scale), m)),
+            );
+            if 2 * l <= n {
+                b = c;
+                l = n + 1 - l;
+                b_discrepancy = discrepancy;
+                m = 1;
+            } else {
+                m += 1;
+            }
+            c = next;
+        }
+        Self {
+            coeffs: (1..=l).map(|i| field.neg(poly_ring.coeff(&c, i))).collect(),
+            initial: terms[..l].to_vec(),
+            field,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    pub fn coeffs(&self) -> &Vec<FS::Set> {
+        &self.coeffs
+    }
+
+    pub fn initial_terms(&self) -> &Vec<FS::Set> {
+        &self.initial
+    }
+
+    /// The polynomial x^d - c_1 x^(d-1) - ... - c_d.
+    pub fn characteristic_polynomial(&self) -> Polynomial<FS::Set> {
+        let d = self.order();
+        let mut coeffs = vec![self.field.one()];
+        coeffs.extend(self.coeffs.iter().map(|c| self.field.neg(c)));
+        coeffs.reverse();
+        debug_assert_eq!(coeffs.len(), d + 1);
+        Polynomial::from_coeffs(coeffs)
+    }
+
+    /// The polynomial 1 - c_1 x - ... - c_d x^d, the reverse of the characteristic polynomial.
+    pub fn connection_polynomial(&self) -> Polynomial<FS::Set> {
+        let mut coeffs = vec![self.field.one()];
+        coeffs.extend(self.coeffs.iter().map(|c| self.field.neg(c)));
+        PolynomialStructure::new(self.field.clone()).reduce_poly(Polynomial::from_coeffs(coeffs))
+    }
+
+    /// The first n terms of the sequence.
+    pub fn terms(&self, n: usize) -> Vec<FS::Set> {
+        let d = self.order();
+        let mut terms = self.initial.clone();
+        while terms.len() < n {
+            let k = terms.len();
+            terms.push(
+                self.field.sum(
+                    (0..d)
+                        .map(|i| self.field.mul(&self.coeffs[i], &terms[k - 1 - i]))
+                        .collect(),
+                ),
+            );
+        }
+        terms.truncate(n);
+        terms
+    }
+
+    //a_n = sum r_i a_i where x^n = sum r_i x^i modulo the characteristic polynomial
+    fn combine_initial(&self, r: &Polynomial<FS::Set>) -> FS::Set {
+        let poly_ring = PolynomialStructure::new(self.field.clone());
+        self.field.sum(
+            self.initial
+                .iter()
+                .enumerate()
+                .map(|(i, a)| self.field.mul(poly_ring.coeff(r, i), a))
+                .collect(),
+        )
+    }
+
+    /// The term a_n, from x^n modulo the characteristic polynomial by repeated squaring.
+    pub fn nth_term(&self, n: &Natural) -> FS::Set {
+        if self.order() == 0 {
+            return self.field.zero();
+        }
+        let poly_ring = Rc::new(PolynomialStructure::new(self.field.clone()));
+        let quotient =
+            QuotientStructure::new_ring(poly_ring.clone(), self.characteristic_polynomial());
+        let x_pow = quotient.nat_pow(&quotient.reduce(&poly_ring.var()), n);
+        self.combine_initial(&x_pow)
+    }
+
+    /// The term a_n, from the nth power of the companion matrix of the recurrence.
+    pub fn nth_term_by_matrix(&self, n: &Natural) -> FS::Set {
+        let d = self.order();
+        if d == 0 {
+            return self.field.zero();
+        }
+        //the companion matrix maps (a_k, ..., a_(k+d-1)) to (a_(k+1), ..., a_(k+d))
+        let companion = Matrix::construct(d, d, |r, c| {
+            if r + 1 < d {
+                if c == r + 1 {
+                    self.field.one()
+                } else {
+                    self.field.zero()
+                }
+            } else {
+                self.coeffs[d - 1 - c].clone()
+            }
+        });
+        let power = MatrixStructure::new(self.field.clone()).nat_pow(&companion, n);
+        self.field.sum(
+            (0..d)
+                .map(|c| self.field.mul(power.at(0, c).unwrap(), &self.initial[c]))
+                .collect(),
+        )
+    }
+}
+
+/// A closed form a_n = sum of p(n) λ^n over the distinct nonzero roots λ of a characteristic polynomial, where the degree of p is less than the multiplicity of λ.
+/// It holds for n >= valid_from.
+#[derive(Debug, Clone)]
+pub struct ClosedForm {
+    pub terms: Vec<(ComplexAlgebraic, Polynomial<ComplexAlgebraic>)>,
+    pub valid_from: usize,
+}
+
+impl ClosedForm {
+    pub fn evaluate(&self, n: usize) -> ComplexAlgebraic {
+        let n_value = ComplexAlgebraic::from_int(&Integer::from(n));
+        ComplexAlgebraic::sum(
+            self.terms
+                .iter()
+                .map(|(lambda, p)| {
+                    ComplexAlgebraic::mul(
+                        &p.evaluate(&n_value),
+                        &ComplexAlgebraic::nat_pow(lambda, &n.into()),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl LinearRecurrence<CannonicalStructure<Rational>> {
+    /// The closed form of the sequence in terms of the complex roots of the characteristic polynomial.
+    pub fn closed_form(&self) -> ClosedForm {
+        let d = self.order();
+        //the generating function is n(x) / c(x) where c is the connection polynomial
+        let c = self.connection_polynomial();
+        let poly_ring = PolynomialStructure::new(Rational::structure());
+        let n = poly_ring.truncate(
+            &poly_ring.mul(&c, &Polynomial::from_coeffs(self.initial.clone())),
+            d,
+        );
+        let (anf, pf) = RationalFunction::from_fraction(n, c)
+            .unwrap()
+            .partial_fractions_over_splitting_field();
+        //b / (x - a)^k = b (-λ)^k sum binom(j + k - 1, k - 1) λ^j x^j where λ = 1/a
+        let anf_poly_ring = PolynomialStructure::new(anf.clone());
+        let mut terms: Vec<(Polynomial<Rational>, Polynomial<Polynomial<Rational>>)> = vec![];
+        for (b, factor, k) in pf.terms {
+            let lambda = anf.inv(&anf.neg(anf_poly_ring.coeff(&factor, 0))).unwrap();
+            let mut binomial = anf_poly_ring.one();
+            for i in 1..k {
+                let n_plus_i = Polynomial::from_coeffs(vec![
+                    Polynomial::constant(Rational::from(i)),
+                    anf.one(),
+                ]);
+                binomial = anf_poly_ring.mul_scalar(
+                    &anf_poly_ring.mul(&binomial, &n_plus_i),
+                    &Polynomial::constant(Rational::from_integers(
+                        Integer::from(1),
+                        Integer::from(i),
+                    )),
+                );
+            }
+            let scale = anf.mul(
+                anf_poly_ring.coeff(&b, 0),
+                &anf.nat_pow(&anf.neg(&lambda), &k.into()),
+            );
+            let p = anf_poly_ring.mul_scalar(&binomial, &scale);
+            match terms.iter_mut().find(|(mu, _q)| anf.equal(mu, &lambda)) {
+                Some((_mu, q)) => *q = anf_poly_ring.add(q, &p),
+                None => terms.push((lambda, p)),
+            }
+        }
+        let valid_from = match pf.polynomial_part.degree() {
+            Some(deg) => deg + 1,
+            None => 0,
+        };
+        let embedding = anf.all_complex_embeddings().into_iter().next().unwrap();
+        ClosedForm {
+            terms: terms
+                .into_iter()
+                .map(|(lambda, p)| {
+                    (
+                        embedding.embed(&lambda),
+                        p.apply_map(|a| embedding.embed(a)),
+                    )
+                })
+                .collect(),
+            valid_from,
+        }
+    }
+}
+
+/// Polynomials p_0, ..., p_r of degree at most d, not all zero, such that p_0(n) a_n + p_1(n) a_(n+1) + ... + p_r(n) a_(n+r) = 0 for every n for which the terms are given.
+/// None if there are no such polynomials, or if there are too few terms for the linear system determining them to be overdetermined.
+pub fn guess_p_recursive<FS: FieldStructure>(
+    field: Rc<FS>,
+    terms: &[FS::Set],
+    order: usize,
+    degree: usize,
+) -> Option<Vec<Polynomial<FS::Set>>> {
+    let unknowns = (order + 1) * (degree + 1);
+    if terms.len() < order + unknowns + 1 {
+        return None;
+    }
+    let equations = terms.len() - order;
+    //the column for the coefficient of n^j in p_i is n^j a_(n+i)
+    let system = Matrix::construct(equations, unknowns, |n, col| {
+        let (i, j) = (col / (degree + 1), col % (degree + 1));
+        field.mul(
+            &field.nat_pow(&field.from_int(&Integer::from(n)), &j.into()),
+            &terms[n + i],
+        )
+    });
+    let kernel = MatrixStructure::new(field.clone()).col_kernel(system);
+    let solution = LinearLatticeStructure::new(field.clone()).take_nonzero_point(&kernel)?;
+    let poly_ring = PolynomialStructure::new(field.clone());
+    Some(
+        (0..=order)
+            .map(|i| {
+                poly_ring.reduce_poly(Polynomial::from_coeffs(
+                    (0..=degree)
+                        .map(|j| solution.at(i * (degree + 1) + j, 0).unwrap().clone())
+                        .collect(),
+                ))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number::finite_fields::modulo::Modulo;
+
+    fn rats(terms: Vec<i64>) -> Vec<Rational> {
+        terms.into_iter().map(Rational::from).collect()
+    }
+
+    #[test]
+    fn test_berlekamp_massey() {
+        let fib = rats(vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        let rec = LinearRecurrence::berlekamp_massey(Rational::structure(), &fib);
+        assert_eq!(rec.coeffs(), &rats(vec![1, 1]));
+        assert_eq!(rec.initial_terms(), &rats(vec![0, 1]));
+        assert_eq!(rec.terms(10), fib);
+        assert_eq!(
+            rec.characteristic_polynomial(),
+            Polynomial::from_coeffs(rats(vec![-1, -1, 1]))
+        );
+
+        //a_n = n^2 has minimal polynomial (x - 1)^3
+        let squares = (0..8).map(|n| Rational::from(n * n)).collect::<Vec<_>>();
+        let rec = LinearRecurrence::berlekamp_massey(Rational::structure(), &squares);
+        assert_eq!(rec.coeffs(), &rats(vec![3, -3, 1]));
+
+        //a sequence which is eventually zero needs a recurrence with characteristic polynomial a power of x
+        let terms = rats(vec![1, 2, 0, 0, 0, 0, 0, 0]);
+        let rec = LinearRecurrence::berlekamp_massey(Rational::structure(), &terms);
+        assert_eq!(rec.order(), 2);
+        assert_eq!(rec.terms(8), terms);
+
+        //over F_5, a_n = 2^n + 3^n
+        let terms = (0..10u32)
+            .map(|n| Modulo::<5>::from((2usize.pow(n) + 3usize.pow(n)) % 5))
+            .collect::<Vec<_>>();
+        let rec = LinearRecurrence::berlekamp_massey(Modulo::<5>::structure(), &terms);
+        assert_eq!(rec.order(), 2);
+        assert_eq!(rec.terms(10), terms);
+
+        assert!(LinearRecurrence::new(Rational::structure(), rats(vec![1]), vec![]).is_err());
+    }
+
+    #[test]
+    fn test_nth_term() {
+        let rec = LinearRecurrence::new(Rational::structure(), rats(vec![1, 1]), rats(vec![0, 1]))
+            .unwrap();
+        let fib_100 = Rational::from(Integer::from(354224848179261915075u128));
+        assert_eq!(rec.nth_term(&Natural::from(100u32)), fib_100);
+        assert_eq!(rec.nth_term_by_matrix(&Natural::from(100u32)), fib_100);
+
+        let rec = LinearRecurrence::new(
+            Rational::structure(),
+            rats(vec![2, -1, 3]),
+            rats(vec![1, -1, 4]),
+        )
+        .unwrap();
+        let terms = rec.terms(30);
+        for (n, term) in terms.iter().enumerate() {
+            assert_eq!(&rec.nth_term(&n.into()), term);
+            assert_eq!(&rec.nth_term_by_matrix(&n.into()), term);
+        }
+    }
+
+    #[test]
+    fn test_closed_form() {
+        //fibonacci numbers are (φ^n - ψ^n) / sqrt(5)
+        let rec = LinearRecurrence::new(Rational::structure(), rats(vec![1, 1]), rats(vec![0, 1]))
+            .unwrap();
+        let closed_form = rec.closed_form();
+        assert_eq!(closed_form.valid_from, 0);
+        assert_eq!(closed_form.terms.len(), 2);
+        for (lambda, p) in &closed_form.terms {
+            assert_eq!(
+                lambda.min_poly(),
+                Polynomial::from_coeffs(rats(vec![-1, -1, 1]))
+            );
+            assert_eq!(p.degree(), Some(0));
+        }
+        assert_eq!(
+            closed_form.evaluate(10),
+            ComplexAlgebraic::from_int(&Integer::from(55))
+        );
+
+        //a_n = (n + 1) 2^n, preceded by a term not given by the closed form
+        let rec = LinearRecurrence::new(
+            Rational::structure(),
+            rats(vec![4, -4, 0]),
+            rats(vec![7, 4, 12]),
+        )
+        .unwrap();
+        let closed_form = rec.closed_form();
+        assert_eq!(closed_form.valid_from, 1);
+        assert_eq!(closed_form.terms.len(), 1);
+        let terms = rec.terms(8);
+        for (n, term) in terms.iter().enumerate().skip(1) {
+            assert_eq!(
+                closed_form.evaluate(n),
+                ComplexAlgebraic::from_rat(term).unwrap()
+            );
+        }
+        assert_eq!(terms[5], Rational::from(6 * 32));
+    }
+
+    #[test]
+    fn test_guess_p_recursive() {
+        //n! satisfies (n + 1) a_n - a_(n+1) = 0
+        let mut factorials = vec![Rational::from(1)];
+        for n in 1..12 {
+            factorials.push(&factorials[n - 1] * Rational::from(n));
+        }
+        let rec = guess_p_recursive(Rational::structure(), &factorials, 1, 1).unwrap();
+        let ratio = Polynomial::div(&rec[0], &rec[1]).unwrap();
+        assert_eq!(ratio, Polynomial::from_coeffs(rats(vec![-1, -1])));
+
+        //the catalan numbers satisfy (4n + 2) c_n - (n + 2) c_(n+1) = 0
+        let catalan = rats(vec![1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796, 58786]);
+        let rec = guess_p_recursive(Rational::structure(), &catalan, 1, 1).unwrap();
+        let scale = rec[1].coeff(1);
+        assert_eq!(
+            rec.iter()
+                .map(|p| p.apply_map(|c| c / &scale))
+                .collect::<Vec<_>>(),
+            vec![
+                Polynomial::from_coeffs(rats(vec![-2, -4])),
+                Polynomial::from_coeffs(rats(vec![2, 1]))
+            ]
+        );
+
+        //2^n + n! has no recurrence of order 1, and too few terms are refused
+        let terms = (0..12)
+            .map(|n| Rational::from(1i64 << n) + &factorials[n])
+            .collect::<Vec<_>>();
+        assert!(guess_p_recursive(Rational::structure(), &terms, 1, 1).is_none());
+        assert!(guess_p_recursive(Rational::structure(), &catalan[..5], 1, 1).is_none());
+    }
+}
@@ -0,0 +1,474 @@
+use super::{multipoly::*, polynomial::*};
+use crate::{linear::matrix::*, structure::structure::*};
+use algebraeon_sets::structure::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A pseudo remainder sequence together with the scalar subresultants, as polynomials in the remaining variables.
+pub type MultiPolynomialSubresultantSequence<Set> =
+    (Vec<MultiPolynomial<Set>>, Vec<MultiPolynomial<Set>>);
+
+//all exponent vectors of length n and total degree d
+fn exponents_of_degree(n: usize, d: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        if d == 0 {
+            vec![vec![]]
+        } else {
+            vec![]
+        }
+    } else {
+        let mut result = vec![];
+        for k in (0..=d).rev() {
+            for mut rest in exponents_of_degree(n - 1, d - k) {
+                rest.insert(0, k);
+                result.push(rest);
+            }
+        }
+        result
+    }
+}
+
+impl<RS: IntegralDomainStructure> MultiPolynomialStructure<RS> {
+    fn univariate_ring(&self) -> PolynomialStructure<Self> {
+        PolynomialStructure::new(Rc::new(self.clone()))
+    }
+
+    fn collapse(
+        &self,
+        p: &Polynomial<MultiPolynomial<RS::Set>>,
+        v: &Variable,
+    ) -> MultiPolynomial<RS::Set> {
+        self.univariate_ring().evaluate(p, &self.var(v.clone()))
+    }
+
+    /// The resultant of f and g viewed as polynomials in v, which is zero if and only if they have a common factor of positive degree in v or both leading coefficients vanish.
+    pub fn resultant(
+        &self,
+        f: &MultiPolynomial<RS::Set>,
+        g: &MultiPolynomial<RS::Set>,
+        v: &Variable,
+    ) -> MultiPolynomial<RS::Set> {
+        self.univariate_ring()
+            .resultant(self.expand(f, v), self.expand(g, v))
+    }
+
+    /// The discriminant of f viewed as a polynomial in v.
+    pub fn discriminant(
+        &self,
+        f: &MultiPolynomial<RS::Set>,
+        v: &Variable,
+    ) -> Result<MultiPolynomial<RS::Set>, &'static str> {
+        self.univariate_ring().discriminant(self.expand(f, v))
+    }
+
+    /// The pseudo remainder sequence and scalar subresultants of f and g viewed as polynomials in v.
+    pub fn pseudo_remainder_subresultant_sequence(
+        &self,
+        f: &MultiPolynomial<RS::Set>,
+        g: &MultiPolynomial<RS::Set>,
+        v: &Variable,
+    ) -> MultiPolynomialSubresultantSequence<RS::Set> {
+        let (prs, ssres) = self
+            .univariate_ring()
+            .pseudo_remainder_subresultant_sequence(self.expand(f, v), self.expand(g, v));
+        (prs.iter().map(|p| self.collapse(p, v)).collect(), ssres)
+    }
+
+    /// The subresultants S_0, ..., S_(k-1) of f and g viewed as polynomials in v, where k is the smaller of their degrees.
+    /// S_j has degree at most j in v and is given by determinants of submatrices of the Sylvester matrix, with S_0 the resultant.
+    /// The coefficient of v^j in S_j is the j-th principal subresultant coefficient and the least j for which it is nonzero is the degree of gcd(f, g).
+    pub fn subresultants(
+        &self,
+        f: &MultiPolynomial<RS::Set>,
+        g: &MultiPolynomial<RS::Set>,
+        v: &Variable,
+    ) -> Vec<MultiPolynomial<RS::Set>> {
+        let poly_ring = self.univariate_ring();
+        let f = self.expand(f, v);
+        let g = self.expand(g, v);
+        let (m, n) = match (poly_ring.degree(&f), poly_ring.degree(&g)) {
+            (Some(m), Some(n)) => (m, n),
+            _ => return vec![],
+        };
+        let f = f.coeffs();
+        let g = g.coeffs();
+        let mat_structure = MatrixStructure::new(Rc::new(self.clone()));
+        (0..std::cmp::min(m, n))
+            .map(|j| {
+                //rows are v^s f for s < n - j and v^s g for s < m - j, restricted to the columns v^(m+n-j-1), ..., v^(j+1) and then v^i
+                let row =
+                    |p: &Vec<&MultiPolynomial<RS::Set>>, deg: usize, shift: usize, i: usize| {
+                        (0..m + n - 2 * j - 1)
+                            .map(|c| m + n - j - 1 - c)
+                            .chain(std::iter::once(i))
+                            .map(|power| {
+                                if shift <= power && power <= deg + shift {
+                                    p[power - shift].clone()
+                                } else {
+                                    self.zero()
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    };
+                let coeffs = (0..=j)
+                    .map(|i| {
+                        let mut rows = vec![];
+                        for shift in (0..n - j).rev() {
+                            rows.push(row(&f, m, shift, i));
+                        }
+                        for shift in (0..m - j).rev() {
+                            rows.push(row(&g, n, shift, i));
+                        }
+                        mat_structure.det_bareiss(Matrix::from_rows(rows)).unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                self.collapse(&Polynomial::from_coeffs(coeffs), v)
+            })
+            .collect()
+    }
+
+    /// The principal subresultant coefficients psc_0, ..., psc_(k-1) of f and g viewed as polynomials in v, where k is the smaller of their degrees.
+    pub fn principal_subresultant_coefficients(
+        &self,
+        f: &MultiPolynomial<RS::Set>,
+        g: &MultiPolynomial<RS::Set>,
+        v: &Variable,
+    ) -> Vec<MultiPolynomial<RS::Set>> {
+        self.subresultants(f, g, v)
+            .into_iter()
+            .enumerate()
+            .map(|(j, s)| self.univariate_ring().coeff(&self.expand(&s, v), j).clone())
+            .collect()
+    }
+
+    //the coefficients of the monomials in vars, which may involve the other variables
+    fn coefficients_in(
+        &self,
+        p: &MultiPolynomial<RS::Set>,
+        vars: &[Variable],
+    ) -> HashMap<Vec<usize>, MultiPolynomial<RS::Set>> {
+        match vars.split_first() {
+            None => {
+                let mut coeffs = HashMap::new();
+                if !self.is_zero(p) {
+                    coeffs.insert(vec![], p.clone());
+                }
+                coeffs
+            }
+            Some((v, rest)) => {
+                let mut coeffs = HashMap::new();
+                for (k, c) in self.expand(p, v).into_coeffs().into_iter().enumerate() {
+                    for (mut e, c) in self.coefficients_in(&c, rest) {
+                        e.insert(0, k);
+                        coeffs.insert(e, c);
+                    }
+                }
+                coeffs
+            }
+        }
+    }
+
+    /// The Macaulay resultant of n homogeneous polynomials of positive degree in n variables, normalized so that the resultant of x_1^d_1, ..., x_n^d_n is 1.
+    /// It vanishes if and only if the polynomials have a common nontrivial zero over an algebraically closed field. Coefficients may involve other variables.
+    pub fn macaulay_resultant(
+        &self,
+        polys: &[MultiPolynomial<RS::Set>],
+        vars: &[Variable],
+    ) -> Result<MultiPolynomial<RS::Set>, &'static str> {
+        let n = vars.len();
+        if polys.len() != n {
+            return Err("the number of polynomials must equal the number of variables");
+        }
+        let mut coeffs = vec![];
+        let mut degrees = vec![];
+        for p in polys {
+            let c = self.coefficients_in(p, vars);
+            let mut d = None;
+            for e in c.keys() {
+                let e_deg = e.iter().sum::<usize>();
+                match d {
+                    None => d = Some(e_deg),
+                    Some(d) => {
+                        if d != e_deg {
+                            return Err("the polynomials must be homogeneous");
+                        }
+                    }
+                }
+            }
+            match d {
+                Some(d) if d > 0 => degrees.push(d),
+                _ => return Err("the polynomials must have positive degree"),
+            }
+            coeffs.push(c);
+        }
+
+        //rows and columns of the Macaulay matrix are indexed by the monomials of degree D
+        //the row for a monomial m is m / x_i^d_i times the i-th polynomial, where i is the first index such that x_i^d_i divides m
+        let big_d = degrees.iter().map(|d| d - 1).sum::<usize>() + 1;
+        let monomials = exponents_of_degree(n, big_d);
+        let row_index = monomials
+            .iter()
+            .map(|m| (0..n).find(|i| m[*i] >= degrees[*i]).unwrap())
+            .collect::<Vec<_>>();
+        //the extraneous factor is the minor whose monomials are divisible by at least two of the x_i^d_i
+        let extraneous = (0..monomials.len())
+            .filter(|r| (0..n).filter(|i| monomials[*r][*i] >= degrees[*i]).count() >= 2)
+            .collect::<Vec<_>>();
+
+        let macaulay_matrix = |coeffs: &Vec<HashMap<Vec<usize>, MultiPolynomial<RS::Set>>>| {
+            monomials
+                .iter()
+                .zip(row_index.iter())
+                .map(|(m, i)| {
+                    monomials
+                        .iter()
+                        .map(|col| {
+                            let mut e = vec![];
+                            for k in 0..n {
+                                let shift = if k == *i { m[k] - degrees[k] } else { m[k] };
+                                if col[k] < shift {
+                                    return self.zero();
+                                }
+                                e.push(col[k] - shift);
+                            }
+                            coeffs[*i].get(&e).cloned().unwrap_or(self.zero())
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let mat_structure = MatrixStructure::new(Rc::new(self.clone()));
+        let minor = |rows: &Vec<Vec<MultiPolynomial<RS::Set>>>| {
+            if extraneous.is_empty() {
+                return self.one();
+            }
+            mat_structure
+                .det_bareiss(Matrix::from_rows(
+                    extraneous
+                        .iter()
+                        .map(|r| extraneous.iter().map(|c| rows[*r][*c].clone()).collect())
+                        .collect::<Vec<Vec<_>>>(),
+                ))
+                .unwrap()
+        };
+
+        let rows = macaulay_matrix(&coeffs);
+        let extraneous_factor = minor(&rows);
+        if !self.is_zero(&extraneous_factor) {
+            Ok(self
+                .div(
+                    &mat_structure.det_bareiss(Matrix::from_rows(rows)).unwrap(),
+                    &extraneous_factor,
+                )
+                .unwrap())
+        } else {
+            //the extraneous factor of the perturbed polynomials f_i + t x_i^d_i has leading term t^k so is nonzero
+            //the quotient is the resultant of the perturbed polynomials and is a polynomial in t
+            let t = Variable::new("t");
+            for (i, c) in coeffs.iter_mut().enumerate() {
+                let mut e = vec![0; n];
+                e[i] = degrees[i];
+                let x = c.remove(&e).unwrap_or(self.zero());
+                c.insert(e, self.add(&x, &self.var(t.clone())));
+            }
+            let rows = macaulay_matrix(&coeffs);
+            let extraneous_factor = minor(&rows);
+            Ok(self
+                .div(
+                    &mat_structure.det_bareiss(Matrix::from_rows(rows)).unwrap(),
+                    &extraneous_factor,
+                )
+                .unwrap()
+                .evaluate_var_zero(&t))
+        }
+    }
+}
+
+impl<R: MetaType> MultiPolynomial<R>
+where
+    R::Structure: IntegralDomainStructure,
+{
+    pub fn resultant(f: &Self, g: &Self, v: &Variable) -> Self {
+        Self::structure().resultant(f, g, v)
+    }
+
+    pub fn discriminant(&self, v: &Variable) -> Result<Self, &'static str> {
+        Self::structure().discriminant(self, v)
+    }
+
+    pub fn subresultants(f: &Self, g: &Self, v: &Variable) -> Vec<Self> {
+        Self::structure().subresultants(f, g, v)
+    }
+
+    pub fn principal_subresultant_coefficients(f: &Self, g: &Self, v: &Variable) -> Vec<Self> {
+        Self::structure().principal_subresultant_coefficients(f, g, v)
+    }
+
+    pub fn macaulay_resultant(polys: &[Self], vars: &[Variable]) -> Result<Self, &'static str> {
+        Self::structure().macaulay_resultant(polys, vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::elements::IntoErgonomic;
+    use malachite_nz::integer::Integer;
+
+    #[test]
+    fn test_resultant_and_discriminant() {
+        let x_var = Variable::new("x");
+        let y_var = Variable::new("y");
+        let x = &MultiPolynomial::<Integer>::var(x_var.clone()).into_ergonomic();
+        let y = &MultiPolynomial::<Integer>::var(y_var.clone()).into_ergonomic();
+
+        let f = (x.pow(2) + y.pow(2) - 1).into_verbose();
+        let g = (x - y).into_verbose();
+        assert_eq!(
+            MultiPolynomial::resultant(&f, &g, &x_var),
+            (2 * y.pow(2) - 1).into_verbose()
+        );
+
+        //the resultant is the determinant of the Sylvester matrix, also when the first polynomial has smaller degree
+        for (f, g) in [
+            (x.clone(), x.pow(3) + y),
+            (x.pow(3) + y, x.clone()),
+            (x.pow(2) + x * y + 1, 3 * x.pow(3) - y * x + 2),
+            (y * x - 1, x.pow(2) - y),
+        ] {
+            let f = f.into_verbose();
+            let g = g.into_verbose();
+            assert_eq!(
+                MultiPolynomial::resultant(&f, &g, &x_var),
+                MultiPolynomial::subresultants(&f, &g, &x_var)[0]
+            );
+        }
+        assert_eq!(
+            MultiPolynomial::resultant(
+                &x.clone().into_verbose(),
+                &(x.pow(3) + y).into_verbose(),
+                &x_var
+            ),
+            y.clone().into_verbose()
+        );
+
+        let b = y;
+        let f = (x.pow(2) + b * x + 1).into_verbose();
+        assert_eq!(
+            f.discriminant(&x_var).unwrap(),
+            (b.pow(2) - 4).into_verbose()
+        );
+        //with respect to y it is linear
+        assert_eq!(
+            f.discriminant(&y_var).unwrap(),
+            MultiPolynomial::constant(Integer::from(1))
+        );
+    }
+
+    #[test]
+    fn test_subresultants() {
+        let x_var = Variable::new("x");
+        let y_var = Variable::new("y");
+        let x = &MultiPolynomial::<Integer>::var(x_var.clone()).into_ergonomic();
+        let y = &MultiPolynomial::<Integer>::var(y_var.clone()).into_ergonomic();
+
+        //gcd x - y
+        let f = ((x - y) * (x.pow(2) + 1)).into_verbose();
+        let g = ((x - y) * (x + 2)).into_verbose();
+        let s = MultiPolynomial::subresultants(&f, &g, &x_var);
+        assert_eq!(s.len(), 2);
+        assert_eq!(s[0], MultiPolynomial::constant(Integer::from(0)));
+        let psc = MultiPolynomial::principal_subresultant_coefficients(&f, &g, &x_var);
+        assert_eq!(psc[0], MultiPolynomial::constant(Integer::from(0)));
+        assert_ne!(psc[1], MultiPolynomial::constant(Integer::from(0)));
+        //S_1 is a multiple of the gcd
+        let ring = MultiPolynomial::<Integer>::structure();
+        assert_eq!(ring.div(&s[1], &(x - y).into_verbose()).unwrap(), psc[1]);
+
+        let (prs, _) = MultiPolynomial::<Integer>::structure()
+            .pseudo_remainder_subresultant_sequence(&f, &g, &x_var);
+        assert!(ring
+            .div(prs.last().unwrap(), &(x - y).into_verbose())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_macaulay_resultant() {
+        let x_var = Variable::new("x");
+        let y_var = Variable::new("y");
+        let z_var = Variable::new("z");
+        let vars = vec![x_var.clone(), y_var.clone(), z_var.clone()];
+        let x = &MultiPolynomial::<Integer>::var(x_var.clone()).into_ergonomic();
+        let y = &MultiPolynomial::<Integer>::var(y_var.clone()).into_ergonomic();
+        let z = &MultiPolynomial::<Integer>::var(z_var.clone()).into_ergonomic();
+
+        //linear forms give the determinant
+        let polys = vec![
+            y.clone().into_verbose(),
+            x.clone().into_verbose(),
+            z.clone().into_verbose(),
+        ];
+        assert_eq!(
+            MultiPolynomial::macaulay_resultant(&polys, &vars).unwrap(),
+            MultiPolynomial::constant(Integer::from(-1))
+        );
+        let polys = vec![
+            (x + 2 * y + 3 * z).into_verbose(),
+            (4 * x + 5 * y + 6 * z).into_verbose(),
+            (7 * x + 8 * y + 10 * z).into_verbose(),
+        ];
+        assert_eq!(
+            MultiPolynomial::macaulay_resultant(&polys, &vars).unwrap(),
+            MultiPolynomial::constant(Integer::from(-3))
+        );
+
+        //the extraneous factor vanishes here so the perturbation is needed
+        let polys = vec![
+            y.pow(2).into_verbose(),
+            x.pow(2).into_verbose(),
+            z.pow(2).into_verbose(),
+        ];
+        assert_eq!(
+            MultiPolynomial::macaulay_resultant(&polys, &vars).unwrap(),
+            MultiPolynomial::constant(Integer::from(1))
+        );
+
+        //a common zero at (1, 1, 1)
+        let polys = vec![
+            (x.pow(2) - y * z).into_verbose(),
+            (y.pow(2) - x * z).into_verbose(),
+            (x * y - z.pow(2)).into_verbose(),
+        ];
+        assert_eq!(
+            MultiPolynomial::macaulay_resultant(&polys, &vars).unwrap(),
+            MultiPolynomial::constant(Integer::from(0))
+        );
+
+        //two binary forms agree with the Sylvester resultant of their dehomogenizations
+        let a_var = Variable::new("a");
+        let a = &MultiPolynomial::<Integer>::var(a_var.clone()).into_ergonomic();
+        let f = (x.pow(2) + a * y.pow(2)).into_verbose();
+        let g = (x.pow(3) + x.pow(2) * y - 2 * y.pow(3)).into_verbose();
+        let res = MultiPolynomial::macaulay_resultant(
+            &[f.clone(), g.clone()],
+            &[x_var.clone(), y_var.clone()],
+        )
+        .unwrap();
+        let ring = MultiPolynomial::<Integer>::structure();
+        let one = MultiPolynomial::constant(Integer::from(1));
+        let dehomogenize = |p: &MultiPolynomial<Integer>| {
+            ring.univariate_ring()
+                .evaluate(&ring.expand(p, &y_var), &one)
+        };
+        assert_eq!(
+            res,
+            MultiPolynomial::resultant(&dehomogenize(&f), &dehomogenize(&g), &x_var)
+        );
+
+        assert!(MultiPolynomial::macaulay_resultant(std::slice::from_ref(&f), &vars).is_err());
+        assert!(
+            MultiPolynomial::macaulay_resultant(&[f, (x + 1).into_verbose()], &[x_var, y_var])
+                .is_err()
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::rc::Rc;
 use algebraeon_sets::structure::*;
 
 use super::structure::*;
+use crate::polynomial::multiplication::karatsuba_mul;
 
 #[derive(Debug, Clone)]
 pub struct QuotientStructure<RS: EuclideanDivisionStructure, const IS_FIELD: bool> {
@@ -120,6 +121,10 @@ impl<RS: EuclideanDivisionStructure, const IS_FIELD: bool> SemiRingStructure
     fn mul(&self, a: &Self::Set, b: &Self::Set) -> Self::Set {
         self.ring.rem(&self.ring.mul(a, b), &self.modulus)
     }
+
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        karatsuba_mul(self, a, b)
+    }
 }
 
 impl<RS: EuclideanDivisionStructure, const IS_FIELD: bool> RingStructure
@@ -6,6 +6,7 @@ use malachite_nz::{integer::Integer, natural::Natural};
 use malachite_q::Rational;
 
 use crate::number::natural::nat_to_usize;
+use crate::polynomial::multiplication::schoolbook_mul;
 use crate::polynomial::polynomial::Polynomial;
 use crate::polynomial::polynomial::PolynomialStructure;
 
@@ -49,6 +50,11 @@ pub trait SemiRingStructure: EqStructure {
         }
         prod
     }
+    /// The coefficients of the product of the polynomials with coefficients a and b.
+    /// Override to use a faster algorithm than the schoolbook one.
+    fn polynomial_mul(&self, a: &[Self::Set], b: &[Self::Set]) -> Vec<Self::Set> {
+        schoolbook_mul(self, a, b)
+    }
 
     fn nat_pow(&self, a: &Self::Set, n: &Natural) -> Self::Set {
         if *n == 0 {